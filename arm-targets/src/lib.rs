@@ -10,6 +10,15 @@ pub struct TargetInfo {
 }
 
 impl TargetInfo {
+    /// Decode a target string, without emitting any cargo configuration
+    pub fn get(target: &str) -> TargetInfo {
+        TargetInfo {
+            profile: Profile::get(target),
+            arch: Arch::get(target),
+            isa: Isa::get(target),
+        }
+    }
+
     pub fn profile(&self) -> Option<Profile> {
         self.profile
     }
@@ -21,7 +30,38 @@ impl TargetInfo {
     pub fn isa(&self) -> Option<Isa> {
         self.isa
     }
+
+    /// Emit cargo configuration for this target to standard out.
+    ///
+    /// This sets the `arm_isa`, `arm_architecture` and `arm_profile` cfgs
+    /// (where they are known), and tells cargo about every value they can take.
+    pub fn emit_cfgs(&self) {
+        if let Some(isa) = self.isa {
+            println!(r#"cargo:rustc-cfg=arm_isa="{}""#, isa);
+        }
+        println!(
+            r#"cargo:rustc-check-cfg=cfg(arm_isa, values({}))"#,
+            Isa::values()
+        );
+
+        if let Some(arch) = self.arch {
+            println!(r#"cargo:rustc-cfg=arm_architecture="{}""#, arch);
+        }
+        println!(
+            r#"cargo:rustc-check-cfg=cfg(arm_architecture, values({}))"#,
+            Arch::values()
+        );
+
+        if let Some(profile) = self.profile {
+            println!(r#"cargo:rustc-cfg=arm_profile="{}""#, profile);
+        }
+        println!(
+            r#"cargo:rustc-check-cfg=cfg(arm_profile, values({}))"#,
+            Profile::values()
+        );
+    }
 }
+
 /// Process the ${TARGET} environment variable, and emit cargo configuration to
 /// standard out.
 pub fn process() -> TargetInfo {
@@ -31,36 +71,20 @@ pub fn process() -> TargetInfo {
 
 /// Process a given target string, and emit cargo configuration to standard out.
 pub fn process_target(target: &str) -> TargetInfo {
-    let mut target_info = TargetInfo::default();
-    if let Some(isa) = Isa::get(target) {
-        println!(r#"cargo:rustc-cfg=arm_isa="{}""#, isa);
-        target_info.isa = Some(isa);
-    }
-    println!(
-        r#"cargo:rustc-check-cfg=cfg(arm_isa, values({}))"#,
-        Isa::values()
-    );
-
-    if let Some(arch) = Arch::get(target) {
-        println!(r#"cargo:rustc-cfg=arm_architecture="{}""#, arch);
-        target_info.arch = Some(arch);
-    }
-    println!(
-        r#"cargo:rustc-check-cfg=cfg(arm_architecture, values({}))"#,
-        Arch::values()
-    );
-
-    if let Some(profile) = Profile::get(target) {
-        println!(r#"cargo:rustc-cfg=arm_profile="{}""#, profile);
-        target_info.profile = Some(profile);
-    }
-    println!(
-        r#"cargo:rustc-check-cfg=cfg(arm_profile, values({}))"#,
-        Profile::values()
-    );
+    let target_info = TargetInfo::get(target);
+    target_info.emit_cfgs();
     target_info
 }
 
+/// Process the ${TARGET} environment variable, and emit cargo configuration to
+/// standard out.
+///
+/// The same as [`process`], for build scripts that don't need the
+/// [`TargetInfo`].
+pub fn emit_cfgs() {
+    process();
+}
+
 /// The Arm Instruction Set
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum Isa {
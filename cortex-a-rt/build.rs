@@ -8,7 +8,17 @@ use std::io::Write;
 
 fn main() {
     arm_targets::process();
-    write("link.x", include_bytes!("link.x"));
+    let mut link_x = include_str!("link.x").to_string();
+    if std::env::var_os("CARGO_FEATURE_HIVECS").is_some() {
+        // Put the vector table where SCTLR.V=1 expects it
+        link_x = link_x
+            .replace(".vector_table : {", ".vector_table 0xFFFF0000 : {")
+            .replace(
+                "*(.vector_table.default)\n    } > CODE",
+                "*(.vector_table.default)\n    } > VECTORS",
+            );
+    }
+    write("link.x", link_x.as_bytes());
 }
 
 fn write(file: &str, contents: &[u8]) {
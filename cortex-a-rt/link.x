@@ -2,9 +2,16 @@
 Basic Cortex-A linker script.
 
 You must supply a file called `memory.x` which defines the memory regions 'CODE' and 'DATA'.
+With the `hivecs` feature, it must also define 'VECTORS', which must cover 0xFFFF0000.
 
 The stack pointer(s) will be (near) the top of the DATA region by default.
 
+You can add your own output sections from `memory.x` (or any other script you
+pass to the linker) with `INSERT AFTER` or `INSERT BEFORE`, instead of replacing
+this whole script. These output sections are stable anchors for that:
+
+  .vector_table, .text, .rodata, .irq_dispatch, .data, .bss and .uninit
+
 Based upon the linker script from https://github.com/rust-embedded/cortex-m
 */
 
@@ -14,9 +21,13 @@ ENTRY(_vector_table);
 EXTERN(_vector_table);
 
 SECTIONS {
-    .text : {
+    .vector_table : {
         /* The vector table must come first */
         *(.vector_table)
+        *(.vector_table.default)
+    } > CODE
+
+    .text : {
         /* Now the rest of the code */
         *(.text .text*)
     } > CODE
@@ -25,6 +36,13 @@ SECTIONS {
         *(.rodata .rodata*)
     } > CODE
 
+    .irq_dispatch : ALIGN(4) {
+        /* The table of `#[interrupt]` handlers, searched by `dispatch_interrupts` */
+        __sirq_dispatch = .;
+        KEEP(*(.irq_dispatch .irq_dispatch.*));
+        __eirq_dispatch = .;
+    } > CODE
+
     .data : ALIGN(4) {
         . = ALIGN(4);
         __sdata = .;
@@ -71,11 +89,13 @@ SECTIONS {
 
 /*
 We reserve some space at the top of the RAM for our exception stacks. The
-remainder is our system mode stack.
+remainder is our system mode stack. With the `el3-boot` feature, the MON stack
+goes at the very top, above the others.
 
 You must keep _stack_top and the stack sizes aligned to eight byte boundaries.
 */
 PROVIDE(_stack_top = ORIGIN(DATA) + LENGTH(DATA));
+PROVIDE(_mon_stack_size = 0x400);
 PROVIDE(_und_stack_size = 0x400);
 PROVIDE(_svc_stack_size = 0x400);
 PROVIDE(_abt_stack_size = 0x400);
@@ -83,6 +103,7 @@ PROVIDE(_irq_stack_size = 0x400);
 PROVIDE(_fiq_stack_size = 0x400);
 
 ASSERT(_stack_top % 8 == 0, "ERROR(cortex-r-rt): top of stack is not 8-byte aligned");
+ASSERT(_mon_stack_size % 8 == 0, "ERROR(cortex-a-rt): size of MON stack is not 8-byte aligned");
 ASSERT(_und_stack_size % 8 == 0, "ERROR(cortex-r-rt): size of UND stack is not 8-byte aligned");
 ASSERT(_svc_stack_size % 8 == 0, "ERROR(cortex-r-rt): size of SVC stack is not 8-byte aligned");
 ASSERT(_abt_stack_size % 8 == 0, "ERROR(cortex-r-rt): size of ABT stack is not 8-byte aligned");
@@ -90,18 +111,34 @@ ASSERT(_irq_stack_size % 8 == 0, "ERROR(cortex-r-rt): size of IRQ stack is not 8
 ASSERT(_fiq_stack_size % 8 == 0, "ERROR(cortex-r-rt): size of FIQ stack is not 8-byte aligned");
 
 /* Weak aliases for ASM default handlers */
+PROVIDE(_vector_table               = _default_vector_table);
 PROVIDE(_start                      = _default_start);
 PROVIDE(_asm_undefined_handler      = _asm_default_undefined_handler);
 PROVIDE(_asm_svc_handler            = _asm_default_svc_handler);
 PROVIDE(_asm_prefetch_abort_handler = _asm_default_prefetch_abort_handler);
 PROVIDE(_asm_data_abort_handler     = _asm_default_data_abort_handler);
+PROVIDE(_asm_hvc_handler            = _asm_default_hvc_handler);
 PROVIDE(_asm_irq_handler            = _asm_default_irq_handler);
 PROVIDE(_asm_fiq_handler            = _asm_default_fiq_handler);
+PROVIDE(_monitor_vector_table       = _default_monitor_vector_table);
+PROVIDE(_asm_smc_handler            = _asm_default_smc_handler);
+
+/* Weak aliases for start-up hooks */
+PROVIDE(_post_stack_setup = _default_hook);
+PROVIDE(_post_bss_init    = _default_hook);
+PROVIDE(_post_data_init   = _default_hook);
+PROVIDE(_post_fpu_enable  = _default_hook);
+PROVIDE(_pre_kmain        = _default_hook);
+
+/* Weak alias for the reschedule handler */
+PROVIDE(_reschedule_handler = _default_hook);
 
 /* Weak aliases for C default handlers */
-PROVIDE(_undefined_handler      = _default_handler);
-PROVIDE(_svc_handler            = _default_handler);
-PROVIDE(_prefetch_abort_handler = _default_handler);
-PROVIDE(_data_abort_handler     = _default_handler);
-PROVIDE(_irq_handler            = _default_handler);
+PROVIDE(_undefined_handler      = _default_undefined_handler);
+PROVIDE(_svc_handler            = _default_svc_handler);
+PROVIDE(_smc_handler            = _default_smc_handler);
+PROVIDE(_prefetch_abort_handler = _default_prefetch_abort_handler);
+PROVIDE(_data_abort_handler     = _default_data_abort_handler);
+PROVIDE(_hvc_handler            = _default_hvc_handler);
+PROVIDE(_irq_handler            = _default_irq_handler);
 /* There is no default C-language FIQ handler */
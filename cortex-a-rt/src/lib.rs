@@ -16,10 +16,86 @@
 //!
 //! ## Features
 //!
+//! - `boot-info`: Makes `_default_start` collect a `BootInfo` (with the
+//!   values of r0-r2 and CPSR it was started with, the core ID and where the
+//!   stacks are) and pass a reference to it to `kmain`, so your `#[entry]`
+//!   function can take a `&BootInfo`. You can also call `boot_info()`.
+//! - `boot-ticks`: Makes `_default_start` read a counter when it starts, and
+//!   again just before it calls `kmain`. Get the readings with `boot_ticks()`.
 //! - `vfp-dp`: Enables support for the double-precision VFP floating point
 //!   support. If your target CPU has this feature or support for NEON which
 //!   also implies double-precision support, this feature should be activated.
 //! - `eabi-fpu`: Enables the FPU, even if you selected a soft-float ABI target.
+//! - `el3-boot`: For cores which reset into Secure state (e.g. an Armv8-A core
+//!   starting in AArch32 EL3) with no firmware to switch to the Non-secure
+//!   world for us. `_default_start` sets `MVBAR` to `_monitor_vector_table`,
+//!   lets the Non-secure world use the FPU, sets `SCR.NS` and then carries on
+//!   in Non-secure System mode. Our linker script PROVIDEs a default
+//!   `_monitor_vector_table` which calls `_smc_handler` for a Secure Monitor
+//!   Call, and spins on every other exception. A MON stack of
+//!   `_mon_stack_size` bytes is put at the very top of the stacks.
+//! - `exception-stats`: Makes the assembly language trampolines count each
+//!   exception, so you can read the totals with `exception_stats()`.
+//! - `handler-table`: Makes the assembly language trampolines call any handler
+//!   installed at run-time with `set_handler()`, in preference to the one
+//!   chosen at link-time.
+//! - `hivecs`: For chips whose boot ROM expects high vectors. Links the vector
+//!   table at 0xFFFF0000, in a memory region called `VECTORS` which your
+//!   `memory.x` must then define, and makes `_default_start` set `SCTLR.V`.
+//! - `reschedule`: Makes the IRQ trampoline call `_reschedule_handler` just
+//!   before the outermost IRQ returns, if `cortex_ar::task::request_reschedule()`
+//!   was called. See [Reschedule Handler](#reschedule-handler).
+//!
+//! ## Memory Layout
+//!
+//! Our linker script, `link.x`, does `INCLUDE memory.x`, so you must supply a
+//! file called `memory.x` somewhere on the linker search path. The easiest way
+//! is to have your `build.rs` copy it into `OUT_DIR`, and print
+//! `cargo:rustc-link-search` with that directory. Your `memory.x` describes
+//! your chip's memory, and uses `REGION_ALIAS` to tell us where to put things:
+//!
+//! * `CODE` - the vector table, code, read-only data, and the initial values
+//!   of `.data`
+//! * `DATA` - `.data`, `.bss`, `.uninit`, the heap and the stacks
+//! * `VECTORS` - the vector table, if you enable the `hivecs` feature
+//!
+//! For example:
+//!
+//! ```text
+//! MEMORY {
+//!     SDRAM : ORIGIN = 0, LENGTH = 128M
+//! }
+//!
+//! REGION_ALIAS("CODE", SDRAM);
+//! REGION_ALIAS("DATA", SDRAM);
+//! ```
+//!
+//! Pass `-Tlink.x` to the linker to use our linker script, for example with
+//! `println!("cargo:rustc-link-arg=-Tlink.x");` in your `build.rs`. If your
+//! chip's memory changes, you only need to change `memory.x`.
+//!
+//! ## Adding Your Own Sections
+//!
+//! You don't need to replace our linker script to add your own sections (for
+//! code in TCM, or a region of memory shared with another core). Instead, put
+//! them in `memory.x` and use `INSERT AFTER` or `INSERT BEFORE` with one of
+//! these output sections, which we promise not to rename: `.vector_table`,
+//! `.text`, `.rodata`, `.irq_dispatch`, `.data`, `.bss` and `.uninit`.
+//!
+//! For example:
+//!
+//! ```text
+//! SECTIONS {
+//!     .shared (NOLOAD) : ALIGN(4) {
+//!         *(.shared .shared.*);
+//!     } > SHARED
+//! } INSERT AFTER .uninit;
+//! ```
+//!
+//! A section you add with `INSERT AFTER .data` is initialised along with
+//! `.data`, and one you add with `INSERT AFTER .bss` is zeroed along with
+//! `.bss`, as long as it stays in the `DATA` region (and, for `.data`, is
+//! loaded from the `CODE` region).
 //!
 //! ## Information about the Run-Time
 //!
@@ -103,6 +179,40 @@
 //! }
 //! ```
 //!
+//! An `#[entry]` function may also return `()`, in which case the processor
+//! spins in a loop once it returns.
+//!
+//! If you have a multi-core system and your start-up code calls `kmain2`,
+//! `kmain3`, etc on the other cores, you can create those functions with
+//! `#[entry(core = 1)]`, `#[entry(core = 2)]`, and so on.
+//!
+//! ### Start-up Hooks
+//!
+//! Our default start-up code, `_default_start`, calls an `extern "C"` function
+//! at the end of each of its phases, so you can slot in a small step (like
+//! releasing a watchdog, or turning on a clock) without writing your own
+//! `_start`. Our linker script PROVIDEs a default for each of them, which does
+//! nothing.
+//!
+//! * `_post_stack_setup` - after the stacks are set up
+//! * `_post_fpu_enable` - after the FPU has been enabled (or would have been, if
+//!   you don't have one)
+//! * `_post_bss_init` - after `.bss` has been zeroed
+//! * `_post_data_init` - after `.data` has been initialised
+//! * `_pre_kmain` - just before `kmain` is called
+//!
+//! They are called in that order. Be careful what a hook touches: global
+//! variables aren't ready until `.bss` and `.data` are, and hooks that run
+//! before `_post_fpu_enable` must not do any floating-point work.
+//!
+//! ```rust
+//! #[unsafe(no_mangle)]
+//! extern "C" fn _post_stack_setup() {
+//!     // Safety: this is the watchdog on our chip
+//!     unsafe { core::ptr::write_volatile(0xE000_1000 as *mut u32, 0) };
+//! }
+//! ```
+//!
 //! ### Undefined Handler
 //!
 //! The symbol `_undefined_handler` should be an `extern "C"` function. It is
@@ -112,8 +222,8 @@
 //!     https://developer.arm.com/documentation/ddi0406/c/System-Level-Architecture/The-System-Level-Programmers--Model/Exception-descriptions/Undefined-Instruction-exception?lang=en
 //!
 //! Our linker script PROVIDEs a default `_undefined_handler` symbol which is an
-//! alias for the `_default_handler` function. You can override it by defining
-//! your own `_undefined_handler` function, like:
+//! alias for the `_default_undefined_handler` function. You can override it by
+//! defining your own `_undefined_handler` function, like:
 //!
 //! ```rust
 //! /// Does not return
@@ -177,8 +287,8 @@
 //! machine code for you by the default assembly trampoline.
 //!
 //! Our linker script PROVIDEs a default `_svc_handler` symbol which is an alias
-//! for the `_default_handler` function. You can override it by defining your
-//! own `_svc_handler` function, like:
+//! for the `_default_svc_handler` function. You can override it by defining
+//! your own `_svc_handler` function, like:
 //!
 //! ```rust
 //! #[unsafe(no_mangle)]
@@ -199,6 +309,76 @@
 //! }
 //! ```
 //!
+//! The handler can also take a second argument, giving it access to the
+//! registers that were saved on entry to the exception (see
+//! [`ExceptionFrame`]). Changes to those registers take effect when the handler
+//! returns. This also works for the Undefined, Prefetch Abort and Data Abort
+//! handlers.
+//!
+//! ```rust
+//! use cortex_a_rt::{exception, ExceptionFrame};
+//!
+//! #[exception(SupervisorCall)]
+//! fn my_svc_handler(arg: u32, frame: &mut ExceptionFrame) {
+//!     // return a value to the caller in R0
+//!     frame.r0 = arg * 2;
+//! }
+//! ```
+//!
+//! ### Secure Monitor Call Handler
+//!
+//! With the `el3-boot` feature, the symbol `_smc_handler` should be an
+//! `extern "C"` function. It is called in Monitor mode (on the MON stack) when
+//! the Non-secure world executes an `smc` instruction. It is passed the value
+//! of R0 at the time of the call (e.g. an SMC Calling Convention function
+//! identifier) and, optionally, the [`ExceptionFrame`], which you can use to
+//! return values to the caller. Execution resumes after the `smc`.
+//!
+//! Our linker script PROVIDEs a default `_smc_handler` symbol which is an alias
+//! for the `_default_smc_handler` function, which returns -1 in R0. You can
+//! override it by defining your own `_smc_handler` function, like:
+//!
+//! ```rust
+//! use cortex_a_rt::ExceptionFrame;
+//!
+//! #[unsafe(no_mangle)]
+//! extern "C" fn _smc_handler(arg: u32, frame: &mut ExceptionFrame) {
+//!     // do stuff here
+//!     frame.r0 = 0;
+//! }
+//! ```
+//!
+//! The handler runs with SCR.NS still set, so any banked registers it touches
+//! are the Non-secure copies.
+//!
+//! ### Hypervisor Call Handler
+//!
+//! On an Armv7-A processor with the Virtualization Extensions, the symbol
+//! `_hvc_handler` should be an `extern "C"` function. It is called in Hyp mode,
+//! on the Hyp mode stack, when a Non-secure PL1 mode executes an `hvc` instruction (see
+//! `cortex_ar::hvc!`). It is passed the immediate argument of the `hvc`
+//! instruction, which the default assembly trampoline extracts from HSR, and,
+//! optionally, the [`ExceptionFrame`], which you can use to return values to
+//! the caller. Execution resumes after the `hvc`.
+//!
+//! Our start-up code doesn't set HVBAR, so you must point it at `_vector_table`
+//! yourself (e.g. with `cortex_ar::vectors::set_hyp_vector_table`) from Hyp
+//! mode.
+//!
+//! Our linker script PROVIDEs a default `_hvc_handler` symbol which is an alias
+//! for the `_default_hvc_handler` function, which returns -1 in R0. You can
+//! override it by defining your own `_hvc_handler` function, like:
+//!
+//! ```rust
+//! use cortex_a_rt::ExceptionFrame;
+//!
+//! #[unsafe(no_mangle)]
+//! extern "C" fn _hvc_handler(hvc: u32, frame: &mut ExceptionFrame) {
+//!     // do stuff here
+//!     frame.r0 = 0;
+//! }
+//! ```
+//!
 //! ### Prefetch Abort Handler
 //!
 //! The symbol `_prefetch_abort_handler` should be an `extern "C"` function. It
@@ -208,8 +388,8 @@
 //!     https://developer.arm.com/documentation/ddi0406/c/System-Level-Architecture/The-System-Level-Programmers--Model/Exception-descriptions/Prefetch-Abort-exception?lang=en
 //!
 //! Our linker script PROVIDEs a default `_prefetch_abort_handler` symbol which
-//! is an alias for the `_default_handler` function. You can override it by
-//! defining your own `_undefined_handler` function.
+//! is an alias for the `_default_prefetch_abort_handler` function. You can
+//! override it by defining your own `_undefined_handler` function.
 //!
 //! This function takes the address of faulting instruction, and can either not
 //! return:
@@ -266,8 +446,13 @@
 //!     https://developer.arm.com/documentation/ddi0406/c/System-Level-Architecture/The-System-Level-Programmers--Model/Exception-descriptions/Data-Abort-exception?lang=en
 //!
 //! Our linker script PROVIDEs a default `_data_abort_handler` symbol which is
-//! an alias for the `_default_handler` function. You can override it by
-//! defining your own `_undefined_handler` function.
+//! an alias for the `_default_data_abort_handler` function. You can override
+//! it by defining your own `_undefined_handler` function.
+//!
+//! The default handler recovers from faults in `cortex_ar::memory::try_copy`.
+//! If you override it, and want `try_copy` to work, call
+//! `cortex_ar::memory::recovery_address` first and return the address it
+//! gives you.
 //!
 //! This function takes the address of faulting instruction, and can either not
 //! return:
@@ -315,6 +500,20 @@
 //! }
 //! ```
 //!
+//! If your handler takes a [`FaultInfo`](cortex_ar::fault::FaultInfo)
+//! instead of the address, the contents of DFSR and DFAR are captured for you
+//! before your handler is called.
+//!
+//! ```rust
+//! use cortex_a_rt::exception;
+//! use cortex_ar::fault::FaultInfo;
+//!
+//! #[exception(DataAbort)]
+//! fn my_handler(info: FaultInfo) -> ! {
+//!     panic!("Data Abort at {:#x} accessing {:#x}", info.addr, info.dfar);
+//! }
+//! ```
+//!
 //! ### IRQ Handler
 //!
 //! The symbol `_irq_handler` should be an `extern "C"` function. It is called
@@ -332,7 +531,7 @@
 //! re-entering this interrupt handler recursively until you stack overflow.
 //!
 //! Our linker script PROVIDEs a default `_irq_handler` symbol which is an alias
-//! for `_default_handler`. You can override it by defining your own
+//! for `_default_irq_handler`. You can override it by defining your own
 //! `_irq_handler` function.
 //!
 //! Expected prototype:
@@ -360,6 +559,82 @@
 //! }
 //! ```
 //!
+//! ### Interrupt Handlers
+//!
+//! Rather than writing one `_irq_handler` that handles every interrupt, you can
+//! write one function per interrupt and mark it with the `#[interrupt(...)]`
+//! attribute, giving the GIC interrupt ID as an `SGI`, `PPI` or `SPI` number.
+//!
+//! ```rust
+//! use cortex_a_rt::interrupt;
+//!
+//! #[interrupt(SPI = 42)]
+//! fn uart0() {
+//!     // Handle interrupt
+//! }
+//! ```
+//!
+//! Each handler is placed in a table (in the `.irq_dispatch` section) by our
+//! linker script. The [`dispatch_interrupts`] function acknowledges each
+//! pending interrupt with an [`InterruptController`], calls the matching
+//! handler from the table and then completes the interrupt. Interrupts without
+//! a handler are passed to `_default_handler`.
+//!
+//! Call `dispatch_interrupts` from your own `_irq_handler`, passing a driver
+//! for your interrupt controller:
+//!
+//! ```rust
+//! use cortex_a_rt::irq;
+//!
+//! #[irq]
+//! fn my_irq_handler() {
+//!     cortex_a_rt::dispatch_interrupts(&mut cortex_ar::gic::GicV3CpuInterface);
+//! }
+//! ```
+//!
+//! ### Reschedule Handler
+//!
+//! With the `reschedule` feature enabled, the IRQ trampoline keeps count of
+//! how deeply nested it is on each core. When the outermost IRQ handler
+//! returns, if `cortex_ar::task::request_reschedule()` was called on this core,
+//! the trampoline calls `_reschedule_handler` before it returns to the
+//! interrupted code. It is called in SYS mode, with interrupts masked.
+//!
+//! This is where an RTOS can call `cortex_ar::task::switch_context` to move to
+//! another task, knowing that it isn't inside any other interrupt handler.
+//!
+//! Our linker script PROVIDEs a default `_reschedule_handler` symbol which is
+//! an alias for `_default_hook`, which does nothing.
+//!
+//! Expected prototype:
+//!
+//! ```rust
+//! #[unsafe(no_mangle)]
+//! extern "C" fn _reschedule_handler() {
+//!     // pick the next task, and switch to it
+//! }
+//! ```
+//!
+//! ### Custom Vector Table
+//!
+//! If you need to put something other than a jump to one of the `_asm_*`
+//! symbols in the vector table (say, to jump straight into your own FIQ
+//! handler), you can generate a complete `_vector_table` with the
+//! `vector_table!` macro. It replaces the `_default_vector_table` this library
+//! provides.
+//!
+//! ```rust ignore
+//! cortex_a_rt::vector_table! {
+//!     reset: _start,
+//!     undefined: _asm_undefined_handler,
+//!     svc: _asm_svc_handler,
+//!     prefetch_abort: _asm_prefetch_abort_handler,
+//!     data_abort: _asm_data_abort_handler,
+//!     irq: _asm_irq_handler,
+//!     fiq: my_fiq_handler,
+//! }
+//! ```
+//!
 //! ## ASM functions
 //!
 //! These are the naked 'raw' assembly functions the run-time requires:
@@ -397,6 +672,12 @@
 //!   default handler will call `_data_abort_handler` in ABT mode, saving state
 //!   as required.
 //!
+//! * `_asm_hvc_handler` - a naked function to call when a Non-secure PL1 mode
+//!   executes an `hvc`, and the vector table is being used by Hyp mode. Our
+//!   linker script PROVIDEs a default function at `_asm_default_hvc_handler`
+//!   but you can override it. The provided default handler will call
+//!   `_hvc_handler` in Hyp mode, saving state as required.
+//!
 //! * `_asm_irq_handler` - a naked function to call when an Undefined Exception
 //!   occurs. Our linker script PROVIDEs a default function at
 //!   `_asm_default_irq_handler` but you can override it. The provided default
@@ -408,13 +689,35 @@
 //!   `_asm_default_fiq_handler` but you can override it. The provided default
 //!   just spins forever.
 //!
+//! * `_monitor_vector_table` - the Monitor mode vector table, only used with
+//!   the `el3-boot` feature. Our linker script PROVIDEs a default table at
+//!   `_default_monitor_vector_table` but you can override it, for example if
+//!   you want to handle more than Secure Monitor Calls. It must be 32-byte
+//!   aligned.
+//!
+//! * `_asm_smc_handler` - a naked function to call when a Secure Monitor Call
+//!   occurs, with the `el3-boot` feature. Our linker script PROVIDEs a default
+//!   function at `_asm_default_smc_handler` but you can override it. The
+//!   provided default handler will call `_smc_handler` in MON mode, saving
+//!   state as required.
+//!
 //! ## Outputs
 //!
 //! This library produces global symbols called:
 //!
-//! * `_vector_table` - the start of the interrupt vector table
+//! * `_default_vector_table` - the default interrupt vector table
+//! * `_default_monitor_vector_table` - a Monitor vector table that jumps to
+//!   `_asm_smc_handler` for a Secure Monitor Call and spins on every other
+//!   exception, if the `el3-boot` feature is enabled
+//! * `_asm_default_smc_handler` - assembly language trampoline that calls
+//!   `_smc_handler`, if the `el3-boot` feature is enabled
+//! * `_default_smc_handler` - a C compatible function that returns -1 to the
+//!   caller, if the `el3-boot` feature is enabled
 //! * `_default_start` - the default Reset handler, that sets up some stacks and
 //!   calls an `extern "C"` function called `kmain`.
+//! * `_stack_setup` - initialises UND, SVC, ABT, IRQ, FIQ and SYS stacks from
+//!   the address given in `r0`, for use by your own start-up code (e.g. on a
+//!   second core)
 //! * `_asm_default_undefined_handler` - assembly language trampoline that calls
 //!   `_undefined_handler`
 //! * `_asm_default_svc_handler` - assembly language trampoline that calls
@@ -423,10 +726,33 @@
 //!   calls `_prefetch_abort_handler`
 //! * `_asm_default_data_abort_handler` - assembly language trampoline that
 //!   calls `_data_abort_handler`
+//! * `_asm_default_hvc_handler` - assembly language trampoline that calls
+//!   `_hvc_handler`
 //! * `_asm_default_irq_handler` - assembly language trampoline that calls
 //!   `_irq_handler`
 //! * `_asm_default_fiq_handler` - an FIQ handler that just spins
-//! * `_default_handler` - a C compatible function that spins forever.
+//! * `_default_handler` - a C compatible function that spins forever, keeping
+//!   hold of which exception occurred so a debugger can see it.
+//! * `_default_undefined_handler`, `_default_svc_handler`,
+//!   `_default_prefetch_abort_handler` and `_default_data_abort_handler` - C
+//!   compatible functions that call `_default_handler` (except for Data
+//!   Aborts that `cortex_ar::memory::recovery_address` can recover from).
+//! * `_default_hvc_handler` - a C compatible function that returns -1 to the
+//!   caller
+//! * `_default_irq_handler` - a C compatible function that calls
+//!   `_default_handler`.
+//! * `_default_hook` - a C compatible function that does nothing, used as the
+//!   default for each of the start-up hooks, and for `_reschedule_handler`
+//! * `_boot_info` - the `BootInfo` behind `boot_info()`, if the `boot-info`
+//!   feature is enabled
+//! * `_boot_ticks` - the readings behind `boot_ticks()`, if the `boot-ticks`
+//!   feature is enabled
+//! * `_exception_counts` - the counters behind `exception_stats()`, if the
+//!   `exception-stats` feature is enabled
+//! * `_handler_table` - the handlers installed with `set_handler()`, if the
+//!   `handler-table` feature is enabled
+//! * `_irq_depth` - how deeply nested the IRQ trampoline is on each core, if
+//!   the `reschedule` feature is enabled
 //!
 //! The assembly language trampolines are required because Armv7-A processors do
 //! not save a great deal of state on entry to an exception handler, unlike
@@ -448,36 +774,222 @@
 #[cfg(target_arch = "arm")]
 use cortex_ar::register::{cpsr::ProcessorMode, Cpsr};
 
-pub use cortex_ar_rt_macros::{entry, exception, irq};
+pub use cortex_ar_rt_macros::{entry, exception, interrupt, irq};
+
+use cortex_ar::interrupt::InterruptController;
+
+#[cfg(feature = "boot-info")]
+mod boot_info;
+
+#[cfg(feature = "boot-ticks")]
+mod boot_ticks;
+
+#[cfg(feature = "handler-table")]
+pub use cortex_ar::exception::{clear_handler, set_handler, Handler};
+
+#[cfg(feature = "boot-info")]
+pub use boot_info::{boot_info, BootInfo};
+
+#[cfg(feature = "boot-ticks")]
+pub use boot_ticks::{boot_ticks, BootTicks};
+
+#[cfg(feature = "exception-stats")]
+pub use cortex_ar::exception::{exception_stats, ExceptionStats};
+
+pub use cortex_ar::exception::{ExceptionFrame, Vector};
 
 /// Our default exception handler.
 ///
 /// We end up here if an exception fires and the weak 'PROVIDE' in the link.x
-/// file hasn't been over-ridden.
+/// file hasn't been over-ridden. It spins forever, but keeps hold of its
+/// arguments so you can see what happened with a debugger:
+///
+/// * `vector` is a [`Vector`], as a `u32`
+/// * `arg` is the faulting address for an Undefined, Prefetch Abort or Data
+///   Abort exception, the SVC number for a Supervisor Call, or the ID of an
+///   interrupt that `dispatch_interrupts` didn't have a handler for
 #[no_mangle]
-pub extern "C" fn _default_handler() {
+pub extern "C" fn _default_handler(vector: u32, arg: usize) {
     loop {
+        core::hint::black_box((vector, arg));
         core::hint::spin_loop();
     }
 }
 
+/// Our default Undefined handler, which reports to `_default_handler`.
+#[no_mangle]
+pub extern "C" fn _default_undefined_handler(addr: usize) {
+    _default_handler(Vector::Undefined as u32, addr);
+}
+
+/// Our default Supervisor Call handler, which reports to `_default_handler`.
+#[no_mangle]
+pub extern "C" fn _default_svc_handler(svc: u32) {
+    _default_handler(Vector::SupervisorCall as u32, svc as usize);
+}
+
+/// Our default Hypervisor Call handler, which returns -1 (`NOT_SUPPORTED` in
+/// the SMC Calling Convention) to the caller in R0.
+#[no_mangle]
+pub extern "C" fn _default_hvc_handler(_hvc: u32, frame: &mut ExceptionFrame) {
+    frame.r0 = u32::MAX;
+}
+
+/// Our default Prefetch Abort handler, which reports to `_default_handler`.
+#[no_mangle]
+pub extern "C" fn _default_prefetch_abort_handler(addr: usize) {
+    _default_handler(Vector::PrefetchAbort as u32, addr);
+}
+
+/// Our default Data Abort handler, which resumes at the fixup address if the
+/// fault was in `cortex_ar::memory::try_copy` (or anything else registered
+/// for recovery), and otherwise reports to `_default_handler`.
+#[no_mangle]
+pub extern "C" fn _default_data_abort_handler(addr: usize) -> usize {
+    if let Some(fixup) = cortex_ar::memory::recovery_address(addr) {
+        return fixup;
+    }
+    _default_handler(Vector::DataAbort as u32, addr);
+    addr
+}
+
+/// Our default IRQ handler, which reports to `_default_handler`.
+///
+/// We end up here if an IRQ fires and the weak 'PROVIDE' in the link.x file
+/// hasn't been over-ridden.
+#[no_mangle]
+pub extern "C" fn _default_irq_handler() {
+    _default_handler(Vector::Irq as u32, 0);
+}
+
+/// Our default Secure Monitor Call handler, which returns -1 (`NOT_SUPPORTED`
+/// in the SMC Calling Convention) to the caller in R0.
+#[cfg(feature = "el3-boot")]
+#[no_mangle]
+pub extern "C" fn _default_smc_handler(_arg: u32, frame: &mut ExceptionFrame) {
+    frame.r0 = u32::MAX;
+}
+
+/// An entry in the table of `#[interrupt]` handlers.
+///
+/// This must match the layout of the entries generated by the `#[interrupt]`
+/// macro.
+#[repr(C)]
+struct InterruptHandler {
+    id: u32,
+    handler: unsafe extern "C" fn(),
+}
+
+/// Call the `#[interrupt]` handler for every pending interrupt.
+///
+/// Keeps acknowledging interrupts from the given interrupt controller until
+/// there are none left pending. Each interrupt is passed to the function marked
+/// with a matching `#[interrupt(...)]` attribute, or to `_default_handler` if
+/// there isn't one, and then completed.
+///
+/// Call this from your `_irq_handler`:
+///
+/// ```rust ignore
+/// #[irq]
+/// fn irq_handler() {
+///     let mut gic = cortex_ar::gic::GicV3CpuInterface;
+///     cortex_a_rt::dispatch_interrupts(&mut gic);
+/// }
+/// ```
+pub fn dispatch_interrupts<C: InterruptController>(controller: &mut C) {
+    extern "C" {
+        static __sirq_dispatch: InterruptHandler;
+        static __eirq_dispatch: InterruptHandler;
+    }
+
+    // Safety: The linker script places only `InterruptHandler` entries between
+    // these two symbols
+    let handlers = unsafe {
+        let start = core::ptr::addr_of!(__sirq_dispatch);
+        let end = core::ptr::addr_of!(__eirq_dispatch);
+        let len = (end as usize - start as usize) / core::mem::size_of::<InterruptHandler>();
+        core::slice::from_raw_parts(start, len)
+    };
+
+    while let Some(id) = controller.acknowledge() {
+        match handlers.iter().find(|h| h.id == id) {
+            // Safety: These functions were generated by `#[interrupt]` and so
+            // expect to be called from the IRQ handler
+            Some(entry) => unsafe { (entry.handler)() },
+            None => _default_handler(Vector::Irq as u32, id as usize),
+        }
+        controller.end_of_interrupt(id);
+    }
+}
+
+/// Generate a custom `_vector_table`.
+///
+/// Give the symbol to jump to for each of the eight exception vectors (other
+/// than the unused one). The table is placed in the `.vector_table` section,
+/// which our linker script puts ahead of the default table, and the
+/// `_vector_table` symbol it defines replaces our `_default_vector_table`.
+///
+/// ```rust ignore
+/// cortex_a_rt::vector_table! {
+///     reset: _start,
+///     undefined: _asm_undefined_handler,
+///     svc: _asm_svc_handler,
+///     prefetch_abort: _asm_prefetch_abort_handler,
+///     data_abort: _asm_data_abort_handler,
+///     irq: _asm_irq_handler,
+///     fiq: my_fiq_handler,
+/// }
+/// ```
+///
+/// The symbols given above (apart from `my_fiq_handler`) are the ones the
+/// default table uses, so you only need to change the ones you want to
+/// replace.
+#[macro_export]
+macro_rules! vector_table {
+    (
+        reset: $reset:ident,
+        undefined: $undefined:ident,
+        svc: $svc:ident,
+        prefetch_abort: $prefetch_abort:ident,
+        data_abort: $data_abort:ident,
+        irq: $irq:ident,
+        fiq: $fiq:ident $(,)?
+    ) => {
+        ::core::arch::global_asm!(concat!(
+            ".section .vector_table,\"ax\",%progbits\n",
+            ".global _vector_table\n",
+            ".type _vector_table, %function\n",
+            "_vector_table:\n",
+            concat!("    ldr pc, =", stringify!($reset), "\n"),
+            concat!("    ldr pc, =", stringify!($undefined), "\n"),
+            concat!("    ldr pc, =", stringify!($svc), "\n"),
+            concat!("    ldr pc, =", stringify!($prefetch_abort), "\n"),
+            concat!("    ldr pc, =", stringify!($data_abort), "\n"),
+            "    nop\n",
+            concat!("    ldr pc, =", stringify!($irq), "\n"),
+            concat!("    ldr pc, =", stringify!($fiq), "\n"),
+            ".size _vector_table, . - _vector_table\n",
+        ));
+    };
+}
+
 // The Interrupt Vector Table, and some default assembly-language handler.
 #[cfg(target_arch = "arm")]
 core::arch::global_asm!(
     r#"
-    .section .vector_table,"ax",%progbits
-    .global _vector_table
-    .type _vector_table, %function
-    _vector_table:
+    .section .vector_table.default,"ax",%progbits
+    .global _default_vector_table
+    .type _default_vector_table, %function
+    _default_vector_table:
         ldr     pc, =_start
         ldr     pc, =_asm_undefined_handler
         ldr     pc, =_asm_svc_handler
         ldr     pc, =_asm_prefetch_abort_handler
         ldr     pc, =_asm_data_abort_handler
-        nop
+        ldr     pc, =_asm_hvc_handler
         ldr     pc, =_asm_irq_handler
         ldr     pc, =_asm_fiq_handler
-    .size _vector_table, . - _vector_table
+    .size _default_vector_table, . - _default_vector_table
     "#
 );
 
@@ -486,7 +998,9 @@ core::arch::global_asm!(
 ///
 /// It should match `restore_context!`.
 ///
-/// On entry to this block, we assume that we are in exception context.
+/// On entry to this block, we assume that we are in exception context. On exit,
+/// R12 points at the saved R0-R3 and R12, followed by whatever the exception
+/// handler pushed before this block (see `ExceptionFrame`).
 #[cfg(all(
     target_arch = "arm",
     not(any(target_abi = "eabihf", feature = "eabi-fpu"))
@@ -495,13 +1009,15 @@ macro_rules! save_context {
     () => {
         r#"
         // save preserved registers (and gives us some working area)
-        push    {{r0-r3}}
+        push    {{r0-r3, r12}}
+        // keep a pointer to the saved registers (the exception frame)
+        mov     r12, sp
         // align SP down to eight byte boundary
         mov     r0, sp
         and     r0, r0, 7
         sub     sp, r0
-        // push alignment amount, and final preserved register
-        push    {{r0, r12}}
+        // push alignment amount, and a padding word
+        push    {{r0, r1}}
         "#
     };
 }
@@ -517,12 +1033,12 @@ macro_rules! save_context {
 macro_rules! restore_context {
     () => {
         r#"
-        // restore alignment amount, and preserved register
-        pop     {{r0, r12}}
+        // restore alignment amount (and discard the padding word)
+        pop     {{r0, r1}}
         // restore pre-alignment SP
         add     sp, r0
         // restore more preserved registers
-        pop     {{r0-r3}}
+        pop     {{r0-r3, r12}}
         "#
     };
 }
@@ -540,7 +1056,9 @@ macro_rules! save_context {
     () => {
         r#"
         // save preserved registers (and gives us some working area)
-        push    {{r0-r3}}
+        push    {{r0-r3, r12}}
+        // keep a pointer to the saved registers (the exception frame)
+        mov     r12, sp
         // save FPU context
         vpush   {{d0-d7}}
         vmrs    r0, FPSCR
@@ -550,8 +1068,8 @@ macro_rules! save_context {
         mov     r0, sp
         and     r0, r0, 7
         sub     sp, r0
-        // push alignment amount, and final preserved register
-        push    {{r0, r12}}
+        // push alignment amount, and a padding word
+        push    {{r0, r1}}
         "#
     };
 }
@@ -568,8 +1086,8 @@ macro_rules! save_context {
 macro_rules! restore_context {
     () => {
         r#"
-        // restore alignment amount, and preserved register
-        pop     {{r0, r12}}
+        // restore alignment amount (and discard the padding word)
+        pop     {{r0, r1}}
         // restore pre-alignment SP
         add     sp, r0
         // pop FPU state
@@ -578,7 +1096,7 @@ macro_rules! restore_context {
         vmsr    FPSCR, r0
         vpop    {{d0-d7}}
         // restore more preserved registers
-        pop     {{r0-r3}}
+        pop     {{r0-r3, r12}}
         "#
     };
 }
@@ -596,7 +1114,9 @@ macro_rules! save_context {
     () => {
         r#"
         // save preserved registers (and gives us some working area)
-        push    {{r0-r3}}
+        push    {{r0-r3, r12}}
+        // keep a pointer to the saved registers (the exception frame)
+        mov     r12, sp
         // save FPU context
         vpush   {{d0-d7}}
         vpush   {{d16-d31}}
@@ -607,8 +1127,8 @@ macro_rules! save_context {
         mov     r0, sp
         and     r0, r0, 7
         sub     sp, r0
-        // push alignment amount, and final preserved register
-        push    {{r0, r12}}
+        // push alignment amount, and a padding word
+        push    {{r0, r1}}
         "#
     };
 }
@@ -625,8 +1145,8 @@ macro_rules! save_context {
 macro_rules! restore_context {
     () => {
         r#"
-        // restore alignment amount, and preserved register
-        pop     {{r0, r12}}
+        // restore alignment amount (and discard the padding word)
+        pop     {{r0, r1}}
         // restore pre-alignment SP
         add     sp, r0
         // pop FPU state
@@ -636,11 +1156,180 @@ macro_rules! restore_context {
         vpop    {{d16-d31}}
         vpop    {{d0-d7}}
         // restore more preserved registers
-        pop     {{r0-r3}}
+        pop     {{r0-r3, r12}}
         "#
     };
 }
 
+/// This macro expands to code for counting an exception, if the
+/// `exception-stats` feature is enabled.
+///
+/// Pass the byte offset of the counter within `_exception_counts`. It trashes
+/// R0-R2 and the flags, so use it straight after `save_context!`.
+#[cfg(feature = "exception-stats")]
+macro_rules! count_exception {
+    ($offset:literal) => {
+        concat!(
+            r#"
+        // count this exception
+        ldr     r0, =_exception_counts
+        add     r0, r0, #"#,
+            stringify!($offset),
+            r#"
+    1:
+        ldrex   r1, [r0]
+        add     r1, r1, #1
+        strex   r2, r1, [r0]
+        cmp     r2, #0
+        bne     1b
+        "#
+        )
+    };
+}
+
+/// This macro expands to code that does nothing because the
+/// `exception-stats` feature is disabled.
+#[cfg(not(feature = "exception-stats"))]
+macro_rules! count_exception {
+    ($offset:literal) => {
+        r#"
+        // not counting exceptions - do nothing
+        "#
+    };
+}
+
+/// This macro expands to code for calling an exception handler, via the
+/// handler table if the `handler-table` feature is enabled.
+///
+/// Pass the link-time handler, and the byte offset of its entry within
+/// `_handler_table`. It trashes R2 and the flags.
+#[cfg(feature = "handler-table")]
+macro_rules! call_handler {
+    ($handler:ident, $offset:literal) => {
+        concat!(
+            r#"
+        // use the handler from the table, if one was installed
+        ldr     r2, =_handler_table
+        ldr     r2, [r2, #"#,
+            stringify!($offset),
+            r#"]
+        cmp     r2, #0
+        beq     3f
+        blx     r2
+        b       4f
+    3:
+        bl      "#,
+            stringify!($handler),
+            r#"
+    4:
+        "#
+        )
+    };
+}
+
+/// This macro expands to code for calling an exception handler, because the
+/// `handler-table` feature is disabled.
+#[cfg(not(feature = "handler-table"))]
+macro_rules! call_handler {
+    ($handler:ident, $offset:literal) => {
+        concat!(
+            r#"
+        bl      "#,
+            stringify!($handler),
+            r#"
+        "#
+        )
+    };
+}
+
+/// This macro expands to code for noting that we've entered an IRQ handler,
+/// so we can tell when the outermost one returns.
+#[cfg(feature = "reschedule")]
+macro_rules! irq_enter {
+    () => {
+        r#"
+        // we're one IRQ deeper on this core
+        // core index = MPIDR.Aff0, or MPIDR.Aff1 if MPIDR.MT is set
+        mrc     p15, 0, r0, c0, c0, 5
+        and     r1, r0, #(1 << 24)
+        lsr     r1, r1, #21
+        lsr     r0, r0, r1
+        and     r0, r0, #7
+        ldr     r1, =_irq_depth
+        ldrb    r2, [r1, r0]
+        add     r2, r2, #1
+        strb    r2, [r1, r0]
+        "#
+    };
+}
+
+/// This macro expands to code for noting that we've left an IRQ handler, and
+/// calling `_reschedule_handler` if this was the outermost one and a
+/// reschedule was requested.
+#[cfg(feature = "reschedule")]
+macro_rules! irq_exit {
+    () => {
+        r#"
+        // we're one IRQ shallower on this core
+        // core index = MPIDR.Aff0, or MPIDR.Aff1 if MPIDR.MT is set
+        mrc     p15, 0, r0, c0, c0, 5
+        and     r1, r0, #(1 << 24)
+        lsr     r1, r1, #21
+        lsr     r0, r0, r1
+        and     r0, r0, #7
+        ldr     r1, =_irq_depth
+        ldrb    r2, [r1, r0]
+        subs    r2, r2, #1
+        strb    r2, [r1, r0]
+        bne     5f
+        // this is the outermost IRQ, so was a reschedule requested?
+        ldr     r1, =_reschedule_pending
+        ldrb    r2, [r1, r0]
+        cmp     r2, #0
+        beq     5f
+        mov     r2, #0
+        strb    r2, [r1, r0]
+        bl      _reschedule_handler
+    5:
+        "#
+    };
+}
+
+/// This macro expands to code that does nothing because the `reschedule`
+/// feature is disabled.
+#[cfg(not(feature = "reschedule"))]
+macro_rules! irq_enter {
+    () => {
+        r#"
+        // not tracking IRQ depth - do nothing
+        "#
+    };
+}
+
+/// This macro expands to code that does nothing because the `reschedule`
+/// feature is disabled.
+#[cfg(not(feature = "reschedule"))]
+macro_rules! irq_exit {
+    () => {
+        r#"
+        // not checking for a reschedule - do nothing
+        "#
+    };
+}
+
+// How many IRQs deep each core is, for the `reschedule` feature
+#[cfg(all(target_arch = "arm", feature = "reschedule"))]
+core::arch::global_asm!(
+    r#"
+    .section .bss._irq_depth
+    .global _irq_depth
+    .type _irq_depth, %object
+    _irq_depth:
+        .space  8
+    .size _irq_depth, . - _irq_depth
+    "#
+);
+
 // Our assembly language exception handlers
 #[cfg(target_arch = "arm")]
 core::arch::global_asm!(
@@ -648,17 +1337,20 @@ core::arch::global_asm!(
        
     // Called from the vector table when we have an undefined exception.
     // Saves state and calls a C-compatible handler like
-    // `extern "C" fn _undefined_handler(addr: usize) -> usize;`
+    // `extern "C" fn _undefined_handler(addr: usize, frame: *mut ExceptionFrame) -> usize;`
     // or
-    // `extern "C" fn _undefined_handler(addr: usize) -> !;`
+    // `extern "C" fn _undefined_handler(addr: usize, frame: *mut ExceptionFrame) -> !;`
     .section .text._asm_default_undefined_handler
     .global _asm_default_undefined_handler
     .type _asm_default_undefined_handler, %function
     _asm_default_undefined_handler:
         // state save from compiled code
         srsfd   sp!, #{und_mode}
-        // to work out what mode we're in, we need R0
-        push    {{r0}}
+        // do our standard exception save, which gives us R0 to work with
+    "#,
+    save_context!(),
+    count_exception!(0),
+    r#"
         // First adjust LR for two purposes: Passing the faulting instruction to the C handler,
         // and to return to the failing instruction after the C handler returns.
         // Load processor status for the calling code
@@ -669,22 +1361,21 @@ core::arch::global_asm!(
         ite     eq
         subeq   lr, lr, #4
         subne   lr, lr, #2
-        // now do our standard exception save (which saves the 'wrong' R0)
-    "#,
-    save_context!(),
-    r#"
+        // Put the adjusted LR in the exception frame too
+        str     lr, [r12, #20]
         // Pass the faulting instruction address to the handler.
         mov     r0, lr
+        // Pass the exception frame to the handler.
+        mov     r1, r12
         // call C handler
-        bl      _undefined_handler
+    "#,
+    call_handler!(_undefined_handler, 0),
+    r#"
         // if we get back here, assume they returned a new LR in r0
         mov     lr, r0
-        // do our standard restore (with the 'wrong' R0)
     "#,
     restore_context!(),
     r#"
-        // get the R0 we saved early
-        pop     {{r0}}
         // overwrite the saved LR with the one from the C handler
         str     lr, [sp]
         // Return from the asm handler
@@ -696,13 +1387,14 @@ core::arch::global_asm!(
 
     // Called from the vector table when we have an software interrupt.
     // Saves state and calls a C-compatible handler like
-    // `extern "C" fn _svc_handler(svc: u32);`
+    // `extern "C" fn _svc_handler(svc: u32, frame: *mut ExceptionFrame);`
     .global _asm_default_svc_handler
     .type _asm_default_svc_handler, %function
     _asm_default_svc_handler:
         srsfd   sp!, #{svc_mode}
     "#,
     save_context!(),
+    count_exception!(4),
     r#"
         mrs      r0, cpsr                 // Load processor status
         tst      r0, {t_bit}              // Occurred in Thumb state?
@@ -711,8 +1403,9 @@ core::arch::global_asm!(
         ldreq    r0, [lr,#-4]             // No: Load word and...
         biceq    r0, r0, #0xFF000000      // ...extract comment field
         // r0 now contains SVC number
-        bl       _svc_handler
+        mov      r1, r12                  // Pass the exception frame
     "#,
+    call_handler!(_svc_handler, 4),
     restore_context!(),
     r#"
         rfefd   sp!
@@ -723,7 +1416,7 @@ core::arch::global_asm!(
 
     // Called from the vector table when we have an undefined exception.
     // Saves state and calls a C-compatible handler like
-    // `extern "C" fn _data_abort_handler(addr: usize);`
+    // `extern "C" fn _data_abort_handler(addr: usize, frame: *mut ExceptionFrame);`
     .global _asm_default_data_abort_handler
     .type _asm_default_data_abort_handler, %function
     _asm_default_data_abort_handler:
@@ -733,11 +1426,16 @@ core::arch::global_asm!(
         srsfd   sp!, #{abt_mode}
     "#,
     save_context!(),
+    count_exception!(12),
     r#"
         // Pass the faulting instruction address to the handler.
         mov     r0, lr
+        // Pass the exception frame to the handler.
+        mov     r1, r12
         // call C handler
-        bl      _data_abort_handler
+    "#,
+    call_handler!(_data_abort_handler, 12),
+    r#"
         // if we get back here, assume they returned a new LR in r0
         mov     lr, r0
     "#,
@@ -754,7 +1452,7 @@ core::arch::global_asm!(
 
     // Called from the vector table when we have a prefetch abort.
     // Saves state and calls a C-compatible handler like
-    // `extern "C" fn _prefetch_abort_handler(addr: usize);`
+    // `extern "C" fn _prefetch_abort_handler(addr: usize, frame: *mut ExceptionFrame);`
     .global _asm_default_prefetch_abort_handler
     .type _asm_default_prefetch_abort_handler, %function
     _asm_default_prefetch_abort_handler:
@@ -764,11 +1462,16 @@ core::arch::global_asm!(
         srsfd   sp!, #{abt_mode}
     "#,
     save_context!(),
+    count_exception!(8),
     r#"
         // Pass the faulting instruction address to the handler.
         mov     r0, lr
+        // Pass the exception frame to the handler.
+        mov     r1, r12
         // call C handler
-        bl      _prefetch_abort_handler
+    "#,
+    call_handler!(_prefetch_abort_handler, 8),
+    r#"
         // if we get back here, assume they returned a new LR in r0
         mov     lr, r0
     "#,
@@ -801,9 +1504,14 @@ core::arch::global_asm!(
         // save state to the system stack (adjusting SP for alignment)
     "#,
         save_context!(),
+        count_exception!(16),
+        irq_enter!(),
     r#"
         // call C handler
-        bl      _irq_handler
+    "#,
+    call_handler!(_irq_handler, 16),
+        irq_exit!(),
+    r#"
         // restore from the system stack
     "#,
         restore_context!(),
@@ -821,7 +1529,11 @@ core::arch::global_asm!(
     .global _asm_default_fiq_handler
     .type _asm_default_fiq_handler, %function
     _asm_default_fiq_handler:
-        b       _asm_default_fiq_handler
+    "#,
+    count_exception!(20),
+    r#"
+    2:
+        b       2b
     .size    _asm_default_fiq_handler, . - _asm_default_fiq_handler
     "#,
     svc_mode = const ProcessorMode::Svc as u8,
@@ -835,6 +1547,56 @@ core::arch::global_asm!(
     },
 );
 
+// Our Hypervisor Call trampoline, which runs in Hyp mode
+//
+// This needs the Virtualization Extensions, and is only used if you point
+// HVBAR at the vector table.
+#[cfg(target_arch = "arm")]
+core::arch::global_asm!(
+    r#"
+    .arch_extension virt
+
+    .section .text._asm_default_hvc_handler
+
+    // Called from the vector table (as the Hyp Trap exception) when a
+    // Non-secure PL1 mode executes an `hvc`. Saves state and calls a C-compatible handler like
+    // `extern "C" fn _hvc_handler(hvc: u32, frame: *mut ExceptionFrame);`
+    .global _asm_default_hvc_handler
+    .type _asm_default_hvc_handler, %function
+    _asm_default_hvc_handler:
+        // Hyp mode doesn't have its own LR, so save the caller's
+        push    {{lr}}
+        // Save ELR_hyp and SPSR_hyp, in the same layout as `srsfd`
+        sub     sp, sp, #8
+        mrs     lr, elr_hyp
+        str     lr, [sp]
+        mrs     lr, spsr_hyp
+        str     lr, [sp, #4]
+    "#,
+    save_context!(),
+    r#"
+        mrc     p15, 4, r0, c5, c2, 0     // Read HSR
+        lsr     r1, r0, #26               // Extract the Exception Class
+        cmp     r1, #0x12                 // Was it an HVC?
+    1:
+        bne     1b                        // No: spin, with HSR in r0
+        ubfx    r0, r0, #0, #16           // Yes: extract the immediate
+        mov     r1, r12                   // Pass the exception frame
+        bl      _hvc_handler
+    "#,
+    restore_context!(),
+    r#"
+        ldr     lr, [sp]
+        msr     elr_hyp, lr
+        ldr     lr, [sp, #4]
+        msr     spsr_hyp, lr
+        add     sp, sp, #8
+        pop     {{lr}}
+        eret
+    .size _asm_default_hvc_handler, . - _asm_default_hvc_handler
+    "#
+);
+
 /// This macro expands to code to turn on the FPU
 #[cfg(all(target_arch = "arm", any(target_abi = "eabihf", feature = "eabi-fpu")))]
 macro_rules! fpu_enable {
@@ -864,6 +1626,253 @@ macro_rules! fpu_enable {
     };
 }
 
+/// This macro expands to code to read the PMU cycle counter into r10 and r11
+#[cfg(all(target_arch = "arm", feature = "boot-ticks"))]
+macro_rules! boot_ticks_start {
+    () => {
+        r#"
+        // Start the PMU cycle counter, and record when we started booting
+        mrc     p15, 0, r0, c9, c12, 0
+        orr     r0, r0, #1
+        mcr     p15, 0, r0, c9, c12, 0
+        mov     r0, #0x80000000
+        mcr     p15, 0, r0, c9, c12, 1
+        isb
+        mrc     p15, 0, r10, c9, c13, 0
+        mov     r11, #0
+        "#
+    };
+}
+
+/// This macro expands to code to read the PMU cycle counter again, and store both readings
+/// in `_boot_ticks`
+#[cfg(all(target_arch = "arm", feature = "boot-ticks"))]
+macro_rules! boot_ticks_end {
+    () => {
+        r#"
+        // Record when we finished booting
+        mrc     p15, 0, r2, c9, c13, 0
+        mov     r3, #0
+        ldr     r0, =_boot_ticks
+        strd    r10, r11, [r0]
+        strd    r2, r3, [r0, #8]
+        "#
+    };
+}
+
+/// This macro expands to code that does nothing because we aren't measuring
+/// the boot time
+#[cfg(all(target_arch = "arm", not(feature = "boot-ticks")))]
+macro_rules! boot_ticks_start {
+    () => {
+        r#"
+        // no boot tick measurement - do nothing
+        "#
+    };
+}
+
+/// This macro expands to code that does nothing because we aren't measuring
+/// the boot time
+#[cfg(all(target_arch = "arm", not(feature = "boot-ticks")))]
+macro_rules! boot_ticks_end {
+    () => {
+        r#"
+        // no boot tick measurement - do nothing
+        "#
+    };
+}
+
+/// This macro expands to code to leave Secure state (EL3), and carry on in
+/// Non-secure System mode
+#[cfg(all(target_arch = "arm", feature = "el3-boot"))]
+macro_rules! el3_boot {
+    () => {
+        r#"
+        // Set the Monitor vector table, for anything taken to Monitor mode
+        ldr     r1, =_monitor_vector_table
+        mcr     p15, 0, r1, c12, c0, 1
+        // Let the Non-secure world use the FPU (CP10 and CP11) via NSACR
+        mrc     p15, 0, r1, c1, c1, 2
+        orr     r1, r1, #0xC00
+        mcr     p15, 0, r1, c1, c1, 2
+        // Go to Monitor mode (0x16), with IRQ and FIQ masked
+        msr     cpsr_c, #0xD6
+        // Set the MON stack pointer, at the top of the stacks. r0 then points
+        // to the bottom of the MON stack, for the other stacks to go under.
+        mov     sp, r0
+        ldr     r1, =_mon_stack_size
+        sub     r0, r0, r1
+        // Set SCR.NS, and let the Non-secure world mask FIQs and external
+        // aborts (SCR.FW and SCR.AW)
+        mov     r1, #0x31
+        mcr     p15, 0, r1, c1, c1, 0
+        isb
+        // Return to System mode, which is now Non-secure
+        mov     r1, {sys_mode}
+        msr     spsr_cxsf, r1
+        adr     lr, 1f
+        movs    pc, lr
+    1:
+        "#
+    };
+}
+
+/// This macro expands to code to turn on high vectors, so exceptions use the
+/// vector table at 0xFFFF0000
+#[cfg(all(target_arch = "arm", feature = "hivecs"))]
+macro_rules! hivecs_enable {
+    () => {
+        r#"
+        // Set SCTLR.V
+        mrc     p15, 0, r0, c1, c0, 0
+        orr     r0, r0, #0x2000
+        mcr     p15, 0, r0, c1, c0, 0
+        isb
+        "#
+    };
+}
+
+/// This macro expands to code that does nothing because we use low vectors
+#[cfg(all(target_arch = "arm", not(feature = "hivecs")))]
+macro_rules! hivecs_enable {
+    () => {
+        r#"
+        // low vectors - do nothing
+        "#
+    };
+}
+
+/// This macro expands to code that does nothing because we're already in
+/// the right security state
+#[cfg(all(target_arch = "arm", not(feature = "el3-boot")))]
+macro_rules! el3_boot {
+    () => {
+        r#"
+        // no EL3 set-up - do nothing
+        "#
+    };
+}
+
+// A Monitor vector table which spins on every exception
+#[cfg(all(target_arch = "arm", feature = "el3-boot"))]
+core::arch::global_asm!(
+    r#"
+    .section .text._default_monitor_vector_table
+    .align 5
+    .global _default_monitor_vector_table
+    .type _default_monitor_vector_table, %function
+    _default_monitor_vector_table:
+        b       .
+        b       .
+        ldr     pc, =_asm_smc_handler
+        b       .
+        b       .
+        b       .
+        b       .
+        b       .
+    .size _default_monitor_vector_table, . - _default_monitor_vector_table
+
+    .section .text._asm_default_smc_handler
+
+    // Called from the Monitor vector table when we have a Secure Monitor Call.
+    // Saves state and calls a C-compatible handler like
+    // `extern "C" fn _smc_handler(arg: u32, frame: *mut ExceptionFrame);`
+    .global _asm_default_smc_handler
+    .type _asm_default_smc_handler, %function
+    _asm_default_smc_handler:
+        srsfd   sp!, #{mon_mode}
+    "#,
+    save_context!(),
+    r#"
+        ldr     r0, [r12]                 // Pass the caller's R0
+        mov     r1, r12                   // Pass the exception frame
+        bl      _smc_handler
+    "#,
+    restore_context!(),
+    r#"
+        rfefd   sp!
+    .size _asm_default_smc_handler, . - _asm_default_smc_handler
+    "#,
+    mon_mode = const ProcessorMode::Mon as u8,
+);
+
+/// This macro expands to code to keep hold of r0-r2 and CPSR, for the
+/// `BootInfo`
+#[cfg(all(target_arch = "arm", feature = "boot-info"))]
+macro_rules! boot_info_save {
+    () => {
+        r#"
+        // Keep hold of what we were started with, for the BootInfo
+        mov     r4, r0
+        mov     r5, r1
+        mov     r6, r2
+        mrs     r7, cpsr
+        "#
+    };
+}
+
+/// This macro expands to code that does nothing because there is no
+/// `BootInfo`
+#[cfg(all(target_arch = "arm", not(feature = "boot-info")))]
+macro_rules! boot_info_save {
+    () => {
+        r#"
+        // no BootInfo - do nothing
+        "#
+    };
+}
+
+/// This macro expands to code to fill in the `BootInfo`
+#[cfg(all(target_arch = "arm", feature = "boot-info"))]
+macro_rules! boot_info_store {
+    () => {
+        r#"
+        // Fill in the BootInfo
+        ldr     r0, =_boot_info
+        stm     r0, {{r4-r7}}
+        mrc     p15, 0, r1, c0, c0, 5
+        and     r1, r1, #0xFF
+        str     r1, [r0, #16]
+        ldr     r1, =_stack_top
+        str     r1, [r0, #20]
+        str     sp, [r0, #24]
+        "#
+    };
+}
+
+/// This macro expands to code that does nothing because there is no
+/// `BootInfo`
+#[cfg(all(target_arch = "arm", not(feature = "boot-info")))]
+macro_rules! boot_info_store {
+    () => {
+        r#"
+        // no BootInfo - do nothing
+        "#
+    };
+}
+
+/// This macro expands to code to pass the `BootInfo` to `kmain`
+#[cfg(all(target_arch = "arm", feature = "boot-info"))]
+macro_rules! boot_info_arg {
+    () => {
+        r#"
+        // Pass the BootInfo to kmain
+        ldr     r0, =_boot_info
+        "#
+    };
+}
+
+/// This macro expands to code that does nothing because there is no
+/// `BootInfo`
+#[cfg(all(target_arch = "arm", not(feature = "boot-info")))]
+macro_rules! boot_info_arg {
+    () => {
+        r#"
+        // no BootInfo - do nothing
+        "#
+    };
+}
+
 // Default start-up code for Armv7-A
 //
 // We set up our stacks and `kmain` in system mode.
@@ -876,43 +1885,22 @@ core::arch::global_asm!(
     .global _default_start
     .type _default_start, %function
     _default_start:
-        // Set up stacks.
+    "#,
+    boot_info_save!(),
+    boot_ticks_start!(),
+    r#"
         ldr     r0, =_stack_top
-        // Set stack pointer (right after) and mask interrupts for for UND mode (Mode 0x1B)
-        msr     cpsr, {und_mode}
-        mov     sp, r0
-        ldr     r1, =_und_stack_size
-        sub     r0, r0, r1
-        // Set stack pointer (right after) and mask interrupts for for SVC mode (Mode 0x13)
-        msr     cpsr, {svc_mode}
-        mov     sp, r0
-        ldr     r1, =_svc_stack_size
-        sub     r0, r0, r1
-        // Set stack pointer (right after) and mask interrupts for for ABT mode (Mode 0x17)
-        msr     cpsr, {abt_mode}
-        mov     sp, r0
-        ldr     r1, =_abt_stack_size
-        sub     r0, r0, r1
-        // Set stack pointer (right after) and mask interrupts for for IRQ mode (Mode 0x12)
-        msr     cpsr, {irq_mode}
-        mov     sp, r0
-        ldr     r1, =_irq_stack_size
-        sub     r0, r0, r1
-        // Set stack pointer (right after) and mask interrupts for for FIQ mode (Mode 0x11)
-        msr     cpsr, {fiq_mode}
-        mov     sp, r0
-        ldr     r1, =_fiq_stack_size
-        sub     r0, r0, r1
-        // Set stack pointer (right after) and mask interrupts for for System mode (Mode 0x1F)
-        msr     cpsr, {sys_mode}
-        mov     sp, r0
-        // Clear the Thumb Exception bit because we're in Arm mode
-        mrc     p15, 0, r0, c1, c0, 0
-        bic     r0, #{te_bit}
-        mcr     p15, 0, r0, c1, c0, 0
     "#,
+    el3_boot!(),
+    r#"
+        // Set up stacks. r0 points to the top of them.
+        bl      _stack_setup
+        bl      _post_stack_setup
+    "#,
+    hivecs_enable!(),
     fpu_enable!(),
     r#"
+        bl      _post_fpu_enable
         // Initialise .bss
         ldr     r0, =__sbss
         ldr     r1, =__ebss
@@ -923,6 +1911,7 @@ core::arch::global_asm!(
         stm     r0!, {{r2}}
         b       0b
     1:
+        bl      _post_bss_init
         // Initialise .data
         ldr     r0, =__sdata
         ldr     r1, =__edata
@@ -934,6 +1923,12 @@ core::arch::global_asm!(
         stm     r0!, {{r3}}
         b       0b
     1:
+        bl      _post_data_init
+        bl      _pre_kmain
+    "#,
+    boot_ticks_end!(),
+    boot_info_store!(),
+    r#"
         // Zero all registers before calling kmain
         mov     r0, 0
         mov     r1, 0
@@ -948,11 +1943,67 @@ core::arch::global_asm!(
         mov     r10, 0
         mov     r11, 0
         mov     r12, 0
+    "#,
+    boot_info_arg!(),
+    r#"
         // Jump to application
         bl      kmain
         // In case the application returns, loop forever
         b       .
     .size _default_start, . - _default_start
+
+    // The default start-up hook, which does nothing
+    .section .text._default_hook
+    .global _default_hook
+    .type _default_hook, %function
+    _default_hook:
+        bx      lr
+    .size _default_hook, . - _default_hook
+
+    // Configure a stack for every mode. Leaves you in sys mode.
+    //
+    // Pass in stack top in r0.
+    .section .text._stack_setup
+    .global _stack_setup
+    .type _stack_setup, %function
+    _stack_setup:
+        // Save LR from whatever mode we're currently in
+        mov     r2, lr
+        // (we might not be in the same mode when we return).
+        // Set stack pointer (right after) and mask interrupts for for UND mode (Mode 0x1B)
+        msr     cpsr, {und_mode}
+        mov     sp, r0
+        ldr     r1, =_und_stack_size
+        sub     r0, r0, r1
+        // Set stack pointer (right after) and mask interrupts for for SVC mode (Mode 0x13)
+        msr     cpsr, {svc_mode}
+        mov     sp, r0
+        ldr     r1, =_svc_stack_size
+        sub     r0, r0, r1
+        // Set stack pointer (right after) and mask interrupts for for ABT mode (Mode 0x17)
+        msr     cpsr, {abt_mode}
+        mov     sp, r0
+        ldr     r1, =_abt_stack_size
+        sub     r0, r0, r1
+        // Set stack pointer (right after) and mask interrupts for for IRQ mode (Mode 0x12)
+        msr     cpsr, {irq_mode}
+        mov     sp, r0
+        ldr     r1, =_irq_stack_size
+        sub     r0, r0, r1
+        // Set stack pointer (right after) and mask interrupts for for FIQ mode (Mode 0x11)
+        msr     cpsr, {fiq_mode}
+        mov     sp, r0
+        ldr     r1, =_fiq_stack_size
+        sub     r0, r0, r1
+        // Set stack pointer (right after) and mask interrupts for for System mode (Mode 0x1F)
+        msr     cpsr, {sys_mode}
+        mov     sp, r0
+        // Clear the Thumb Exception bit because we're in Arm mode
+        mrc     p15, 0, r1, c1, c0, 0
+        bic     r1, #{te_bit}
+        mcr     p15, 0, r1, c1, c0, 0
+        bx      r2
+    .size _stack_setup, . - _stack_setup
     "#,
     und_mode = const {
         Cpsr::new_with_raw_value(0)
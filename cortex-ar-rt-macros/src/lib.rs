@@ -1,6 +1,7 @@
 //! Macros for the cortex-a-rt and cortex-r-rt libraries
 //!
-//! Provides `#[entry]`, `#[exception(...)]` and `#[irq]` attribute macros.
+//! Provides `#[entry]`, `#[exception(...)]`, `#[irq]` and `#[interrupt(...)]`
+//! attribute macros.
 //!
 //! Do not use this crate directly.
 //!
@@ -12,8 +13,8 @@ use proc_macro::{TokenStream, TokenTree};
 use proc_macro2::Span;
 use quote::quote;
 use syn::{
-    parse, parse_macro_input, spanned::Spanned, AttrStyle, Attribute, Ident, ItemFn, ReturnType,
-    Type, Visibility,
+    parse, parse::Parser, parse_macro_input, spanned::Spanned, AttrStyle, Attribute, FnArg, Ident,
+    ItemFn, LitInt, ReturnType, Token, Type, Visibility,
 };
 
 /// Creates an `unsafe` program entry point (i.e. a `kmain` function).
@@ -47,56 +48,151 @@ use syn::{
 /// The symbol `kmain` is what the assembly code in both the cortex-r-rt and
 /// cortex-a-rt start-up code will jump to, and the `extern "C"` makes it sound
 /// to call from assembly.
+///
+/// The function may also be an `unsafe fn`, in which case it is called from
+/// within an `unsafe` block.
+///
+/// If your function returns `()` instead of `!`, the entry point spins in a
+/// loop after your function returns.
+///
+/// On a multi-core system, you can give the index of the core that should run
+/// the function. Core 0 gets `kmain`, core 1 gets `kmain2`, core 2 gets
+/// `kmain3`, and so on. Your start-up code must call the appropriate symbol on
+/// each core.
+///
+/// ```rust ignore
+/// #[entry(core = 1)]
+/// fn core1_main() -> ! {
+///     loop {}
+/// }
+/// ```
+///
+/// If you enable the `boot-info` feature in the run-time crate, the start-up
+/// code passes `kmain` a reference to a `BootInfo`, and your function can take
+/// it as an argument. Only the function for core 0 can do this.
+///
+/// ```rust ignore
+/// #[entry]
+/// fn foo(info: &BootInfo) -> ! {
+///     loop {}
+/// }
+/// ```
 #[proc_macro_attribute]
 pub fn entry(args: TokenStream, input: TokenStream) -> TokenStream {
     let f = parse_macro_input!(input as ItemFn);
 
+    // is the function `-> !`, or does it return `()`?
+    let diverges = match f.sig.output {
+        ReturnType::Default => Some(false),
+        ReturnType::Type(_, ref ty) => match **ty {
+            Type::Never(_) => Some(true),
+            Type::Tuple(ref tuple) if tuple.elems.is_empty() => Some(false),
+            _ => None,
+        },
+    };
+
     // check the function signature.
     //
-    // it should be `fn foo() -> !` or `unsafe fn foo() -> !`
+    // it should be `fn foo() -> !`, `fn foo()`, or the `unsafe fn` versions,
+    // optionally taking a `&BootInfo`
+    let info_type = match f.sig.inputs.first() {
+        Some(FnArg::Typed(arg)) => {
+            if !is_boot_info_ref(&arg.ty) {
+                return parse::Error::new(
+                    arg.ty.span(),
+                    "the argument to an `#[entry]` function must be a `&BootInfo`",
+                )
+                .to_compile_error()
+                .into();
+            }
+            Some((*arg.ty).clone())
+        }
+        _ => None,
+    };
     let valid_signature = f.sig.constness.is_none()
         && f.vis == Visibility::Inherited
         && f.sig.abi.is_none()
-        && f.sig.inputs.is_empty()
+        && (f.sig.inputs.is_empty() || (f.sig.inputs.len() == 1 && info_type.is_some()))
         && f.sig.generics.params.is_empty()
         && f.sig.generics.where_clause.is_none()
         && f.sig.variadic.is_none()
-        && match f.sig.output {
-            ReturnType::Default => false,
-            ReturnType::Type(_, ref ty) => matches!(**ty, Type::Never(_)),
-        };
+        && diverges.is_some();
 
     if !valid_signature {
         return parse::Error::new(
             f.span(),
-            "`#[entry]` function must have signature `[unsafe] fn() -> !`",
+            "`#[entry]` function must have signature `[unsafe] fn([&BootInfo]) -> !` or `[unsafe] fn([&BootInfo])`",
         )
         .to_compile_error()
         .into();
     }
 
-    if !args.is_empty() {
-        return parse::Error::new(Span::call_site(), "This attribute accepts no arguments")
-            .to_compile_error()
-            .into();
+    let core = if args.is_empty() {
+        0
+    } else {
+        match parse_core_index(args) {
+            Ok(core) => core,
+            Err(error) => return error.to_compile_error().into(),
+        }
+    };
+
+    // only the start-up code for core 0 passes a `BootInfo`
+    if core != 0 && info_type.is_some() {
+        return parse::Error::new(
+            f.sig.inputs.span(),
+            "only the `#[entry]` function for core 0 can take a `&BootInfo`",
+        )
+        .to_compile_error()
+        .into();
     }
 
-    let tramp_ident = Ident::new("__cortex_ar_rt_kmain", Span::call_site());
-    let ident = &f.sig.ident;
+    // Core 0 runs `kmain`, core 1 runs `kmain2`, etc
+    let export_name = if core == 0 {
+        String::from("kmain")
+    } else {
+        format!("kmain{}", core + 1)
+    };
+    let tramp_ident = Ident::new(
+        &format!("__cortex_ar_rt_{}", export_name),
+        Span::call_site(),
+    );
 
     if let Err(error) = check_attr_whitelist(&f.attrs, Kind::Entry) {
         return error;
     }
 
     let (ref cfgs, ref attrs) = extract_cfgs(f.attrs.clone());
+    let (info_param, call) = match info_type {
+        Some(ty) => {
+            let ident = &f.sig.ident;
+            let call = if f.sig.unsafety.is_some() {
+                quote!(unsafe { #ident(info) })
+            } else {
+                quote!(#ident(info))
+            };
+            (quote!(info: #ty), call)
+        }
+        None => (quote!(), call_without_args(&f)),
+    };
+    let body = if diverges == Some(true) {
+        call
+    } else {
+        // nothing to return to, so wait here once main is done
+        quote!(
+            #call;
+            loop {
+                core::hint::spin_loop();
+            }
+        )
+    };
 
     quote!(
         #(#cfgs)*
         #(#attrs)*
         #[doc(hidden)]
-        #[export_name = "kmain"]
-        pub unsafe extern "C" fn #tramp_ident() -> ! {
-            #ident()
+        #[export_name = #export_name]
+        pub unsafe extern "C" fn #tramp_ident(#info_param) -> ! {
+            #body
         }
 
         #f
@@ -161,6 +257,28 @@ impl std::fmt::Display for Exception {
 /// * PrefetchAbort (creates `_prefetch_abort_handler`)
 /// * DataAbort (creates `_data_abort_handler`)
 /// * Irq (creates `_irq_handler`) - although people should prefer `#[irq]`.
+///
+/// Apart from Irq, each handler can also take the registers saved on exception
+/// entry as a second argument:
+///
+/// ```rust ignore
+/// #[exception(SupervisorCall)]
+/// fn svc_handler(arg: u32, frame: &mut ExceptionFrame) {
+///     // return a value to the caller in R0
+///     frame.r0 = arg;
+/// }
+/// ```
+///
+/// A DataAbort handler can take a `cortex_ar::fault::FaultInfo` instead of
+/// the faulting address. The generated function captures DFSR and DFAR before
+/// your handler is called:
+///
+/// ```rust ignore
+/// #[exception(DataAbort)]
+/// fn data_abort_handler(info: FaultInfo) -> ! {
+///     panic!("Data Abort at {:#x} accessing {:#x}", info.addr, info.dfar);
+/// }
+/// ```
 #[proc_macro_attribute]
 pub fn exception(args: TokenStream, input: TokenStream) -> TokenStream {
     handle_exception_interrupt(args, input, Kind::Exception)
@@ -175,8 +293,8 @@ pub fn exception(args: TokenStream, input: TokenStream) -> TokenStream {
 ///
 /// ```rust ignore
 /// #[irq]
-/// fn foo(addr: usize) -> ! {
-///     panic!("On no")
+/// fn foo() {
+///     // talk to the interrupt controller
 /// }
 /// ```
 ///
@@ -185,15 +303,18 @@ pub fn exception(args: TokenStream, input: TokenStream) -> TokenStream {
 /// ```rust
 /// #[doc(hidden)]
 /// #[export_name = "_irq_handler"]
-/// pub unsafe extern "C" fn __cortex_ar_rt_irq_handler(addr: usize) -> ! {
-///     foo(addr)
+/// pub unsafe extern "C" fn __cortex_ar_rt_irq_handler() {
+///     foo()
 /// }
 ///
-/// fn foo(addr: usize) -> ! {
-///     panic!("On no")
+/// fn foo() {
+///     // talk to the interrupt controller
 /// }
 /// ```
 ///
+/// The function may also be an `unsafe fn`, in which case it is called from
+/// within an `unsafe` block.
+///
 /// This is preferred over `#[exception(Irq)` because most people
 /// probably won't consider interrupts to be a form of exception.
 #[proc_macro_attribute]
@@ -201,7 +322,193 @@ pub fn irq(args: TokenStream, input: TokenStream) -> TokenStream {
     handle_exception_interrupt(args, input, Kind::Interrupt)
 }
 
-/// Note if we got `#[entry]`, `#[exception(...)]` or `#[irq]`
+/// Creates an `unsafe` handler for one specific interrupt.
+///
+/// It's `unsafe` because you are not supposed to call it - it should only be
+/// called by the interrupt dispatcher in cortex-r-rt or cortex-a-rt.
+///
+/// The argument gives the GIC interrupt ID, either as a Software Generated
+/// Interrupt (`SGI = 0..=15`, INTID 0 to 15), a Private Peripheral Interrupt
+/// (`PPI = 0..=15`, INTID 16 to 31) or a Shared Peripheral Interrupt (`SPI =
+/// 0..=987`, INTID 32 to 1019).
+///
+/// When placed on a function like:
+///
+/// ```rust ignore
+/// #[interrupt(SPI = 42)]
+/// fn uart0() {
+///     // handle the interrupt
+/// }
+/// ```
+///
+/// You get something like:
+///
+/// ```rust
+/// #[doc(hidden)]
+/// #[export_name = "__cortex_ar_rt_interrupt_74"]
+/// pub unsafe extern "C" fn __cortex_ar_rt_interrupt_uart0() {
+///     uart0()
+/// }
+///
+/// const _: () = {
+///     #[repr(C)]
+///     struct InterruptHandler {
+///         id: u32,
+///         handler: unsafe extern "C" fn(),
+///     }
+///
+///     #[used]
+///     #[link_section = ".irq_dispatch"]
+///     static HANDLER: InterruptHandler = InterruptHandler {
+///         id: 74,
+///         handler: __cortex_ar_rt_interrupt_uart0,
+///     };
+/// };
+///
+/// fn uart0() {
+///     // handle the interrupt
+/// }
+/// ```
+///
+/// The linker script collects every entry in the `.irq_dispatch` section into
+/// a table, which `dispatch_interrupts` searches after acknowledging an
+/// interrupt. The exported symbol is only there so that two handlers for the
+/// same interrupt ID cause a link error.
+#[proc_macro_attribute]
+pub fn interrupt(args: TokenStream, input: TokenStream) -> TokenStream {
+    let f = parse_macro_input!(input as ItemFn);
+
+    // check the function signature.
+    //
+    // it should be `fn foo()` or `unsafe fn foo()`
+    let valid_signature = f.sig.constness.is_none()
+        && f.vis == Visibility::Inherited
+        && f.sig.abi.is_none()
+        && f.sig.inputs.is_empty()
+        && f.sig.generics.params.is_empty()
+        && f.sig.generics.where_clause.is_none()
+        && f.sig.variadic.is_none()
+        && match f.sig.output {
+            ReturnType::Default => true,
+            ReturnType::Type(_, ref ty) => match **ty {
+                Type::Tuple(ref tuple) => tuple.elems.is_empty(),
+                _ => false,
+            },
+        };
+
+    if !valid_signature {
+        return parse::Error::new(
+            f.span(),
+            "`#[interrupt]` function must have signature `[unsafe] fn()`",
+        )
+        .to_compile_error()
+        .into();
+    }
+
+    let intid = match parse_interrupt_id(args) {
+        Ok(intid) => intid,
+        Err(error) => return error.to_compile_error().into(),
+    };
+
+    if let Err(error) = check_attr_whitelist(&f.attrs, Kind::Vectored) {
+        return error;
+    }
+
+    let ident = &f.sig.ident;
+    let tramp_ident = Ident::new(
+        &format!("__cortex_ar_rt_interrupt_{}", ident),
+        Span::call_site(),
+    );
+    let export_name = format!("__cortex_ar_rt_interrupt_{}", intid);
+    let (ref cfgs, ref attrs) = extract_cfgs(f.attrs.clone());
+    let call = call_without_args(&f);
+
+    quote!(
+        #(#cfgs)*
+        #(#attrs)*
+        #[doc(hidden)]
+        #[export_name = #export_name]
+        pub unsafe extern "C" fn #tramp_ident() {
+            #call
+        }
+
+        #(#cfgs)*
+        const _: () = {
+            #[repr(C)]
+            #[allow(dead_code)]
+            struct InterruptHandler {
+                id: u32,
+                handler: unsafe extern "C" fn(),
+            }
+
+            #[used]
+            #[link_section = ".irq_dispatch"]
+            static HANDLER: InterruptHandler = InterruptHandler {
+                id: #intid,
+                handler: #tramp_ident,
+            };
+        };
+
+        #f
+    )
+    .into()
+}
+
+/// Get the `n` from `core = n`
+fn parse_core_index(args: TokenStream) -> Result<u32, parse::Error> {
+    let parser = |input: parse::ParseStream| {
+        let name: Ident = input.parse()?;
+        if name != "core" {
+            return Err(parse::Error::new(name.span(), "expected `core`"));
+        }
+        input.parse::<Token![=]>()?;
+        input.parse::<LitInt>()
+    };
+    let number = parser.parse(args).map_err(|e| {
+        parse::Error::new(
+            e.span(),
+            "This attribute accepts either no arguments, or a core index like `core = 1`",
+        )
+    })?;
+    number.base10_parse()
+}
+
+/// Convert `SGI = n`, `PPI = n` or `SPI = n` into a GIC interrupt ID
+fn parse_interrupt_id(args: TokenStream) -> Result<u32, parse::Error> {
+    let parser = |input: parse::ParseStream| {
+        let kind: Ident = input.parse()?;
+        input.parse::<Token![=]>()?;
+        let number: LitInt = input.parse()?;
+        Ok((kind, number))
+    };
+    let (kind, number) = parser.parse(args).map_err(|e| {
+        parse::Error::new(
+            e.span(),
+            "This attribute requires an argument like `SPI = 42`, `PPI = 14` or `SGI = 0`",
+        )
+    })?;
+    let value: u32 = number.base10_parse()?;
+    let (offset, limit) = match kind.to_string().as_str() {
+        "SGI" => (0, 16),
+        "PPI" => (16, 16),
+        "SPI" => (32, 988),
+        _ => {
+            return Err(parse::Error::new(
+                kind.span(),
+                "Interrupt type must be one of `SGI`, `PPI` or `SPI`",
+            ));
+        }
+    };
+    if value >= limit {
+        return Err(parse::Error::new(
+            number.span(),
+            format!("{} number must be less than {}", kind, limit),
+        ));
+    }
+    Ok(offset + value)
+}
+
+/// Note if we got `#[entry]`, `#[exception(...)]`, `#[irq]` or `#[interrupt(...)]`
 #[derive(PartialEq, Eq, Clone, Copy, Debug)]
 enum Kind {
     /// Corresponds to `#[entry]`
@@ -210,6 +517,8 @@ enum Kind {
     Exception,
     /// Corresponds to `#[irq]`
     Interrupt,
+    /// Corresponds to `#[interrupt(...)]`
+    Vectored,
 }
 
 /// A common routine for handling exception or interrupt functions
@@ -226,8 +535,8 @@ fn handle_exception_interrupt(args: TokenStream, input: TokenStream, kind: Kind)
     };
 
     let exception = match kind {
-        Kind::Entry => {
-            panic!("Don't handle #[entry] with `handle_exception_interrupt`!");
+        Kind::Entry | Kind::Vectored => {
+            panic!("Only handle #[exception] and #[irq] with `handle_exception_interrupt`!");
         }
         Kind::Exception => {
             let mut args_iter = args.into_iter();
@@ -296,12 +605,44 @@ fn handle_exception_interrupt(args: TokenStream, input: TokenStream, kind: Kind)
         Kind::Interrupt => Exception::Irq,
     };
 
+    // Exception handlers may take a `&mut ExceptionFrame` as their second
+    // argument. The assembly trampoline always passes the frame pointer in R1.
+    let frame_type = match exception {
+        Exception::Irq => None,
+        _ => match exception_frame_type(&f) {
+            Ok(frame_type) => frame_type,
+            Err(error) => return error,
+        },
+    };
+    let (frame_param, frame_arg, unsafe_frame_arg) = match frame_type {
+        Some(ty) => (
+            quote!(, frame: *mut #ty),
+            quote!(, &mut *frame),
+            quote!(, unsafe { &mut *frame }),
+        ),
+        None => (quote!(), quote!(), quote!()),
+    };
+
+    // Data Abort handlers may take a `FaultInfo` instead of the faulting
+    // address, which we capture before calling them.
+    let fault_info_type = match exception {
+        Exception::DataAbort => match f.sig.inputs.first() {
+            Some(FnArg::Typed(arg)) if !is_usize(&arg.ty) => Some((*arg.ty).clone()),
+            _ => None,
+        },
+        _ => None,
+    };
+    let first_arg = match fault_info_type {
+        Some(ty) => quote!(<#ty>::capture(addr)),
+        None => quote!(addr),
+    };
+
     let ident = &f.sig.ident;
     let (ref cfgs, ref attrs) = extract_cfgs(f.attrs.clone());
 
     let handler = match exception {
-        // extern "C" fn _undefined_handler(addr: usize) -> !;
-        // unsafe extern "C" fn _undefined_handler(addr: usize) -> usize;
+        // extern "C" fn _undefined_handler(addr: usize, frame: *mut ExceptionFrame) -> !;
+        // unsafe extern "C" fn _undefined_handler(addr: usize, frame: *mut ExceptionFrame) -> usize;
         Exception::Undefined => {
             let tramp_ident = Ident::new("__cortex_ar_rt_undefined_handler", Span::call_site());
             if returns_never {
@@ -310,8 +651,8 @@ fn handle_exception_interrupt(args: TokenStream, input: TokenStream, kind: Kind)
                     #(#attrs)*
                     #[doc(hidden)]
                     #[export_name = "_undefined_handler"]
-                    pub unsafe extern "C" fn #tramp_ident(addr: usize) -> ! {
-                        #ident(addr)
+                    pub unsafe extern "C" fn #tramp_ident(addr: usize #frame_param) -> ! {
+                        #ident(addr #unsafe_frame_arg)
                     }
 
                     #f
@@ -322,9 +663,9 @@ fn handle_exception_interrupt(args: TokenStream, input: TokenStream, kind: Kind)
                     #(#attrs)*
                     #[doc(hidden)]
                     #[export_name = "_undefined_handler"]
-                    pub unsafe extern "C" fn #tramp_ident(addr: usize) -> usize {
+                    pub unsafe extern "C" fn #tramp_ident(addr: usize #frame_param) -> usize {
                         unsafe {
-                            #ident(addr)
+                            #ident(addr #frame_arg)
                         }
                     }
 
@@ -332,8 +673,8 @@ fn handle_exception_interrupt(args: TokenStream, input: TokenStream, kind: Kind)
                 )
             }
         }
-        // extern "C" fn _prefetch_abort_handler(addr: usize) -> !;
-        // unsafe extern "C" fn _prefetch_abort_handler(addr: usize) -> usize;
+        // extern "C" fn _prefetch_abort_handler(addr: usize, frame: *mut ExceptionFrame) -> !;
+        // unsafe extern "C" fn _prefetch_abort_handler(addr: usize, frame: *mut ExceptionFrame) -> usize;
         Exception::PrefetchAbort => {
             let tramp_ident =
                 Ident::new("__cortex_ar_rt_prefetch_abort_handler", Span::call_site());
@@ -343,8 +684,8 @@ fn handle_exception_interrupt(args: TokenStream, input: TokenStream, kind: Kind)
                     #(#attrs)*
                     #[doc(hidden)]
                     #[export_name = "_prefetch_abort_handler"]
-                    pub unsafe extern "C" fn #tramp_ident(addr: usize) -> ! {
-                        #ident(addr)
+                    pub unsafe extern "C" fn #tramp_ident(addr: usize #frame_param) -> ! {
+                        #ident(addr #unsafe_frame_arg)
                     }
 
                     #f
@@ -355,9 +696,9 @@ fn handle_exception_interrupt(args: TokenStream, input: TokenStream, kind: Kind)
                     #(#attrs)*
                     #[doc(hidden)]
                     #[export_name = "_prefetch_abort_handler"]
-                    pub unsafe extern "C" fn #tramp_ident(addr: usize) -> usize {
+                    pub unsafe extern "C" fn #tramp_ident(addr: usize #frame_param) -> usize {
                         unsafe {
-                            #ident(addr)
+                            #ident(addr #frame_arg)
                         }
                     }
 
@@ -365,8 +706,8 @@ fn handle_exception_interrupt(args: TokenStream, input: TokenStream, kind: Kind)
                 )
             }
         }
-        // extern "C" fn _data_abort_handler(addr: usize) -> !;
-        // unsafe extern "C" fn _data_abort_handler(addr: usize) -> usize;
+        // extern "C" fn _data_abort_handler(addr: usize, frame: *mut ExceptionFrame) -> !;
+        // unsafe extern "C" fn _data_abort_handler(addr: usize, frame: *mut ExceptionFrame) -> usize;
         Exception::DataAbort => {
             let tramp_ident = Ident::new("__cortex_ar_rt_data_abort_handler", Span::call_site());
             if returns_never {
@@ -375,8 +716,8 @@ fn handle_exception_interrupt(args: TokenStream, input: TokenStream, kind: Kind)
                     #(#attrs)*
                     #[doc(hidden)]
                     #[export_name = "_data_abort_handler"]
-                    pub unsafe extern "C" fn #tramp_ident(addr: usize) -> ! {
-                        #ident(addr)
+                    pub unsafe extern "C" fn #tramp_ident(addr: usize #frame_param) -> ! {
+                        #ident(#first_arg #unsafe_frame_arg)
                     }
 
                     #f
@@ -387,9 +728,9 @@ fn handle_exception_interrupt(args: TokenStream, input: TokenStream, kind: Kind)
                     #(#attrs)*
                     #[doc(hidden)]
                     #[export_name = "_data_abort_handler"]
-                    pub unsafe extern "C" fn #tramp_ident(addr: usize) -> usize {
+                    pub unsafe extern "C" fn #tramp_ident(addr: usize #frame_param) -> usize {
                         unsafe {
-                            #ident(addr)
+                            #ident(#first_arg #frame_arg)
                         }
                     }
 
@@ -397,7 +738,7 @@ fn handle_exception_interrupt(args: TokenStream, input: TokenStream, kind: Kind)
                 )
             }
         }
-        // extern "C" fn _svc_handler(addr: usize);
+        // extern "C" fn _svc_handler(arg: u32, frame: *mut ExceptionFrame);
         Exception::SupervisorCall => {
             let tramp_ident = Ident::new("__cortex_ar_rt_svc_handler", Span::call_site());
             quote!(
@@ -405,8 +746,8 @@ fn handle_exception_interrupt(args: TokenStream, input: TokenStream, kind: Kind)
                 #(#attrs)*
                 #[doc(hidden)]
                 #[export_name = "_svc_handler"]
-                pub unsafe extern "C" fn #tramp_ident(arg: u32) {
-                    #ident(arg)
+                pub unsafe extern "C" fn #tramp_ident(arg: u32 #frame_param) {
+                    #ident(arg #unsafe_frame_arg)
                 }
 
                 #f
@@ -415,13 +756,14 @@ fn handle_exception_interrupt(args: TokenStream, input: TokenStream, kind: Kind)
         // extern "C" fn _irq_handler(addr: usize);
         Exception::Irq => {
             let tramp_ident = Ident::new("__cortex_ar_rt_irq_handler", Span::call_site());
+            let call = call_without_args(&f);
             quote!(
                 #(#cfgs)*
                 #(#attrs)*
                 #[doc(hidden)]
                 #[export_name = "_irq_handler"]
                 pub unsafe extern "C" fn #tramp_ident() {
-                    #ident()
+                    #call
                 }
 
                 #f
@@ -435,6 +777,73 @@ fn handle_exception_interrupt(args: TokenStream, input: TokenStream, kind: Kind)
     .into()
 }
 
+/// Get the type of the optional `&mut ExceptionFrame` second argument.
+///
+/// Returns `Ok(None)` if the handler only takes one argument.
+fn exception_frame_type(f: &ItemFn) -> Result<Option<Type>, TokenStream> {
+    match f.sig.inputs.len() {
+        0 | 1 => Ok(None),
+        2 => {
+            if let Some(FnArg::Typed(arg)) = f.sig.inputs.iter().nth(1) {
+                if let Type::Reference(ref reference) = *arg.ty {
+                    if reference.mutability.is_some() {
+                        return Ok(Some((*reference.elem).clone()));
+                    }
+                }
+            }
+            Err(parse::Error::new(
+                f.sig.inputs.span(),
+                "The second argument of an exception handler must be `&mut ExceptionFrame`",
+            )
+            .to_compile_error()
+            .into())
+        }
+        _ => Err(parse::Error::new(
+            f.sig.inputs.span(),
+            "Exception handlers take at most two arguments",
+        )
+        .to_compile_error()
+        .into()),
+    }
+}
+
+/// Generate a call to a function that takes no arguments.
+///
+/// If the function is an `unsafe fn`, the call is wrapped in an `unsafe` block.
+fn call_without_args(f: &ItemFn) -> proc_macro2::TokenStream {
+    let ident = &f.sig.ident;
+    if f.sig.unsafety.is_some() {
+        quote!(unsafe { #ident() })
+    } else {
+        quote!(#ident())
+    }
+}
+
+/// Is this type `usize`?
+fn is_usize(ty: &Type) -> bool {
+    matches!(ty, Type::Path(ref path) if path.path.is_ident("usize"))
+}
+
+/// Is this type `&BootInfo`?
+///
+/// Any path ending in `BootInfo` will do, so `&cortex_a_rt::BootInfo` is fine.
+fn is_boot_info_ref(ty: &Type) -> bool {
+    let Type::Reference(ref reference) = ty else {
+        return false;
+    };
+    if reference.mutability.is_some() {
+        return false;
+    }
+    match *reference.elem {
+        Type::Path(ref path) if path.qself.is_none() => path
+            .path
+            .segments
+            .last()
+            .is_some_and(|segment| segment.ident == "BootInfo" && segment.arguments.is_none()),
+        _ => false,
+    }
+}
+
 /// Given a list of attributes, split them into `cfg` and non-`cfg`.
 ///
 /// Returns `(cfgs, non_cfgs)`.
@@ -482,7 +891,7 @@ fn check_attr_whitelist(attrs: &[Attribute], caller: Kind) -> Result<(), TokenSt
             Kind::Exception => {
                 "this attribute is not allowed on an exception handler controlled by cortex-r-rt/cortex-a-rt"
             }
-            Kind::Interrupt => {
+            Kind::Interrupt | Kind::Vectored => {
                 "this attribute is not allowed on an interrupt handler controlled by cortex-r-rt/cortex-a-rt"
             }
         };
@@ -0,0 +1,177 @@
+//! Types for physical and virtual addresses
+//!
+//! With the MMU on, the address the processor uses (the virtual address) may
+//! not be the address that goes out on the bus (the physical address). The
+//! translation tables, the MPU and any DMA engine want physical addresses,
+//! whilst cache maintenance by address wants virtual addresses. Using a
+//! [`PhysAddr`] or a [`VirtAddr`] instead of a bare integer makes it a type
+//! error to pass one where the other is expected.
+//!
+//! On a system with an MPU instead of an MMU, the two are always the same, but
+//! we still use the types to say which one an API expects.
+//!
+//! ```rust ignore
+//! use cortex_ar::addr::{PhysAddr, VirtAddr};
+//!
+//! let buffer = VirtAddr::from_ptr(&raw const BUFFER);
+//! cortex_ar::cache::clean_dcache_by_range(buffer, 128);
+//!
+//! let section = PhysAddr::new(0x8012_3456).align_down(0x10_0000);
+//! assert_eq!(section, PhysAddr::new(0x8010_0000));
+//! ```
+
+macro_rules! address {
+    ($name:ident, $doc:literal) => {
+        #[doc = $doc]
+        #[derive(Copy, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+        #[repr(transparent)]
+        pub struct $name(usize);
+
+        impl $name {
+            /// Make an address from an integer
+            pub const fn new(addr: usize) -> $name {
+                $name(addr)
+            }
+
+            /// Get the address as an integer
+            pub const fn as_usize(self) -> usize {
+                self.0
+            }
+
+            /// Get the address as a `u32`, for writing to a register
+            pub const fn as_u32(self) -> u32 {
+                self.0 as u32
+            }
+
+            /// Is this address a multiple of `align`?
+            ///
+            /// `align` must be a power of two.
+            pub const fn is_aligned(self, align: usize) -> bool {
+                self.0 & (align - 1) == 0
+            }
+
+            /// Round this address down to a multiple of `align`
+            ///
+            /// `align` must be a power of two.
+            pub const fn align_down(self, align: usize) -> $name {
+                $name(self.0 & !(align - 1))
+            }
+
+            /// Round this address up to a multiple of `align`
+            ///
+            /// `align` must be a power of two. Returns `None` if the result
+            /// would not fit.
+            pub const fn align_up(self, align: usize) -> Option<$name> {
+                match self.0.checked_add(align - 1) {
+                    Some(addr) => Some($name(addr & !(align - 1))),
+                    None => None,
+                }
+            }
+
+            /// Get the address `offset` bytes after this one
+            ///
+            /// Returns `None` if the result would not fit.
+            pub const fn checked_add(self, offset: usize) -> Option<$name> {
+                match self.0.checked_add(offset) {
+                    Some(addr) => Some($name(addr)),
+                    None => None,
+                }
+            }
+
+            /// Get the address `offset` bytes before this one
+            ///
+            /// Returns `None` if the result would be below zero.
+            pub const fn checked_sub(self, offset: usize) -> Option<$name> {
+                match self.0.checked_sub(offset) {
+                    Some(addr) => Some($name(addr)),
+                    None => None,
+                }
+            }
+        }
+
+        // Looks the same as a pointer does
+        impl core::fmt::Debug for $name {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                write!(f, "{:#x}", self.0)
+            }
+        }
+
+        impl core::fmt::Display for $name {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                write!(f, "{:#010x}", self.0)
+            }
+        }
+
+        impl core::fmt::LowerHex for $name {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                core::fmt::LowerHex::fmt(&self.0, f)
+            }
+        }
+
+        #[cfg(feature = "defmt")]
+        impl defmt::Format for $name {
+            fn format(&self, f: defmt::Formatter) {
+                defmt::write!(f, "{=usize:#010x}", self.0)
+            }
+        }
+    };
+}
+
+address!(
+    PhysAddr,
+    "A physical address, as seen on the bus by memory, peripherals and DMA engines"
+);
+address!(
+    VirtAddr,
+    "A virtual address, as used by the processor when it runs code"
+);
+
+impl VirtAddr {
+    /// Get the virtual address of a pointer
+    pub fn from_ptr<T: ?Sized>(ptr: *const T) -> VirtAddr {
+        VirtAddr(ptr.cast::<u8>() as usize)
+    }
+
+    /// Get a pointer to this virtual address
+    pub const fn as_ptr<T>(self) -> *const T {
+        self.0 as *const T
+    }
+
+    /// Get a mutable pointer to this virtual address
+    pub const fn as_mut_ptr<T>(self) -> *mut T {
+        self.0 as *mut T
+    }
+}
+
+impl<T: ?Sized> From<*const T> for VirtAddr {
+    fn from(ptr: *const T) -> VirtAddr {
+        VirtAddr::from_ptr(ptr)
+    }
+}
+
+impl<T: ?Sized> From<*mut T> for VirtAddr {
+    fn from(ptr: *mut T) -> VirtAddr {
+        VirtAddr::from_ptr(ptr)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn align() {
+        let addr = PhysAddr::new(0x8012_3456);
+        assert!(!addr.is_aligned(0x10_0000));
+        assert_eq!(addr.align_down(0x10_0000), PhysAddr::new(0x8010_0000));
+        assert_eq!(addr.align_up(0x10_0000), Some(PhysAddr::new(0x8020_0000)));
+        assert!(PhysAddr::new(0x8000_0000).is_aligned(0x10_0000));
+    }
+
+    #[test]
+    fn align_up_overflow() {
+        let addr = VirtAddr::new(usize::MAX);
+        assert_eq!(addr.align_up(64), None);
+        assert_eq!(VirtAddr::new(64).align_up(64), Some(VirtAddr::new(64)));
+    }
+}
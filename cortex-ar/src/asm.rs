@@ -31,6 +31,127 @@ pub fn isb() {
     compiler_fence(Ordering::SeqCst);
 }
 
+/// Data Memory Barrier
+///
+/// Ensures that all explicit memory accesses before this instruction are observed before any
+/// explicit memory accesses after it. Unlike [`dsb`], it doesn't wait for them to complete.
+#[inline]
+pub fn dmb() {
+    compiler_fence(Ordering::SeqCst);
+    unsafe {
+        core::arch::asm!("dmb", options(nostack, preserves_flags));
+    }
+    compiler_fence(Ordering::SeqCst);
+}
+
+/// Which observers a [`dmb_with`] or [`dsb_with`] barrier applies to
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Domain {
+    /// Only this core
+    NonShareable,
+    /// This core and the others in the Inner Shareable domain - usually the
+    /// other cores in the cluster
+    InnerShareable,
+    /// Everything in the Outer Shareable domain - usually DMA-capable
+    /// peripherals too
+    OuterShareable,
+    /// The whole system
+    FullSystem,
+}
+
+/// Which memory accesses a [`dmb_with`] or [`dsb_with`] barrier orders
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Access {
+    /// Loads and stores
+    All,
+    /// Only stores, after stores
+    Stores,
+    /// Loads, before loads and stores (Armv8 only)
+    #[cfg(arm_architecture = "v8-r")]
+    Loads,
+}
+
+/// Emit a barrier instruction with the option that matches the given domain
+/// and access type
+macro_rules! barrier {
+    ($insn:literal, $domain:expr, $access:expr) => {
+        // Safety: Barriers have no side-effects beyond ordering memory accesses
+        unsafe {
+            match ($domain, $access) {
+                (Domain::FullSystem, Access::All) => {
+                    core::arch::asm!(concat!($insn, " sy"), options(nostack, preserves_flags))
+                }
+                (Domain::FullSystem, Access::Stores) => {
+                    core::arch::asm!(concat!($insn, " st"), options(nostack, preserves_flags))
+                }
+                (Domain::OuterShareable, Access::All) => {
+                    core::arch::asm!(concat!($insn, " osh"), options(nostack, preserves_flags))
+                }
+                (Domain::OuterShareable, Access::Stores) => {
+                    core::arch::asm!(concat!($insn, " oshst"), options(nostack, preserves_flags))
+                }
+                (Domain::InnerShareable, Access::All) => {
+                    core::arch::asm!(concat!($insn, " ish"), options(nostack, preserves_flags))
+                }
+                (Domain::InnerShareable, Access::Stores) => {
+                    core::arch::asm!(concat!($insn, " ishst"), options(nostack, preserves_flags))
+                }
+                (Domain::NonShareable, Access::All) => {
+                    core::arch::asm!(concat!($insn, " nsh"), options(nostack, preserves_flags))
+                }
+                (Domain::NonShareable, Access::Stores) => {
+                    core::arch::asm!(concat!($insn, " nshst"), options(nostack, preserves_flags))
+                }
+                #[cfg(arm_architecture = "v8-r")]
+                (Domain::FullSystem, Access::Loads) => {
+                    core::arch::asm!(concat!($insn, " ld"), options(nostack, preserves_flags))
+                }
+                #[cfg(arm_architecture = "v8-r")]
+                (Domain::OuterShareable, Access::Loads) => {
+                    core::arch::asm!(concat!($insn, " oshld"), options(nostack, preserves_flags))
+                }
+                #[cfg(arm_architecture = "v8-r")]
+                (Domain::InnerShareable, Access::Loads) => {
+                    core::arch::asm!(concat!($insn, " ishld"), options(nostack, preserves_flags))
+                }
+                #[cfg(arm_architecture = "v8-r")]
+                (Domain::NonShareable, Access::Loads) => {
+                    core::arch::asm!(concat!($insn, " nshld"), options(nostack, preserves_flags))
+                }
+            }
+        }
+    };
+}
+
+/// Data Memory Barrier, limited to the given domain and access type
+///
+/// Like [`dmb`] (which is `dmb_with(Domain::FullSystem, Access::All)`), but
+/// cheaper when you only need to order some accesses, or only need other
+/// cores to see them in order. For example, a spin-lock shared between the
+/// cores of a cluster only needs `Domain::InnerShareable`.
+///
+/// Processors that don't distinguish between the options (like the
+/// Cortex-R5) treat them all as a full system barrier.
+#[inline]
+pub fn dmb_with(domain: Domain, access: Access) {
+    compiler_fence(Ordering::SeqCst);
+    barrier!("dmb", domain, access);
+    compiler_fence(Ordering::SeqCst);
+}
+
+/// Data Synchronization Barrier, limited to the given domain and access type
+///
+/// Like [`dsb`] (which is `dsb_with(Domain::FullSystem, Access::All)`), but
+/// only waits for the given kind of accesses, as observed by the given domain.
+#[inline]
+pub fn dsb_with(domain: Domain, access: Access) {
+    compiler_fence(Ordering::SeqCst);
+    barrier!("dsb", domain, access);
+    compiler_fence(Ordering::SeqCst);
+}
+
 /// Emit an NOP instruction
 #[inline]
 pub fn nop() {
@@ -57,9 +178,51 @@ pub fn sev() {
     }
 }
 
+/// Emit a YIELD instruction
+///
+/// A hint that this is a spin-wait loop, so a multi-threaded core can run something else.
+#[inline]
+pub fn yield_() {
+    unsafe { core::arch::asm!("yield", options(nomem, nostack, preserves_flags)) }
+}
+
+/// Emit a BKPT instruction, with the given immediate value
+///
+/// If no debugger is attached, this causes a Prefetch Abort - see
+/// [`debug::breakpoint_if_attached`](crate::debug::breakpoint_if_attached).
+#[inline]
+pub fn bkpt<const IMM: u8>() {
+    unsafe { core::arch::asm!("bkpt #{imm}", imm = const IMM, options(nostack, preserves_flags)) }
+}
+
+/// Emit a UDF (*Permanently Undefined*) instruction
+///
+/// This always causes an Undefined Instruction exception.
+#[inline]
+pub fn udf() -> ! {
+    unsafe { core::arch::asm!("udf #0", options(noreturn, nomem, nostack)) }
+}
+
+/// Emit a PLD instruction, hinting that we will soon read from `addr`
+///
+/// This is only a hint, so any address is fine - it never faults.
+#[inline]
+pub fn pld<T>(addr: *const T) {
+    unsafe { core::arch::asm!("pld [{}]", in(reg) addr, options(nomem, nostack, preserves_flags)) }
+}
+
+/// Emit a PLI instruction, hinting that we will soon execute code at `addr`
+///
+/// This is only a hint, so any address is fine - it never faults.
+#[inline]
+pub fn pli<T>(addr: *const T) {
+    unsafe { core::arch::asm!("pli [{}]", in(reg) addr, options(nomem, nostack, preserves_flags)) }
+}
+
 /// Which core are we?
 ///
 /// Return the bottom 24-bits of the MPIDR
+#[deprecated(note = "use `register::Mpidr::read().affinity()`, or `core_index()`")]
 #[inline]
 pub fn core_id() -> u32 {
     let r: u32;
@@ -0,0 +1,303 @@
+//! 64-bit atomic integers
+//!
+//! Not every AArch32 target gives you `core::sync::atomic::AtomicU64`, but
+//! timers and sequence numbers keep needing one. These types work on all of
+//! them.
+//!
+//! On Arm we use the `LDREXD` and `STREXD` instructions, which every Armv7-R,
+//! Armv7-A and Armv8-R processor has. Otherwise (e.g. when testing on the
+//! host) we use whichever `critical-section` implementation you have selected.
+//!
+//! ```rust ignore
+//! use core::sync::atomic::Ordering;
+//!
+//! static SEQUENCE: cortex_ar::atomic64::AtomicU64 = cortex_ar::atomic64::AtomicU64::new(0);
+//!
+//! let next = SEQUENCE.fetch_add(1, Ordering::Relaxed);
+//! ```
+
+use core::cell::UnsafeCell;
+use core::sync::atomic::Ordering;
+
+macro_rules! atomic_int {
+    ($name:ident, $int:ty, $doc:literal) => {
+        #[doc = $doc]
+        ///
+        /// Works like the type of the same name in `core::sync::atomic`.
+        #[repr(C, align(8))]
+        pub struct $name {
+            value: UnsafeCell<u64>,
+        }
+
+        // Safety: All access to the value goes through the atomic operations
+        // in `imp`
+        unsafe impl Sync for $name {}
+
+        impl $name {
+            /// Create a new atomic integer
+            pub const fn new(value: $int) -> $name {
+                $name {
+                    value: UnsafeCell::new(value as u64),
+                }
+            }
+
+            /// Get a mutable reference to the value
+            ///
+            /// This is safe because the mutable reference means no-one else
+            /// can be accessing it.
+            pub fn get_mut(&mut self) -> &mut $int {
+                // Safety: $int and u64 have the same size and alignment
+                unsafe { &mut *self.value.get().cast::<$int>() }
+            }
+
+            /// Consume the atomic and return the value
+            pub fn into_inner(self) -> $int {
+                self.value.into_inner() as $int
+            }
+
+            /// Load the value
+            pub fn load(&self, order: Ordering) -> $int {
+                let value = imp::load(self.value.get());
+                fence_after(order);
+                value as $int
+            }
+
+            /// Store a new value
+            pub fn store(&self, value: $int, order: Ordering) {
+                fence_before(order);
+                imp::update(self.value.get(), |_| Some(value as u64)).ok();
+                fence_after(order);
+            }
+
+            /// Store a new value, returning the previous value
+            pub fn swap(&self, value: $int, order: Ordering) -> $int {
+                self.fetch_update_inner(order, |_| Some(value))
+                    .unwrap_or_else(|v| v)
+            }
+
+            /// Store `new` if the current value is `current`
+            ///
+            /// Returns the previous value, as `Ok` if it was `current`, or
+            /// `Err` if it wasn't.
+            pub fn compare_exchange(
+                &self,
+                current: $int,
+                new: $int,
+                success: Ordering,
+                failure: Ordering,
+            ) -> Result<$int, $int> {
+                fence_before(success);
+                let result = imp::update(self.value.get(), |old| {
+                    (old as $int == current).then_some(new as u64)
+                });
+                match result {
+                    Ok(old) => {
+                        fence_after(success);
+                        Ok(old as $int)
+                    }
+                    Err(old) => {
+                        fence_after(failure);
+                        Err(old as $int)
+                    }
+                }
+            }
+
+            /// Add to the current value (wrapping on overflow), returning the
+            /// previous value
+            pub fn fetch_add(&self, value: $int, order: Ordering) -> $int {
+                self.fetch_update_inner(order, |old| Some(old.wrapping_add(value)))
+                    .unwrap_or_else(|v| v)
+            }
+
+            /// Subtract from the current value (wrapping on overflow),
+            /// returning the previous value
+            pub fn fetch_sub(&self, value: $int, order: Ordering) -> $int {
+                self.fetch_update_inner(order, |old| Some(old.wrapping_sub(value)))
+                    .unwrap_or_else(|v| v)
+            }
+
+            /// Replace the value with the result of `f`, returning the
+            /// previous value
+            ///
+            /// If `f` returns `None`, the value is left alone and you get
+            /// `Err` with the current value. Keep `f` short and simple,
+            /// because it runs inside the `LDREXD`/`STREXD` loop (or the
+            /// critical section), and it may run more than once.
+            pub fn fetch_update<F>(
+                &self,
+                set_order: Ordering,
+                fetch_order: Ordering,
+                f: F,
+            ) -> Result<$int, $int>
+            where
+                F: FnMut($int) -> Option<$int>,
+            {
+                let result = self.fetch_update_inner(set_order, f);
+                if result.is_err() {
+                    fence_after(fetch_order);
+                }
+                result
+            }
+
+            fn fetch_update_inner<F>(&self, order: Ordering, mut f: F) -> Result<$int, $int>
+            where
+                F: FnMut($int) -> Option<$int>,
+            {
+                fence_before(order);
+                let result = imp::update(self.value.get(), |old| f(old as $int).map(|v| v as u64));
+                fence_after(order);
+                result.map(|v| v as $int).map_err(|v| v as $int)
+            }
+        }
+
+        impl Default for $name {
+            fn default() -> $name {
+                $name::new(0)
+            }
+        }
+
+        impl core::fmt::Debug for $name {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                core::fmt::Debug::fmt(&self.load(Ordering::Relaxed), f)
+            }
+        }
+    };
+}
+
+atomic_int!(
+    AtomicU64,
+    u64,
+    "An unsigned 64-bit integer which can be shared between threads"
+);
+atomic_int!(
+    AtomicI64,
+    i64,
+    "A signed 64-bit integer which can be shared between threads"
+);
+
+/// Put a barrier before the operation, if the ordering needs one
+#[inline]
+fn fence_before(order: Ordering) {
+    if matches!(
+        order,
+        Ordering::Release | Ordering::AcqRel | Ordering::SeqCst
+    ) {
+        core::sync::atomic::fence(Ordering::SeqCst);
+    }
+}
+
+/// Put a barrier after the operation, if the ordering needs one
+#[inline]
+fn fence_after(order: Ordering) {
+    if matches!(
+        order,
+        Ordering::Acquire | Ordering::AcqRel | Ordering::SeqCst
+    ) {
+        core::sync::atomic::fence(Ordering::SeqCst);
+    }
+}
+
+#[cfg(target_arch = "arm")]
+mod imp {
+    /// Read a 64-bit value in one go
+    #[inline]
+    pub fn load(ptr: *mut u64) -> u64 {
+        let lo: u32;
+        let hi: u32;
+        // Safety: ptr is 8-byte aligned and valid, because it came from one of
+        // our atomics. LDREXD is the only single-copy atomic 64-bit load on
+        // processors without LPAE.
+        unsafe {
+            core::arch::asm!(
+                "ldrexd r2, r3, [{ptr}]",
+                "clrex",
+                ptr = in(reg) ptr,
+                out("r2") lo,
+                out("r3") hi,
+                options(nostack, preserves_flags),
+            );
+        }
+        (u64::from(hi) << 32) | u64::from(lo)
+    }
+
+    /// Replace a 64-bit value with the result of `f`
+    ///
+    /// Returns the old value, as `Err` if `f` returned `None`.
+    #[inline]
+    pub fn update<F>(ptr: *mut u64, mut f: F) -> Result<u64, u64>
+    where
+        F: FnMut(u64) -> Option<u64>,
+    {
+        loop {
+            let lo: u32;
+            let hi: u32;
+            // Safety: as for `load`, but leaves the exclusive monitor set
+            unsafe {
+                core::arch::asm!(
+                    "ldrexd r2, r3, [{ptr}]",
+                    ptr = in(reg) ptr,
+                    out("r2") lo,
+                    out("r3") hi,
+                    options(nostack, preserves_flags),
+                );
+            }
+            let old = (u64::from(hi) << 32) | u64::from(lo);
+            let Some(new) = f(old) else {
+                // Safety: clearing the exclusive monitor is always fine
+                unsafe {
+                    core::arch::asm!("clrex", options(nomem, nostack, preserves_flags));
+                }
+                return Err(old);
+            };
+            let failed: u32;
+            // Safety: ptr is valid, and STREXD only writes if nothing else has
+            // touched the value since our LDREXD
+            unsafe {
+                core::arch::asm!(
+                    "strexd {failed}, r0, r1, [{ptr}]",
+                    failed = out(reg) failed,
+                    ptr = in(reg) ptr,
+                    in("r0") new as u32,
+                    in("r1") (new >> 32) as u32,
+                    options(nostack, preserves_flags),
+                );
+            }
+            if failed == 0 {
+                return Ok(old);
+            }
+        }
+    }
+}
+
+#[cfg(not(target_arch = "arm"))]
+mod imp {
+    /// Read a 64-bit value in one go
+    #[inline]
+    pub fn load(ptr: *mut u64) -> u64 {
+        // Safety: ptr is valid because it came from one of our atomics, and
+        // nothing else touches it outside a critical section
+        critical_section::with(|_cs| unsafe { ptr.read() })
+    }
+
+    /// Replace a 64-bit value with the result of `f`
+    ///
+    /// Returns the old value, as `Err` if `f` returned `None`.
+    #[inline]
+    pub fn update<F>(ptr: *mut u64, mut f: F) -> Result<u64, u64>
+    where
+        F: FnMut(u64) -> Option<u64>,
+    {
+        critical_section::with(|_cs| {
+            // Safety: ptr is valid because it came from one of our atomics,
+            // and nothing else touches it outside a critical section
+            let old = unsafe { ptr.read() };
+            match f(old) {
+                Some(new) => {
+                    unsafe { ptr.write(new) };
+                    Ok(old)
+                }
+                None => Err(old),
+            }
+        })
+    }
+}
@@ -0,0 +1,520 @@
+//! Cache maintenance, and cache-line aligned buffers for DMA
+//!
+//! If a DMA engine (or anything else that doesn't look in our data cache)
+//! shares a buffer with the processor, you must:
+//!
+//! * clean the buffer before the DMA engine reads it, so it sees what the
+//!   processor wrote, and
+//! * invalidate the buffer after the DMA engine writes it, and before the
+//!   processor reads it, so the processor doesn't see stale cached data.
+//!
+//! Invalidating a cache line throws away anything else in that line, so DMA
+//! buffers should not share cache lines with other data. [`CacheAligned`]
+//! (and [`DmaBuffer`]) take up whole cache lines to make this easy.
+
+use arbitrary_int::u3;
+
+use crate::{
+    addr::VirtAddr,
+    register::{self, clidr::CacheType},
+};
+
+/// The largest data cache line size of any processor we support, in bytes.
+pub const MAX_LINE_SIZE: usize = 64;
+
+/// Get the size of the smallest data cache line, in bytes.
+#[inline]
+pub fn dcache_line_size() -> usize {
+    register::Ctr::read().dcache_line_size()
+}
+
+/// Get the size of the smallest instruction cache line, in bytes.
+#[inline]
+pub fn icache_line_size() -> usize {
+    register::Ctr::read().icache_line_size()
+}
+
+/// Call `f` with the address of every cache line which covers the range.
+fn for_each_line<F>(addr: VirtAddr, len: usize, line_size: usize, mut f: F)
+where
+    F: FnMut(u32),
+{
+    if len == 0 {
+        return;
+    }
+    let end = addr.as_usize().saturating_add(len);
+    let mut line = addr.align_down(line_size).as_usize();
+    while line < end {
+        f(line as u32);
+        line += line_size;
+    }
+    crate::asm::dsb();
+}
+
+/// Clean the data cache lines covering the given range, to the Point of
+/// Coherency.
+///
+/// Anything the processor has written to these addresses becomes visible to
+/// other bus masters.
+#[inline]
+pub fn clean_dcache_by_range(addr: VirtAddr, len: usize) {
+    crate::asm::dsb();
+    // Safety: Cleaning the cache does not change what memory contains, as far
+    // as the processor is concerned
+    for_each_line(addr, len, dcache_line_size(), |line| unsafe {
+        register::Dccmvac::write(register::Dccmvac(line));
+    });
+}
+
+/// Invalidate the data cache lines covering the given range, to the Point of
+/// Coherency.
+///
+/// # Safety
+///
+/// Anything the processor has written to these addresses but which hasn't
+/// been cleaned yet is lost, including any other data that shares a cache line
+/// with the start or end of the range.
+#[inline]
+pub unsafe fn invalidate_dcache_by_range(addr: VirtAddr, len: usize) {
+    crate::asm::dsb();
+    for_each_line(addr, len, dcache_line_size(), |line| unsafe {
+        register::Dcimvac::write(register::Dcimvac(line));
+    });
+}
+
+/// Clean and invalidate the data cache lines covering the given range, to the
+/// Point of Coherency.
+#[inline]
+pub fn clean_invalidate_dcache_by_range(addr: VirtAddr, len: usize) {
+    crate::asm::dsb();
+    // Safety: Cleaning the cache before invalidating it means nothing is lost
+    for_each_line(addr, len, dcache_line_size(), |line| unsafe {
+        register::Dccimvac::write(register::Dccimvac(line));
+    });
+}
+
+/// Make the instruction cache see code that was written to the given range.
+///
+/// Call this after copying code into RAM (e.g. in a bootloader), or patching
+/// it, and before executing it. We clean the data cache lines to the Point of
+/// Unification, invalidate the instruction cache lines and the branch
+/// predictor, and then wait for all that to finish.
+#[inline]
+pub fn sync_icache_for(addr: VirtAddr, len: usize) {
+    crate::asm::dsb();
+    // Safety: Cleaning the cache does not change what memory contains, as far
+    // as the processor is concerned
+    for_each_line(addr, len, dcache_line_size(), |line| unsafe {
+        register::Dccmvau::write(register::Dccmvau(line));
+    });
+    // The new code must have reached the Point of Unification before the
+    // instruction cache goes to fetch it again
+    crate::asm::dsb();
+    // Safety: Invalidating the instruction cache only means it has to fetch
+    // the code again
+    for_each_line(addr, len, icache_line_size(), |line| unsafe {
+        register::Icimvau::write(register::Icimvau(line));
+    });
+    // Safety: As above, for branch predictions
+    unsafe {
+        register::Bpiall::write(register::Bpiall(0));
+    }
+    crate::asm::dsb();
+    crate::asm::isb();
+}
+
+/// Invalidate the whole instruction cache, and the branch predictor.
+#[inline]
+pub fn invalidate_icache_all() {
+    // Safety: Invalidating the instruction cache only means it has to fetch
+    // the code again
+    unsafe {
+        register::Iciallu::write(register::Iciallu(0));
+        register::Bpiall::write(register::Bpiall(0));
+    }
+    crate::asm::dsb();
+    crate::asm::isb();
+}
+
+/// Invalidate every data cache, up to the Level of Coherency, by set/way.
+///
+/// The data caches hold random junk after reset, so you must do this before
+/// you turn them on (unless your boot ROM did it for you).
+///
+/// # Safety
+///
+/// Anything in the data caches which hasn't been written back is lost, so only
+/// call this whilst the data caches are off.
+pub unsafe fn invalidate_dcache_all() {
+    // Safety: Up to the caller
+    for_each_set_way(|set_way| unsafe {
+        register::Dcisw::write(register::Dcisw(set_way));
+    });
+}
+
+/// Clean and invalidate every data cache, up to the Level of Coherency, by
+/// set/way.
+///
+/// Everything in the data caches is written back to memory, and then thrown
+/// away.
+pub fn clean_invalidate_dcache_all() {
+    // Safety: Cleaning first means nothing is lost
+    for_each_set_way(|set_way| unsafe {
+        register::Dccisw::write(register::Dccisw(set_way));
+    });
+}
+
+/// Call `f` with the set/way value of every line in every data cache, up to
+/// the Level of Coherency.
+fn for_each_set_way<F>(mut f: F)
+where
+    F: FnMut(u32),
+{
+    crate::asm::dsb();
+    let clidr = register::Clidr::read();
+    // LoC is the level after the last one we have to look at
+    for level in 0..clidr.loc().value() {
+        match clidr.cache_type(level) {
+            Some(CacheType::DataOnly | CacheType::Separate | CacheType::Unified) => {}
+            _ => continue,
+        }
+        let ccsidr = read_ccsidr(level, false);
+        let level = u32::from(level);
+        let line_shift = ccsidr.line_shift();
+        let ways = ccsidr.ways();
+        let sets = ccsidr.sets();
+        // The way number goes in the top bits
+        let way_shift = (ways - 1).leading_zeros();
+        for way in 0..ways {
+            for set in 0..sets {
+                f(way.checked_shl(way_shift).unwrap_or(0) | (set << line_shift) | (level << 1));
+            }
+        }
+    }
+    crate::asm::dsb();
+}
+
+/// Select a cache with CSSELR, and read its CCSIDR.
+///
+/// `level` counts from zero. Interrupts are off in between, so nobody else can
+/// change CSSELR under our feet.
+fn read_ccsidr(level: u8, instruction: bool) -> register::Ccsidr {
+    let csselr = register::Csselr::new_with_raw_value(0)
+        .with_level(u3::new(level & 0b111))
+        .with_ind(instruction);
+    crate::interrupt::free(|| {
+        // Safety: Selecting a cache to look at has no side-effects
+        unsafe {
+            register::Csselr::write(csselr);
+        }
+        crate::asm::isb();
+        register::Ccsidr::read()
+    })
+}
+
+/// Which sort of cache a [`CacheInfo`] describes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum CacheKind {
+    /// An instruction cache
+    Instruction,
+    /// A data cache
+    Data,
+    /// A cache holding both instructions and data
+    Unified,
+}
+
+/// The size and shape of one of the processor's caches
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct CacheInfo {
+    /// The cache level, where 1 is the closest to the processor
+    pub level: u8,
+    /// What the cache holds
+    pub kind: CacheKind,
+    /// The total size, in bytes
+    pub size: usize,
+    /// The associativity
+    pub ways: u32,
+    /// The number of sets
+    pub sets: u32,
+    /// The size of a cache line, in bytes
+    pub line_size: usize,
+}
+
+impl CacheInfo {
+    fn new(level: u8, kind: CacheKind) -> CacheInfo {
+        let ccsidr = read_ccsidr(level, kind == CacheKind::Instruction);
+        CacheInfo {
+            level: level + 1,
+            kind,
+            size: ccsidr.size_bytes(),
+            ways: ccsidr.ways(),
+            sets: ccsidr.sets(),
+            line_size: ccsidr.line_size_bytes(),
+        }
+    }
+}
+
+/// Describe every cache this processor has, using CLIDR and CCSIDR.
+///
+/// Caches are reported from Level 1 outwards, with the instruction cache before
+/// the data cache where a level has separate caches. Caches outside the
+/// processor (like a PL310 on a Cortex-A9) don't appear in CLIDR, and so
+/// aren't reported.
+pub fn caches() -> impl Iterator<Item = CacheInfo> {
+    let clidr = register::Clidr::read();
+    (0..7u8)
+        .map_while(move |level| match clidr.cache_type(level) {
+            Some(CacheType::NoCache) | None => None,
+            Some(ty) => Some((level, ty)),
+        })
+        .flat_map(|(level, ty)| {
+            let (first, second) = match ty {
+                CacheType::InstructionOnly => (Some(CacheKind::Instruction), None),
+                CacheType::DataOnly => (Some(CacheKind::Data), None),
+                CacheType::Separate => (Some(CacheKind::Instruction), Some(CacheKind::Data)),
+                CacheType::Unified | CacheType::NoCache => (Some(CacheKind::Unified), None),
+            };
+            first
+                .into_iter()
+                .chain(second)
+                .map(move |kind| CacheInfo::new(level, kind))
+        })
+}
+
+/// Turn on the instruction cache.
+///
+/// It is invalidated first, in case it holds junk from before reset.
+pub fn enable_icache() {
+    if register::Sctlr::read().i() {
+        return;
+    }
+    invalidate_icache_all();
+    register::Sctlr::modify(|w| w.set_i(true));
+    crate::asm::isb();
+}
+
+/// Turn off the instruction cache.
+pub fn disable_icache() {
+    register::Sctlr::modify(|w| w.set_i(false));
+    crate::asm::isb();
+    invalidate_icache_all();
+}
+
+/// Turn on the data cache(s).
+///
+/// They are invalidated first, in case they hold junk from before reset.
+/// Whilst the data cache is off, everything goes straight to memory, so this
+/// can't lose anything you wrote.
+///
+/// On Armv7-A, the data cache only affects memory marked as cacheable in the
+/// translation tables, and only when the MMU is on.
+pub fn enable_dcache() {
+    if register::Sctlr::read().c() {
+        return;
+    }
+    // Safety: The data cache is off, so it can only hold stale lines
+    unsafe {
+        invalidate_dcache_all();
+    }
+    register::Sctlr::modify(|w| w.set_c(true));
+    crate::asm::isb();
+}
+
+/// Turn off the data cache(s).
+///
+/// Afterwards the data caches are cleaned and invalidated, so memory holds
+/// everything that was written whilst they were on, and nothing stale is left
+/// behind for when they are turned back on.
+///
+/// Once SCTLR.C is clear, loads and stores skip the cache, so anything pushed
+/// onto the stack before the clean has finished would come back stale. The
+/// whole sequence is therefore one block of assembly which only uses
+/// registers, with interrupts masked so that no handler touches memory part
+/// way through.
+pub fn disable_dcache() {
+    crate::interrupt::free(|| {
+        // Safety: Cleaning before invalidating means nothing is lost, and we
+        // don't touch memory whilst the caches and memory disagree
+        unsafe {
+            core::arch::asm!(
+                // Turn off the data cache
+                "mrc     p15, 0, {tmp}, c1, c0, 0",
+                "bic     {tmp}, {tmp}, #(1 << 2)",
+                "mcr     p15, 0, {tmp}, c1, c0, 0",
+                "isb",
+                "dsb",
+                // Level of Coherency, times two, to compare with CSSELR.Level
+                "mrc     p15, 1, {clidr}, c0, c0, 1",
+                "ands    {loc}, {clidr}, #(7 << 24)",
+                "lsr     {loc}, {loc}, #23",
+                "beq     5f",
+                "mov     {level}, #0",
+                "1:",
+                // Skip levels which have no data or unified cache
+                "add     {tmp}, {level}, {level}, lsr #1",
+                "lsr     {tmp}, {clidr}, {tmp}",
+                "and     {tmp}, {tmp}, #7",
+                "cmp     {tmp}, #2",
+                "blt     4f",
+                // Select this level with CSSELR, and read its shape from CCSIDR
+                "mcr     p15, 2, {level}, c0, c0, 0",
+                "isb",
+                "mrc     p15, 1, {tmp}, c0, c0, 0",
+                "and     {line}, {tmp}, #7",
+                "add     {line}, {line}, #4",
+                "movw    {ways}, #0x3ff",
+                "and     {ways}, {ways}, {tmp}, lsr #3",
+                "clz     {way_shift}, {ways}",
+                "movw    {set}, #0x7fff",
+                "and     {set}, {set}, {tmp}, lsr #13",
+                "lsl     {set}, {set}, {line}",
+                "mov     {tmp}, #1",
+                "lsl     {line}, {tmp}, {line}",
+                // Clean and invalidate every line, counting the sets and ways
+                // down to zero
+                "2:",
+                "mov     {way}, {ways}",
+                "3:",
+                "lsl     {tmp}, {way}, {way_shift}",
+                "orr     {tmp}, {tmp}, {level}",
+                "orr     {tmp}, {tmp}, {set}",
+                "mcr     p15, 0, {tmp}, c7, c14, 2",
+                "subs    {way}, {way}, #1",
+                "bge     3b",
+                "subs    {set}, {set}, {line}",
+                "bge     2b",
+                "4:",
+                "add     {level}, {level}, #2",
+                "cmp     {loc}, {level}",
+                "bgt     1b",
+                "5:",
+                // Leave CSSELR pointing at Level 1
+                "mov     {tmp}, #0",
+                "mcr     p15, 2, {tmp}, c0, c0, 0",
+                "dsb",
+                "isb",
+                tmp = out(reg) _,
+                clidr = out(reg) _,
+                loc = out(reg) _,
+                level = out(reg) _,
+                line = out(reg) _,
+                ways = out(reg) _,
+                way_shift = out(reg) _,
+                way = out(reg) _,
+                set = out(reg) _,
+                options(nostack),
+            );
+        }
+    });
+}
+
+/// Turn on branch prediction.
+///
+/// The branch predictor is invalidated first. On some processors (e.g. the
+/// Cortex-R5) branch prediction is controlled elsewhere, and this bit does
+/// nothing.
+pub fn enable_branch_prediction() {
+    // Safety: Invalidating the branch predictor just makes it guess again
+    unsafe {
+        register::Bpiall::write(register::Bpiall(0));
+    }
+    crate::asm::dsb();
+    register::Sctlr::modify(|w| w.set_z(true));
+    crate::asm::isb();
+}
+
+/// A value that starts on a cache line boundary and takes up whole cache lines
+///
+/// Cache maintenance on a `CacheAligned` value never affects any other value.
+#[repr(C, align(64))]
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct CacheAligned<T>(pub T);
+
+impl<T> CacheAligned<T> {
+    /// Wrap a value
+    pub const fn new(value: T) -> CacheAligned<T> {
+        CacheAligned(value)
+    }
+
+    /// Clean this value from the data cache, so that a DMA engine can read it.
+    #[inline]
+    pub fn clean(&self) {
+        clean_dcache_by_range(
+            VirtAddr::from_ptr(self as *const Self),
+            core::mem::size_of::<Self>(),
+        );
+    }
+
+    /// Invalidate this value in the data cache, so that we can read what a DMA
+    /// engine wrote to it.
+    ///
+    /// This takes `&mut self` because any changes the processor made, which
+    /// haven't been cleaned yet, are lost.
+    #[inline]
+    pub fn invalidate(&mut self) {
+        // Safety: We have exclusive access to these cache lines, because
+        // nothing else shares them
+        unsafe {
+            invalidate_dcache_by_range(
+                VirtAddr::from_ptr(self as *const Self),
+                core::mem::size_of::<Self>(),
+            );
+        }
+    }
+
+    /// Clean and invalidate this value in the data cache.
+    #[inline]
+    pub fn clean_invalidate(&mut self) {
+        clean_invalidate_dcache_by_range(
+            VirtAddr::from_ptr(self as *const Self),
+            core::mem::size_of::<Self>(),
+        );
+    }
+}
+
+impl<T> core::ops::Deref for CacheAligned<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> core::ops::DerefMut for CacheAligned<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+/// A byte buffer suitable for DMA
+pub type DmaBuffer<const N: usize> = CacheAligned<[u8; N]>;
+
+// `embedded-dma` gives us `ReadBuffer` and `WriteBuffer` for `&'static` and
+// `&'static mut` references to these. The caller is still responsible for
+// cleaning or invalidating the buffer as described above.
+
+#[cfg(feature = "embedded-dma")]
+unsafe impl<T> embedded_dma::ReadTarget for CacheAligned<T>
+where
+    T: embedded_dma::ReadTarget,
+{
+    type Word = T::Word;
+
+    fn as_read_buffer(&self) -> (*const Self::Word, usize) {
+        self.0.as_read_buffer()
+    }
+}
+
+#[cfg(feature = "embedded-dma")]
+unsafe impl<T> embedded_dma::WriteTarget for CacheAligned<T>
+where
+    T: embedded_dma::WriteTarget,
+{
+    type Word = T::Word;
+
+    fn as_write_buffer(&mut self) -> (*mut Self::Word, usize) {
+        self.0.as_write_buffer()
+    }
+}
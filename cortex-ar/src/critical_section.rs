@@ -2,11 +2,31 @@
 //!
 //! We have single-core and multi-core versions. Select with the
 //! `critical-section-single-core` and `critical-section-multi-core` features.
+//!
+//! Critical sections may be nested on the same core. With the multi-core
+//! version, the spin-lock records which core holds it, and only the outermost
+//! critical section on that core releases it.
+//!
+//! Both versions only disable IRQ. If your system delivers interrupts on FIQ,
+//! select `critical-section-single-core-fiq` or
+//! `critical-section-multi-core-fiq` instead, to disable FIQ as well.
+//!
+//! If the same binary has to run on both uniprocessor and multiprocessor
+//! systems, select `critical-section-auto`. It checks the MPIDR and uses the
+//! single-core version on a uniprocessor system and the multi-core version
+//! otherwise.
+//!
+//! With the `critical-section-watchdog` feature, every version also times how
+//! long the critical section is held for. See [`crate::cs_watchdog`].
 
-#[cfg(feature = "critical-section-single-core")]
+#[cfg(any(
+    feature = "critical-section-single-core",
+    feature = "critical-section-auto"
+))]
 mod single_core {
-    struct SingleCoreCriticalSection;
+    pub struct SingleCoreCriticalSection;
 
+    #[cfg(not(feature = "critical-section-auto"))]
     critical_section::set_impl!(SingleCoreCriticalSection);
 
     /// Indicates the critical section was entered with interrupts on
@@ -15,25 +35,48 @@ mod single_core {
     /// Indicates the critical section was entered with interrupts off
     pub const INT_OFF: u8 = 1;
 
-    #[cfg(feature = "critical-section-single-core")]
+    /// Set if the critical section was entered with fast interrupts off
+    #[cfg(feature = "critical-section-single-core-fiq")]
+    pub const FIQ_OFF: u8 = 2;
+
     unsafe impl critical_section::Impl for SingleCoreCriticalSection {
         unsafe fn acquire() -> critical_section::RawRestoreState {
             use core::sync::atomic;
-            // the i bit means "masked"
-            let was_active = !crate::register::Cpsr::read().i();
+            let cpsr = crate::register::Cpsr::read();
             crate::interrupt::disable();
+            #[cfg(feature = "critical-section-single-core-fiq")]
+            crate::interrupt::disable_fiq();
             atomic::compiler_fence(atomic::Ordering::SeqCst);
-            if was_active {
-                INT_ON
-            } else {
-                INT_OFF
+            // the i bit means "masked"
+            let state = if cpsr.i() { INT_OFF } else { INT_ON };
+            // as does the f bit
+            #[cfg(feature = "critical-section-single-core-fiq")]
+            let state = if cpsr.f() { state | FIQ_OFF } else { state };
+            #[cfg(feature = "critical-section-watchdog")]
+            if state & INT_OFF == 0 {
+                crate::cs_watchdog::start();
             }
+            state
         }
 
-        unsafe fn release(was_active: critical_section::RawRestoreState) {
+        unsafe fn release(state: critical_section::RawRestoreState) {
             use core::sync::atomic;
+            #[cfg(feature = "critical-section-watchdog")]
+            if state & INT_OFF == 0 {
+                crate::cs_watchdog::stop();
+            }
+            // Only re-enable fast interrupts if they were enabled before the critical section.
+            #[cfg(feature = "critical-section-single-core-fiq")]
+            if state & FIQ_OFF == 0 {
+                atomic::compiler_fence(atomic::Ordering::SeqCst);
+                // Safety: This is OK because we're releasing a lock that was
+                // entered with fast interrupts enabled
+                unsafe {
+                    crate::interrupt::enable_fiq();
+                }
+            }
             // Only re-enable interrupts if they were enabled before the critical section.
-            if was_active == INT_ON {
+            if state & INT_OFF == 0 {
                 atomic::compiler_fence(atomic::Ordering::SeqCst);
                 // Safety: This is OK because we're releasing a lock that was
                 // entered with interrupts enabled
@@ -45,10 +88,14 @@ mod single_core {
     }
 }
 
-#[cfg(feature = "critical-section-multi-core")]
+#[cfg(any(
+    feature = "critical-section-multi-core",
+    feature = "critical-section-auto"
+))]
 mod multi_core {
-    struct MultiCoreCriticalSection;
+    pub struct MultiCoreCriticalSection;
 
+    #[cfg(not(feature = "critical-section-auto"))]
     critical_section::set_impl!(MultiCoreCriticalSection);
 
     /// The default value for our spin-lock
@@ -63,17 +110,36 @@ mod multi_core {
     /// Indicates the critical section was entered with interrupts off, and the spin-lock unlocked
     pub const INT_OFF_UNLOCKED: u8 = 2;
 
+    /// Indicates the critical section was entered with interrupts on, and the spin-lock locked (by us)
+    pub const INT_ON_LOCKED: u8 = 3;
+
+    /// Set if the critical section was entered with fast interrupts off
+    #[cfg(feature = "critical-section-multi-core-fiq")]
+    pub const FIQ_OFF: u8 = 0x80;
+
     pub static CORE_SPIN_LOCK: core::sync::atomic::AtomicU32 =
         core::sync::atomic::AtomicU32::new(UNLOCKED);
+
+    /// Unlock the spin-lock, and wake up any cores waiting for it
+    fn unlock() {
+        CORE_SPIN_LOCK.store(UNLOCKED, core::sync::atomic::Ordering::Release);
+        // The other cores must be able to see the store before they wake up
+        crate::asm::dsb();
+        crate::asm::sev();
+    }
+
     unsafe impl critical_section::Impl for MultiCoreCriticalSection {
         unsafe fn acquire() -> critical_section::RawRestoreState {
             use core::sync::atomic;
 
+            let cpsr = crate::register::Cpsr::read();
             // the i bit means "masked"
-            let was_active = !crate::register::Cpsr::read().i();
+            let was_active = !cpsr.i();
             crate::interrupt::disable();
+            #[cfg(feature = "critical-section-multi-core-fiq")]
+            crate::interrupt::disable_fiq();
 
-            let core_id = crate::asm::core_id();
+            let core_id = crate::register::Mpidr::read().affinity();
 
             let locked_already = loop {
                 match CORE_SPIN_LOCK.compare_exchange(
@@ -91,17 +157,22 @@ mod multi_core {
                         break true;
                     }
                     Err(_) => {
-                        // someone else holds the lock
-                        core::hint::spin_loop();
+                        // someone else holds the lock, so wait for them to
+                        // release it (which sends an event)
+                        crate::asm::wfe();
                     }
                 }
             };
 
             atomic::compiler_fence(atomic::Ordering::SeqCst);
 
-            match (was_active, locked_already) {
+            let state = match (was_active, locked_already) {
                 (true, true) => {
-                    panic!("Invalid CS state?!");
+                    // we are nested inside a critical section on this core,
+                    // but someone turned interrupts back on. The outermost
+                    // critical section will release the lock, so we only
+                    // need to turn interrupts back on.
+                    INT_ON_LOCKED
                 }
                 (true, false) => {
                     // we need to turn interrupts on, and release the lock
@@ -115,24 +186,48 @@ mod multi_core {
                     // we need to do nothing
                     INT_OFF_LOCKED
                 }
+            };
+
+            #[cfg(feature = "critical-section-watchdog")]
+            if !locked_already {
+                crate::cs_watchdog::start();
             }
+
+            // the f bit also means "masked"
+            #[cfg(feature = "critical-section-multi-core-fiq")]
+            let state = if cpsr.f() { state | FIQ_OFF } else { state };
+
+            state
         }
 
-        unsafe fn release(was_active: critical_section::RawRestoreState) {
+        unsafe fn release(state: critical_section::RawRestoreState) {
             use core::sync::atomic;
 
             atomic::compiler_fence(atomic::Ordering::SeqCst);
-            match was_active {
-                INT_OFF_LOCKED => {
-                    // do nothing
-                }
+
+            #[cfg(feature = "critical-section-multi-core-fiq")]
+            let (fiq_was_active, state) = (state & FIQ_OFF == 0, state & !FIQ_OFF);
+
+            #[cfg(feature = "critical-section-watchdog")]
+            if matches!(state, INT_OFF_UNLOCKED | INT_ON_UNLOCKED) {
+                crate::cs_watchdog::stop();
+            }
+
+            match state {
                 INT_OFF_UNLOCKED => {
                     // the spin-lock was unlocked before, so unlock it
-                    CORE_SPIN_LOCK.store(UNLOCKED, atomic::Ordering::Release);
+                    unlock();
+                }
+                INT_ON_LOCKED => {
+                    // Safety: This is OK because we're releasing a lock that was
+                    // entered with interrupts enabled
+                    unsafe {
+                        crate::interrupt::enable();
+                    }
                 }
                 INT_ON_UNLOCKED => {
                     // the spin-lock was unlocked before, so unlock it
-                    CORE_SPIN_LOCK.store(UNLOCKED, atomic::Ordering::Release);
+                    unlock();
                     // Safety: This is OK because we're releasing a lock that was
                     // entered with interrupts enabled
                     unsafe {
@@ -140,9 +235,58 @@ mod multi_core {
                     }
                 }
                 _ => {
-                    unreachable!()
+                    // INT_OFF_LOCKED - do nothing. We never hand out any other
+                    // value, but if we get one, doing nothing is the safest
+                    // thing to do.
+                }
+            }
+
+            // Only re-enable fast interrupts once the spin-lock is released
+            #[cfg(feature = "critical-section-multi-core-fiq")]
+            if fiq_was_active {
+                // Safety: This is OK because we're releasing a lock that was
+                // entered with fast interrupts enabled
+                unsafe {
+                    crate::interrupt::enable_fiq();
                 }
             }
         }
     }
 }
+
+#[cfg(feature = "critical-section-auto")]
+mod auto {
+    use super::multi_core::MultiCoreCriticalSection;
+    use super::single_core::SingleCoreCriticalSection;
+    use critical_section::Impl;
+
+    struct AutoCriticalSection;
+
+    critical_section::set_impl!(AutoCriticalSection);
+
+    /// Are we the only core in the system?
+    ///
+    /// The MPIDR never changes, and reading it is as cheap as reading a cached
+    /// copy, so we check it every time.
+    fn is_uniprocessor() -> bool {
+        crate::register::Mpidr::read().uniprocessor()
+    }
+
+    unsafe impl critical_section::Impl for AutoCriticalSection {
+        unsafe fn acquire() -> critical_section::RawRestoreState {
+            if is_uniprocessor() {
+                unsafe { <SingleCoreCriticalSection as Impl>::acquire() }
+            } else {
+                unsafe { <MultiCoreCriticalSection as Impl>::acquire() }
+            }
+        }
+
+        unsafe fn release(state: critical_section::RawRestoreState) {
+            if is_uniprocessor() {
+                unsafe { <SingleCoreCriticalSection as Impl>::release(state) }
+            } else {
+                unsafe { <MultiCoreCriticalSection as Impl>::release(state) }
+            }
+        }
+    }
+}
@@ -0,0 +1,113 @@
+//! Measures how long each critical section is held for
+//!
+//! Enable the `critical-section-watchdog` feature (along with one of the
+//! `critical-section-*` implementations) to get this. It's meant for tracking
+//! down latency spikes, not for production use, because it makes every
+//! critical section slower.
+//!
+//! Our critical section notes the time when it is entered, and when it is
+//! released it calls your hook if it was held for longer than the limit you
+//! set. Only the outermost critical section is timed - which means the one
+//! that turns interrupts back on (with the single-core implementation), or
+//! releases the spin-lock (with the multi-core implementation).
+//!
+//! ```rust ignore
+//! fn too_long(ticks: u32) {
+//!     cortex_ar::debug::breakpoint_if_attached();
+//! }
+//!
+//! cortex_ar::cs_watchdog::set_hook(too_long);
+//! cortex_ar::cs_watchdog::set_limit(10_000);
+//! ```
+//!
+//! On Armv8-R we use the Generic Timer's physical count (CNTPCT). Everywhere
+//! else we use the PMU cycle counter (PMCCNTR), which you must enable.
+
+use core::sync::atomic::{AtomicPtr, AtomicU32, Ordering};
+
+/// How many cores we can time critical sections on
+///
+/// Cores are identified by
+/// [`Mpidr::core_index`](crate::register::Mpidr::core_index), and any core
+/// with a higher number isn't timed.
+pub const MAX_CORES: usize = 8;
+
+/// A function to call when a critical section is held for too long
+///
+/// It is given how long the critical section was held for, in ticks of the
+/// counter. It's called just before the critical section ends, so interrupts
+/// are still masked.
+pub type Hook = fn(ticks: u32);
+
+/// The installed hook, or null
+static HOOK: AtomicPtr<()> = AtomicPtr::new(core::ptr::null_mut());
+
+/// The longest a critical section can be held for without calling the hook
+static LIMIT: AtomicU32 = AtomicU32::new(u32::MAX);
+
+/// When each core entered its current critical section
+static START: [AtomicU32; MAX_CORES] = [const { AtomicU32::new(0) }; MAX_CORES];
+
+/// Set how long (in ticks of the counter) a critical section can be held for
+/// before we call the hook
+///
+/// The default is `u32::MAX`, which never calls the hook.
+pub fn set_limit(ticks: u32) {
+    LIMIT.store(ticks, Ordering::Relaxed);
+}
+
+/// Set the function to call when a critical section is held for too long
+///
+/// Returns the hook previously installed, if any.
+pub fn set_hook(hook: Hook) -> Option<Hook> {
+    let old = HOOK.swap(hook as *mut (), Ordering::AcqRel);
+    if old.is_null() {
+        None
+    } else {
+        // Safety: we only ever store null pointers, or a `Hook`
+        Some(unsafe { core::mem::transmute::<*mut (), Hook>(old) })
+    }
+}
+
+/// Read the counter
+#[inline]
+fn now() -> u32 {
+    #[cfg(arm_architecture = "v8-r")]
+    {
+        crate::register::CntPct::read().0 as u32
+    }
+    #[cfg(not(arm_architecture = "v8-r"))]
+    {
+        crate::register::Pmccntr::read().0
+    }
+}
+
+/// Get the slot for this core, if it has one
+#[inline]
+fn slot() -> Option<&'static AtomicU32> {
+    START.get(usize::from(crate::register::Mpidr::read().core_index()))
+}
+
+/// Called when an outermost critical section is entered
+pub(crate) fn start() {
+    if let Some(slot) = slot() {
+        slot.store(now(), Ordering::Relaxed);
+    }
+}
+
+/// Called when an outermost critical section is about to be released
+pub(crate) fn stop() {
+    let Some(slot) = slot() else {
+        return;
+    };
+    let ticks = now().wrapping_sub(slot.load(Ordering::Relaxed));
+    if ticks <= LIMIT.load(Ordering::Relaxed) {
+        return;
+    }
+    let hook = HOOK.load(Ordering::Acquire);
+    if !hook.is_null() {
+        // Safety: we only ever store null pointers, or a `Hook`
+        let hook = unsafe { core::mem::transmute::<*mut (), Hook>(hook) };
+        hook(ticks);
+    }
+}
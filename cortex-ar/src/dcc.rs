@@ -0,0 +1,58 @@
+//! Support for the Debug Communications Channel (DCC)
+//!
+//! The DCC lets the processor swap 32-bit words with an attached debugger,
+//! without halting the processor like semihosting does. This makes it a good
+//! way to log messages on real hardware.
+//!
+//! [`Writer`] sends one byte per word, in the same way as Linux's `hvc_dcc`
+//! driver, so you can read the output with any tool that polls the DCC (e.g.
+//! OpenOCD or probe-rs).
+//!
+//! Sending blocks until the debugger has collected the previous word, so if no
+//! debugger is attached, your program stops the first time it sends two words.
+
+use crate::register::{Dbgdscrint, Dbgdtrrxint, Dbgdtrtxint};
+
+/// Send a word to the debugger
+///
+/// Waits until the debugger has collected the previous word.
+#[inline]
+pub fn write_word(word: u32) {
+    while Dbgdscrint::read().txfull() {
+        core::hint::spin_loop();
+    }
+    // Safety: Writing to DBGDTRTXint only sends data to the debugger
+    unsafe {
+        Dbgdtrtxint::write(Dbgdtrtxint(word));
+    }
+}
+
+/// Get a word from the debugger, if it has sent one
+#[inline]
+pub fn read_word() -> Option<u32> {
+    if Dbgdscrint::read().rxfull() {
+        Some(Dbgdtrrxint::read().0)
+    } else {
+        None
+    }
+}
+
+/// Writes text to the debugger, one byte per word
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Writer;
+
+impl Writer {
+    /// Send some bytes to the debugger
+    pub fn write_bytes(&mut self, bytes: &[u8]) {
+        for b in bytes {
+            write_word(u32::from(*b));
+        }
+    }
+}
+
+impl core::fmt::Write for Writer {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        self.write_bytes(s.as_bytes());
+        Ok(())
+    }
+}
@@ -0,0 +1,46 @@
+//! Helpers for when a debugger is attached
+//!
+//! A `BKPT` instruction halts the processor if a debugger has enabled halting
+//! debug-mode. If not, it causes a Prefetch Abort, which usually wedges the
+//! system. So if you want your panic handler to stop in the debugger during
+//! development, without hanging a production unit, use
+//! [`breakpoint_if_attached`].
+//!
+//! ```rust ignore
+//! #[panic_handler]
+//! fn panic(_info: &core::panic::PanicInfo) -> ! {
+//!     cortex_ar::debug::breakpoint_if_attached();
+//!     reset_the_system();
+//! }
+//! ```
+
+use crate::register::Dbgdscrint;
+
+/// Is a debugger attached?
+///
+/// Looks at whether halting debug-mode is enabled, which a debugger does when
+/// it connects so that it can halt the processor. A debugger which only
+/// watches (e.g. one that just polls the Debug Communications Channel) may
+/// not be detected.
+#[inline]
+pub fn is_debugger_attached() -> bool {
+    Dbgdscrint::read().hdbgen()
+}
+
+/// Halt at a breakpoint, but only if a debugger is attached
+///
+/// Returns `true` if we hit the breakpoint (and the debugger has since let us
+/// carry on), or `false` if there was no debugger so we did nothing.
+#[inline]
+pub fn breakpoint_if_attached() -> bool {
+    if is_debugger_attached() {
+        // Safety: Halting debug-mode is enabled, so this halts rather than
+        // aborting
+        unsafe {
+            core::arch::asm!("bkpt #0", options(nostack, preserves_flags));
+        }
+        true
+    } else {
+        false
+    }
+}
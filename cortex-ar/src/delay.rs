@@ -0,0 +1,171 @@
+//! Busy-wait delays, and measuring time, without needing a chip HAL
+//!
+//! A [`Delay`] counts either processor cycles, using the PMU's cycle counter,
+//! or (on Armv8-R and Armv7-A) the ticks of the Generic Timer's physical counter. Either
+//! way it can give you an [`Instant`], and subtracting one `Instant` from
+//! another gives you a [`Duration`].
+
+pub use core::time::Duration;
+
+use crate::{pmu, register};
+
+/// What a [`Delay`] counts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Source {
+    /// The PMU's 32-bit cycle counter
+    CycleCounter,
+    /// The Generic Timer's 64-bit physical counter
+    #[cfg(any(arm_architecture = "v8-r", arm_profile = "a"))]
+    PhysicalCounter,
+}
+
+/// A moment in time, as measured by a [`Delay`]
+///
+/// Only compare `Instant`s that came from the same `Delay`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Instant {
+    ticks: u64,
+    hz: u32,
+}
+
+impl Instant {
+    /// The raw tick count
+    pub const fn ticks(self) -> u64 {
+        self.ticks
+    }
+
+    /// How long it has been since `earlier`
+    ///
+    /// Returns zero if `earlier` is actually later.
+    pub fn duration_since(self, earlier: Instant) -> Duration {
+        ticks_to_duration(self.ticks.saturating_sub(earlier.ticks), self.hz)
+    }
+
+    /// The `Instant` a given time after this one
+    pub fn checked_add(self, duration: Duration) -> Option<Instant> {
+        Some(Instant {
+            ticks: self
+                .ticks
+                .checked_add(duration_to_ticks(duration, self.hz))?,
+            hz: self.hz,
+        })
+    }
+}
+
+impl core::ops::Sub for Instant {
+    type Output = Duration;
+
+    fn sub(self, earlier: Instant) -> Duration {
+        self.duration_since(earlier)
+    }
+}
+
+/// Busy-waits for a while, and tells you the time
+pub struct Delay {
+    source: Source,
+    hz: u32,
+    /// The last cycle counter value we saw, for spotting when it wraps
+    last: u32,
+    /// How many times the cycle counter has wrapped
+    wraps: u32,
+}
+
+impl Delay {
+    /// Make a `Delay` using the PMU's cycle counter
+    ///
+    /// This turns on the PMU and its cycle counter, and stops the cycle counter
+    /// from dividing by 64. Give it the frequency the processor runs at.
+    ///
+    /// The cycle counter is only 32 bits, so [`Delay::now`] needs calling at
+    /// least once each time it wraps (every 10 seconds at 400 MHz) for the
+    /// `Instant`s to keep going up.
+    pub fn new_cycle_counter(cpu_hz: u32) -> Delay {
+        pmu::set_cycle_divider(false);
+        pmu::enable();
+        // The cycle counter always exists
+        let _ = pmu::enable_counter(pmu::Counter::Cycle);
+        Delay {
+            source: Source::CycleCounter,
+            hz: cpu_hz,
+            last: pmu::cycle_count(),
+            wraps: 0,
+        }
+    }
+
+    /// Make a `Delay` using the Generic Timer's physical counter
+    ///
+    /// The frequency is read from CNTFRQ, so whatever started us up must have
+    /// set it correctly.
+    #[cfg(any(arm_architecture = "v8-r", arm_profile = "a"))]
+    pub fn new_generic_timer() -> Delay {
+        Delay {
+            source: Source::PhysicalCounter,
+            hz: register::Cntfrq::read().0,
+            last: 0,
+            wraps: 0,
+        }
+    }
+
+    /// How many ticks there are in a second
+    pub fn frequency_hz(&self) -> u32 {
+        self.hz
+    }
+
+    /// What time is it now?
+    pub fn now(&mut self) -> Instant {
+        let ticks = match self.source {
+            Source::CycleCounter => {
+                let count = register::Pmccntr::read().0;
+                if count < self.last {
+                    self.wraps = self.wraps.wrapping_add(1);
+                }
+                self.last = count;
+                (u64::from(self.wraps) << 32) | u64::from(count)
+            }
+            #[cfg(any(arm_architecture = "v8-r", arm_profile = "a"))]
+            Source::PhysicalCounter => register::CntPct::read().0,
+        };
+        Instant { ticks, hz: self.hz }
+    }
+
+    /// Wait for at least the given number of ticks
+    pub fn delay_ticks(&mut self, ticks: u64) {
+        let start = self.now().ticks;
+        while self.now().ticks.wrapping_sub(start) < ticks {
+            core::hint::spin_loop();
+        }
+    }
+
+    /// Wait for at least the given time
+    pub fn delay(&mut self, duration: Duration) {
+        self.delay_ticks(duration_to_ticks(duration, self.hz));
+    }
+
+    /// Wait for at least the given number of microseconds
+    pub fn delay_us(&mut self, us: u32) {
+        self.delay(Duration::from_micros(u64::from(us)));
+    }
+
+    /// Wait for at least the given number of milliseconds
+    pub fn delay_ms(&mut self, ms: u32) {
+        self.delay(Duration::from_millis(u64::from(ms)));
+    }
+}
+
+/// Convert a duration to ticks, rounding up
+fn duration_to_ticks(duration: Duration, hz: u32) -> u64 {
+    let ticks = (duration.as_nanos() * u128::from(hz)).div_ceil(1_000_000_000);
+    u64::try_from(ticks).unwrap_or(u64::MAX)
+}
+
+/// Convert ticks to a duration, rounding down
+fn ticks_to_duration(ticks: u64, hz: u32) -> Duration {
+    if hz == 0 {
+        return Duration::ZERO;
+    }
+    let secs = ticks / u64::from(hz);
+    let rem = ticks % u64::from(hz);
+    let nanos = rem * 1_000_000_000 / u64::from(hz);
+    Duration::new(secs, nanos as u32)
+}
@@ -0,0 +1,65 @@
+//! A table of exception handlers that can be changed at run-time
+//!
+//! Enable the `handler-table` feature of `cortex-r-rt` or `cortex-a-rt` to get
+//! this. Each of the assembly language trampolines looks in the table before
+//! calling its handler. If you have installed a handler with [`set_handler`],
+//! the trampoline calls that. Otherwise it calls the handler chosen at
+//! link-time, like `_data_abort_handler`.
+//!
+//! This lets an application install or swap handlers as it runs, or take over
+//! from a bootloader which set up its own.
+//!
+//! If you supply your own `_asm_xxx_handler` instead of using our trampoline,
+//! that exception doesn't use the table.
+
+use core::sync::atomic::{AtomicPtr, Ordering};
+
+use super::{ExceptionFrame, Vector};
+
+/// The installed handlers, indexed by [`Vector`]
+///
+/// The trampolines find this by name, and index it by byte offset, so don't
+/// re-order it. A null pointer means 'use the link-time handler'.
+#[export_name = "_handler_table"]
+static HANDLER_TABLE: [AtomicPtr<()>; 5] = [const { AtomicPtr::new(core::ptr::null_mut()) }; 5];
+
+/// An exception handler that can be installed at run-time
+///
+/// The arguments, and what happens to the return value, depend on the
+/// [`Vector`]:
+///
+/// * For [`Vector::Undefined`], [`Vector::PrefetchAbort`] and
+///   [`Vector::DataAbort`], `arg` is the faulting address and execution resumes
+///   at the address you return.
+/// * For [`Vector::SupervisorCall`], `arg` is the SVC number and the return
+///   value is ignored.
+/// * For [`Vector::Irq`], neither argument means anything and the return
+///   value is ignored.
+pub type Handler = unsafe extern "C" fn(arg: usize, frame: *mut ExceptionFrame) -> usize;
+
+/// Install a handler for the given exception
+///
+/// Returns the handler previously installed, if any.
+pub fn set_handler(vector: Vector, handler: Handler) -> Option<Handler> {
+    let old = HANDLER_TABLE[vector as usize].swap(handler as *mut (), Ordering::AcqRel);
+    to_handler(old)
+}
+
+/// Remove the handler for the given exception
+///
+/// The trampoline goes back to calling the handler chosen at link-time.
+/// Returns the handler previously installed, if any.
+pub fn clear_handler(vector: Vector) -> Option<Handler> {
+    let old = HANDLER_TABLE[vector as usize].swap(core::ptr::null_mut(), Ordering::AcqRel);
+    to_handler(old)
+}
+
+/// Convert an entry in the table back to a function
+fn to_handler(ptr: *mut ()) -> Option<Handler> {
+    if ptr.is_null() {
+        None
+    } else {
+        // Safety: we only ever store null pointers, or a `Handler`
+        Some(unsafe { core::mem::transmute::<*mut (), Handler>(ptr) })
+    }
+}
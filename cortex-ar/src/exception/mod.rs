@@ -0,0 +1,68 @@
+//! Exception handling types shared by `cortex-r-rt` and `cortex-a-rt`
+//!
+//! The run-time crates re-export everything here, so you shouldn't need to use
+//! this module directly. The exception counters and the run-time handler
+//! table live here so both run-time crates share one copy. Their assembly
+//! language trampolines find them by symbol name.
+
+#[cfg(feature = "exception-stats")]
+mod stats;
+
+#[cfg(feature = "handler-table")]
+mod handler_table;
+
+#[cfg(feature = "exception-stats")]
+pub use stats::{exception_stats, ExceptionStats};
+
+#[cfg(feature = "handler-table")]
+pub use handler_table::{clear_handler, set_handler, Handler};
+
+/// The exceptions our assembly language trampolines handle
+#[repr(u32)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Vector {
+    /// Undefined Instruction exceptions
+    Undefined = 0,
+    /// Supervisor Calls
+    SupervisorCall = 1,
+    /// Prefetch Aborts
+    PrefetchAbort = 2,
+    /// Data Aborts
+    DataAbort = 3,
+    /// IRQs
+    Irq = 4,
+}
+
+/// The registers saved on the stack by our default exception trampolines.
+///
+/// A pointer to this is passed as the second argument to the Undefined,
+/// Supervisor Call, Secure Monitor Call, Hypervisor Call, Prefetch Abort and
+/// Data Abort handlers (where the run-time crate supports them). Any changes
+/// you make are written back to the registers when the handler returns, so a
+/// Supervisor Call handler can (for example) return a value to the caller in
+/// R0.
+#[repr(C)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExceptionFrame {
+    /// The value of R0 when the exception occurred
+    pub r0: u32,
+    /// The value of R1 when the exception occurred
+    pub r1: u32,
+    /// The value of R2 when the exception occurred
+    pub r2: u32,
+    /// The value of R3 when the exception occurred
+    pub r3: u32,
+    /// The value of R12 when the exception occurred
+    pub r12: u32,
+    /// The exception mode's LR
+    ///
+    /// For a Supervisor Call this is the address of the instruction after the
+    /// `svc`, and execution resumes wherever this points. For an Undefined,
+    /// Prefetch Abort or Data Abort exception this points at the faulting
+    /// instruction, but execution resumes at the address returned by the
+    /// handler.
+    pub lr: u32,
+    /// The saved program status register, i.e. the CPSR of the code that was
+    /// interrupted.
+    pub spsr: u32,
+}
@@ -0,0 +1,55 @@
+//! Counters for how many times each exception has occurred
+//!
+//! Enable the `exception-stats` feature of `cortex-r-rt` or `cortex-a-rt` to
+//! get these. Each of the assembly language trampolines bumps a counter on
+//! entry, before it calls your handler, so a long-running system can cheaply
+//! keep an eye on its fault rate, or spot spurious interrupts.
+//!
+//! If you supply your own `_asm_xxx_handler` instead of using our trampoline,
+//! that exception isn't counted.
+
+use core::sync::atomic::{AtomicU32, Ordering};
+
+/// The counters, in the same order as the fields of [`ExceptionStats`]
+///
+/// The trampolines find this by name, and index it by byte offset, so don't
+/// re-order it.
+#[export_name = "_exception_counts"]
+static EXCEPTION_COUNTS: [AtomicU32; 6] = [const { AtomicU32::new(0) }; 6];
+
+/// How many times each exception has occurred since reset
+///
+/// The counters wrap around if they overflow.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ExceptionStats {
+    /// Undefined Instruction exceptions
+    pub undefined: u32,
+    /// Supervisor Calls
+    pub svc: u32,
+    /// Prefetch Aborts
+    pub prefetch_abort: u32,
+    /// Data Aborts
+    pub data_abort: u32,
+    /// IRQs, including any where no interrupt was pending
+    pub irq: u32,
+    /// FIQs
+    pub fiq: u32,
+}
+
+/// Get a snapshot of how many times each exception has occurred
+///
+/// The counters are read one at a time, so an exception which occurs whilst
+/// this function is running may only be reflected in some of them.
+pub fn exception_stats() -> ExceptionStats {
+    let [undefined, svc, prefetch_abort, data_abort, irq, fiq] = EXCEPTION_COUNTS
+        .each_ref()
+        .map(|c| c.load(Ordering::Relaxed));
+    ExceptionStats {
+        undefined,
+        svc,
+        prefetch_abort,
+        data_abort,
+        irq,
+        fiq,
+    }
+}
@@ -0,0 +1,71 @@
+//! Types for handling Data Abort exceptions
+
+use crate::register::{Dfar, Dfsr};
+#[cfg(any(test, arm_architecture = "v8-r", arm_profile = "a"))]
+use crate::register::{Hdfar, Hifar, Hpfar, Hsr};
+
+/// A snapshot of the fault registers, taken on entry to a Data Abort handler
+#[derive(Debug, Clone, Copy)]
+pub struct FaultInfo {
+    /// The address of the instruction that caused the fault
+    pub addr: usize,
+    /// The value of DFSR (*Data Fault Status Register*), describing the fault
+    pub dfsr: Dfsr,
+    /// The value of DFAR (*Data Fault Address Register*), giving the address
+    /// that was being accessed
+    pub dfar: u32,
+}
+
+impl FaultInfo {
+    /// Capture DFSR and DFAR, for a Data Abort at the given address
+    ///
+    /// Call this before doing anything that might cause another fault, as that
+    /// would overwrite these registers. If you use a `FaultInfo` argument in a
+    /// `#[exception(DataAbort)]` handler, this is done for you.
+    #[inline]
+    pub fn capture(addr: usize) -> FaultInfo {
+        FaultInfo {
+            addr,
+            dfsr: Dfsr::read(),
+            dfar: Dfar::read().0,
+        }
+    }
+}
+
+/// A snapshot of the fault registers, taken on entry to an EL2 (Hyp mode)
+/// exception handler
+///
+/// Use [`Hsr::syndrome`] to find out what happened. Which of the addresses
+/// are valid depends on the kind of exception.
+#[cfg(any(test, arm_architecture = "v8-r", arm_profile = "a"))]
+#[derive(Debug, Clone, Copy)]
+pub struct HypFaultInfo {
+    /// The value of HSR (*Hyp Syndrome Register*), describing the exception
+    pub hsr: Hsr,
+    /// The value of HDFAR (*Hyp Data Fault Address Register*), giving the
+    /// virtual address that was being accessed
+    pub hdfar: u32,
+    /// The value of HIFAR (*Hyp Instruction Fault Address Register*), giving
+    /// the virtual address of the instruction that couldn't be fetched
+    pub hifar: u32,
+    /// The value of HPFAR (*Hyp IPA Fault Address Register*), giving the page
+    /// of the faulting Intermediate Physical Address
+    pub hpfar: Hpfar,
+}
+
+#[cfg(any(test, arm_architecture = "v8-r", arm_profile = "a"))]
+impl HypFaultInfo {
+    /// Capture HSR, HDFAR, HIFAR and HPFAR
+    ///
+    /// Call this at EL2, before doing anything that might cause another
+    /// exception, as that would overwrite these registers.
+    #[inline]
+    pub fn capture() -> HypFaultInfo {
+        HypFaultInfo {
+            hsr: Hsr::read(),
+            hdfar: Hdfar::read().0,
+            hifar: Hifar::read().0,
+            hpfar: Hpfar::read(),
+        }
+    }
+}
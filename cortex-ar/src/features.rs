@@ -0,0 +1,139 @@
+//! Code for finding out what this processor can do, from its ID registers
+//!
+//! The target you compiled for only tells you the architecture. Optional
+//! extensions (like the Generic Timer or the Virtualization Extensions on
+//! Armv7-A) vary from processor to processor, so read [`Features`] at run-time
+//! before relying on them.
+
+use crate::register;
+
+/// The optional features this processor has
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Features {
+    /// Does it support Thumb-2 (rather than just the original 16-bit Thumb)?
+    pub thumb2: bool,
+    /// Does it have the Security Extensions (TrustZone)?
+    pub security_extensions: bool,
+    /// Does it have the Virtualization Extensions (EL2, or Hyp mode)?
+    pub virtualization_extensions: bool,
+    /// Does it have the Generic Timer?
+    pub generic_timer: bool,
+    /// Does it have an MMU (a Virtual Memory System Architecture)?
+    pub mmu: bool,
+    /// Does the MMU support the Long-descriptor translation table format, and
+    /// so Large Physical Addresses?
+    pub large_physical_addresses: bool,
+    /// Does it have an MPU (a Protected Memory System Architecture)?
+    pub mpu: bool,
+    /// Does it have the SDIV and UDIV instructions, in Arm state?
+    pub hardware_divide: bool,
+    /// Does it have a Performance Monitors Unit?
+    pub pmu: bool,
+    /// Does it have the AES instructions?
+    pub aes: bool,
+    /// Does it have the SHA1 instructions?
+    pub sha1: bool,
+    /// Does it have the SHA256 instructions?
+    pub sha2: bool,
+    /// Does it have the CRC32 instructions?
+    pub crc32: bool,
+}
+
+impl Features {
+    /// Read the ID registers, and work out what this processor can do
+    pub fn read() -> Features {
+        Features::decode(
+            register::IdPfr0::read().0,
+            register::IdPfr1::read().0,
+            register::IdDfr0::read().0,
+            register::IdMmfr0::read().0,
+            register::IdIsar0::read().0,
+            register::IdIsar5::read().0,
+        )
+    }
+
+    /// Work out the features from the raw values of the ID registers
+    pub const fn decode(
+        id_pfr0: u32,
+        id_pfr1: u32,
+        id_dfr0: u32,
+        id_mmfr0: u32,
+        id_isar0: u32,
+        id_isar5: u32,
+    ) -> Features {
+        let perf_mon = field(id_dfr0, 24);
+        Features {
+            // ID_PFR0.State1 is 3 for Thumb-2
+            thumb2: field(id_pfr0, 4) >= 3,
+            security_extensions: field(id_pfr1, 4) != 0,
+            virtualization_extensions: field(id_pfr1, 12) != 0,
+            generic_timer: field(id_pfr1, 16) != 0,
+            mmu: field(id_mmfr0, 0) >= 3,
+            // ID_MMFR0.VMSA is 5 for VMSAv7 with LPAE, and 6 for VMSAv8-32
+            large_physical_addresses: field(id_mmfr0, 0) >= 5,
+            mpu: field(id_mmfr0, 4) >= 3,
+            // ID_ISAR0.Divide is 2 if Arm state has them as well as Thumb
+            hardware_divide: field(id_isar0, 24) >= 2,
+            // 0xF means an IMPLEMENTATION DEFINED PMU, not an Arm one
+            pmu: perf_mon != 0 && perf_mon != 0xF,
+            aes: field(id_isar5, 4) != 0,
+            sha1: field(id_isar5, 8) != 0,
+            sha2: field(id_isar5, 12) != 0,
+            crc32: field(id_isar5, 16) != 0,
+        }
+    }
+}
+
+/// Get the four-bit ID register field starting at bit `shift`
+const fn field(value: u32, shift: u32) -> u32 {
+    (value >> shift) & 0xF
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn cortex_a7() {
+        // Values from the Cortex-A7 MPCore Technical Reference Manual
+        let features = Features::decode(
+            0x0000_1131,
+            0x0001_1011,
+            0x0201_0555,
+            0x1010_1105,
+            0x0210_1110,
+            0x0000_0000,
+        );
+        assert!(features.thumb2);
+        assert!(features.security_extensions);
+        assert!(features.virtualization_extensions);
+        assert!(features.generic_timer);
+        assert!(features.mmu);
+        assert!(features.large_physical_addresses);
+        assert!(!features.mpu);
+        assert!(features.hardware_divide);
+        assert!(features.pmu);
+        assert!(!features.aes);
+    }
+
+    #[test]
+    fn cortex_r5() {
+        // Values from the Cortex-R5 Technical Reference Manual
+        let features = Features::decode(
+            0x0000_0131,
+            0x0000_0001,
+            0x0001_0400,
+            0x0021_0030,
+            0x0210_1111,
+            0x0000_0000,
+        );
+        assert!(features.thumb2);
+        assert!(!features.security_extensions);
+        assert!(!features.virtualization_extensions);
+        assert!(!features.generic_timer);
+        assert!(!features.mmu);
+        assert!(features.mpu);
+        assert!(features.hardware_divide);
+    }
+}
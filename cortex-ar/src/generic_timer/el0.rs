@@ -1,4 +1,4 @@
-//! Code and types for Generic Timer support at EL0 on Armv8-R.
+//! Code and types for Generic Timer support at EL0 on Armv8-R and Armv7-A.
 
 use crate::register;
 
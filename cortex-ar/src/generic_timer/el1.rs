@@ -1,4 +1,4 @@
-//! Code and types for Generic Timer support at EL1 on Armv8-R.
+//! Code and types for Generic Timer support at EL1 on Armv8-R and Armv7-A.
 
 use crate::register;
 
@@ -131,6 +131,23 @@ impl El1VirtualTimer {
             });
         }
     }
+
+    /// Wait for some number of virtual counter ticks, sleeping with WFE
+    ///
+    /// Configure the event stream with
+    /// [`virtual_event_stream_configure`](Self::virtual_event_stream_configure)
+    /// first, or the processor might not wake up until something else sends an
+    /// event. We check the counter each time the processor wakes up, so the
+    /// delay can overrun by up to one event stream period.
+    pub fn delay_with_event_stream(&mut self, ticks: u64) {
+        let start = self.counter();
+        while self.counter().wrapping_sub(start) < ticks {
+            #[cfg(target_arch = "arm")]
+            crate::asm::wfe();
+            #[cfg(not(target_arch = "arm"))]
+            core::hint::spin_loop();
+        }
+    }
 }
 
 impl GenericTimer for El1VirtualTimer {
@@ -1,10 +1,11 @@
-//! Code and types for Generic Timer support at EL2 on Armv8-R.
+//! Code and types for Generic Timer support at EL2 on Armv8-R, or in Hyp mode
+//! on Armv7-A.
 
 use crate::register;
 
 use super::{El1PhysicalTimer, El1VirtualTimer, GenericTimer};
 
-/// Represents our Physical Timer when we are running at EL2.
+/// Represents the EL1 Physical Timer when we are running at EL2.
 pub struct El2PhysicalTimer(El1PhysicalTimer);
 
 impl El2PhysicalTimer {
@@ -27,6 +28,43 @@ impl El2PhysicalTimer {
     pub fn frequency_hz_set(&mut self, new_frequency_hz: u32) {
         register::Cntfrq::write(register::Cntfrq(new_frequency_hz))
     }
+
+    /// Grant or trap EL1 (and EL0) access to the Physical Counter and Timer
+    ///
+    /// If `counter` is false, EL1 and EL0 reads of the Physical Counter trap
+    /// to EL2. If `timer` is false, EL1 and EL0 accesses to the EL1 Physical
+    /// Timer trap to EL2. A hypervisor can then emulate them, or point the
+    /// guest at the Virtual Counter and Timer instead, which are always
+    /// accessible (offset by CNTVOFF).
+    ///
+    /// Whether EL0 gets access is also controlled by EL1, using CNTKCTL.
+    pub fn el1_access_set(&mut self, counter: bool, timer: bool) {
+        register::Cnthctl::modify(|r| {
+            r.set_pl1pcten(counter);
+            r.set_pl1pcen(timer);
+        });
+    }
+
+    /// Configure an event stream from the physical counter.
+    ///
+    /// This works like
+    /// [`El1VirtualTimer::virtual_event_stream_configure`], but counts
+    /// physical counter ticks, and can only be set up from EL2.
+    ///
+    /// Pass None to disable.
+    pub fn physical_event_stream_configure(&mut self, event_config: Option<&super::EventConfig>) {
+        if let Some(event_config) = event_config {
+            register::Cnthctl::modify(|r| {
+                r.set_evnti(arbitrary_int::u4::from_u8(event_config.rate as u8));
+                r.set_evntdir(event_config.evntdir == super::EventDir::HighLow);
+                r.set_evnten(true);
+            });
+        } else {
+            register::Cnthctl::modify(|r| {
+                r.set_evnten(false);
+            });
+        }
+    }
 }
 
 impl GenericTimer for El2PhysicalTimer {
@@ -75,7 +113,7 @@ impl GenericTimer for El2PhysicalTimer {
     }
 }
 
-/// Represents our Virtual Timer when we are running at EL1.
+/// Represents the EL1 Virtual Timer when we are running at EL2.
 pub struct El2VirtualTimer(El1VirtualTimer);
 
 impl El2VirtualTimer {
@@ -89,6 +127,26 @@ impl El2VirtualTimer {
         unsafe { El2VirtualTimer(El1VirtualTimer::new()) }
     }
 
+    /// Get the virtual counter offset
+    ///
+    /// The Virtual Count is the Physical Count minus this offset.
+    pub fn offset(&self) -> u64 {
+        register::CntVoff::read().0
+    }
+
+    /// Set the virtual counter offset
+    ///
+    /// The Virtual Count is the Physical Count minus this offset. A hypervisor
+    /// can set this before running an EL1 guest, so that the guest sees its
+    /// Virtual Count start from zero (or carry on from where it was when the
+    /// guest was last descheduled).
+    ///
+    /// Any compare value programmed into the Virtual Timer is relative to the
+    /// Virtual Count, so changing the offset moves when the timer fires.
+    pub fn offset_set(&mut self, offset: u64) {
+        register::CntVoff::write(register::CntVoff(offset))
+    }
+
     /// Set frequency
     ///
     /// Sets the frequency, in Hz, that the counters are incrementing at. You
@@ -146,7 +204,12 @@ impl GenericTimer for El2VirtualTimer {
     }
 }
 
-/// Represents our Hypervisor-specific Physical Timer when we are running at EL1.
+/// Represents the Hyp Physical Timer, which only EL2 can use.
+///
+/// It shares the Physical Count with the EL1 Physical Timer, but has its own
+/// compare value and control register (CNTHP_CVAL, CNTHP_TVAL and CNTHP_CTL),
+/// and raises its own interrupt (usually PPI 10). This leaves the EL1 Physical
+/// Timer free for an EL1 guest.
 pub struct El2HypPhysicalTimer();
 
 impl El2HypPhysicalTimer {
@@ -159,6 +222,16 @@ impl El2HypPhysicalTimer {
     pub unsafe fn new() -> El2HypPhysicalTimer {
         El2HypPhysicalTimer()
     }
+
+    /// Set frequency
+    ///
+    /// Sets the frequency, in Hz, that the counters are incrementing at. You
+    /// might need to call this if your system doesn't initialise the frequency
+    /// value to something appropriate, or if you change the clock speed of the
+    /// timer.
+    pub fn frequency_hz_set(&mut self, new_frequency_hz: u32) {
+        register::Cntfrq::write(register::Cntfrq(new_frequency_hz))
+    }
 }
 
 impl super::GenericTimer for El2HypPhysicalTimer {
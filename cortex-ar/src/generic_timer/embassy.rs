@@ -0,0 +1,98 @@
+//! An `embassy-time` driver using the EL1 Physical or Virtual Timer
+//!
+//! Enable the `embassy-time-driver` feature to use this driver. You also need
+//! to select a `critical-section` implementation.
+//!
+//! The driver takes over the EL1 Physical Timer, so don't use
+//! [`El1PhysicalTimer`](super::El1PhysicalTimer) yourself. You must enable the
+//! EL1 Physical Timer interrupt (usually PPI 14) in your interrupt controller,
+//! and call [`on_interrupt`] when it fires:
+//!
+//! ```rust ignore
+//! #[interrupt(PPI = 14)]
+//! fn physical_timer() {
+//!     cortex_ar::generic_timer::embassy::on_interrupt();
+//! }
+//! ```
+//!
+//! If you also enable the `embassy-time-driver-virtual` feature, the driver
+//! uses the EL1 Virtual Timer instead (usually PPI 11). That's the one to use
+//! if a hypervisor has kept the EL1 Physical Timer for itself.
+
+use core::cell::RefCell;
+use core::task::Waker;
+
+use embassy_time_driver::{Driver, TICK_HZ};
+use embassy_time_queue_utils::Queue;
+
+use super::GenericTimer;
+
+/// The timer this driver uses
+#[cfg(not(feature = "embassy-time-driver-virtual"))]
+type Timer = super::El1PhysicalTimer;
+
+/// The timer this driver uses
+#[cfg(feature = "embassy-time-driver-virtual")]
+type Timer = super::El1VirtualTimer;
+
+struct TimerDriver {
+    queue: critical_section::Mutex<RefCell<Queue>>,
+}
+
+embassy_time_driver::time_driver_impl!(static DRIVER: TimerDriver = TimerDriver {
+    queue: critical_section::Mutex::new(RefCell::new(Queue::new())),
+});
+
+impl TimerDriver {
+    /// Set the timer to fire at the given time, in embassy ticks.
+    ///
+    /// If the time has already passed, the interrupt fires straight away.
+    fn set_alarm(&self, at: u64) {
+        // Safety: We only use the timer inside a critical section
+        let mut timer = unsafe { Timer::new() };
+        if at == u64::MAX {
+            // Nothing to wait for. The interrupt is level-sensitive so we must
+            // mask it, or it will keep firing.
+            timer.interrupt_mask(true);
+            return;
+        }
+        let freq = u128::from(timer.frequency_hz());
+        // round up, so we never wake early
+        let compare = (u128::from(at) * freq).div_ceil(u128::from(TICK_HZ));
+        timer.counter_compare_set(u64::try_from(compare).unwrap_or(u64::MAX));
+        timer.interrupt_mask(false);
+        timer.enable(true);
+    }
+}
+
+impl Driver for TimerDriver {
+    fn now(&self) -> u64 {
+        // Safety: We only read the counter
+        let timer = unsafe { Timer::new() };
+        let freq = u128::from(timer.frequency_hz());
+        // If CNTFRQ was never set, time stands still rather than panicking
+        let ticks = (u128::from(timer.counter()) * u128::from(TICK_HZ))
+            .checked_div(freq)
+            .unwrap_or(0);
+        ticks as u64
+    }
+
+    fn schedule_wake(&self, at: u64, waker: &Waker) {
+        critical_section::with(|cs| {
+            let mut queue = self.queue.borrow_ref_mut(cs);
+            if queue.schedule_wake(at, waker) {
+                self.set_alarm(queue.next_expiration(self.now()));
+            }
+        })
+    }
+}
+
+/// Wake any tasks whose time has come, and set the timer for the next one.
+///
+/// Call this from your EL1 Physical (or Virtual) Timer interrupt handler.
+pub fn on_interrupt() {
+    critical_section::with(|cs| {
+        let mut queue = DRIVER.queue.borrow_ref_mut(cs);
+        DRIVER.set_alarm(queue.next_expiration(DRIVER.now()));
+    })
+}
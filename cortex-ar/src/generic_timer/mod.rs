@@ -3,10 +3,15 @@
 //! See Chapter G6: The Generic Timer in AArch32 State in [ARM Architecture
 //! Reference Manual v8][armv8].
 //!
-//! The Generic Timer existed in Armv7-A as well, but not in Armv7-R.
+//! The Generic Timer existed in Armv7-A as well, but not in Armv7-R. Only
+//! Armv7-A processors with the Generic Timer Extension (like the Cortex-A7,
+//! Cortex-A15 and Cortex-A17) have it - the Cortex-A5, Cortex-A8 and
+//! Cortex-A9 do not. Use [`is_present`] to check at run-time.
 //!
 //! [armv8]: https://developer.arm.com/documentation/ddi0487/latest/
 
+use crate::register;
+
 mod el2;
 pub use el2::{El2HypPhysicalTimer, El2PhysicalTimer, El2VirtualTimer};
 
@@ -16,6 +21,85 @@ pub use el1::{El1PhysicalTimer, El1VirtualTimer};
 mod el0;
 pub use el0::{El0PhysicalTimer, El0VirtualTimer};
 
+#[cfg(feature = "embassy-time-driver")]
+pub mod embassy;
+
+#[cfg(feature = "rtic-monotonic")]
+pub mod rtic;
+
+/// The Private Peripheral Interrupt (PPI) for the EL1 Physical Timer
+///
+/// This is INTID 30 on a GIC.
+pub const EL1_PHYSICAL_TIMER_PPI: u32 = 14;
+
+/// The Private Peripheral Interrupt (PPI) for the EL1 Virtual Timer
+///
+/// This is INTID 27 on a GIC.
+pub const EL1_VIRTUAL_TIMER_PPI: u32 = 11;
+
+/// The Private Peripheral Interrupt (PPI) for the EL2 (Hyp) Physical Timer
+///
+/// This is INTID 26 on a GIC.
+pub const EL2_PHYSICAL_TIMER_PPI: u32 = 10;
+
+/// The INTID of PPI 0 on a GIC
+const PPI_INTID_START: u32 = 16;
+
+/// Describes which interrupt a timer raises
+///
+/// These are the interrupt numbers recommended by the Arm architecture, which
+/// the Cortex-R52 uses. Check your chip's documentation if you're not sure.
+pub trait TimerInterrupt {
+    /// The timer's Private Peripheral Interrupt (PPI) number
+    const PPI: u32;
+
+    /// The timer's INTID, as used by a GIC
+    const INTID: u32 = PPI_INTID_START + Self::PPI;
+
+    /// Configure and enable this timer's interrupt in the GIC
+    ///
+    /// The interrupt is given the priority you ask for, and put in Non-secure
+    /// Group 1. It is enabled in the Redistributor for the core we're running
+    /// on, which we take to be `Mpidr::core_index`. You still need to unmask
+    /// the timer's own interrupt, and enable IRQs.
+    #[cfg(feature = "arm-gic")]
+    fn register_with_gic(&self, gic: &mut arm_gic::gicv3::GicV3<'_>, priority: u8) {
+        let intid = arm_gic::IntId::ppi(Self::PPI);
+        let cpu = Some(usize::from(crate::register::Mpidr::read().core_index()));
+        gic.set_interrupt_priority(intid, cpu, priority);
+        gic.set_group(intid, cpu, arm_gic::gicv3::Group::Group1NS);
+        gic.enable_interrupt(intid, cpu, true);
+    }
+}
+
+impl TimerInterrupt for El0PhysicalTimer {
+    const PPI: u32 = EL1_PHYSICAL_TIMER_PPI;
+}
+
+impl TimerInterrupt for El0VirtualTimer {
+    const PPI: u32 = EL1_VIRTUAL_TIMER_PPI;
+}
+
+impl TimerInterrupt for El1PhysicalTimer {
+    const PPI: u32 = EL1_PHYSICAL_TIMER_PPI;
+}
+
+impl TimerInterrupt for El1VirtualTimer {
+    const PPI: u32 = EL1_VIRTUAL_TIMER_PPI;
+}
+
+impl TimerInterrupt for El2PhysicalTimer {
+    const PPI: u32 = EL1_PHYSICAL_TIMER_PPI;
+}
+
+impl TimerInterrupt for El2VirtualTimer {
+    const PPI: u32 = EL1_VIRTUAL_TIMER_PPI;
+}
+
+impl TimerInterrupt for El2HypPhysicalTimer {
+    const PPI: u32 = EL2_PHYSICAL_TIMER_PPI;
+}
+
 /// Describes either a Physical or Virtual timer
 pub trait GenericTimer {
     /// Get the timer frequency
@@ -99,6 +183,59 @@ pub trait GenericTimer {
     }
 }
 
+/// Does this processor have a Generic Timer?
+///
+/// Reads the GenTimer field of ID_PFR1. This is always true on Armv8-R, but on
+/// Armv7-A you should check before touching any of the timers, as their
+/// registers are UNDEFINED on processors without one.
+pub fn is_present() -> bool {
+    (register::IdPfr1::read().0 >> 16) & 0xF != 0
+}
+
+/// Which parts of the Generic Timer user code at EL0 can use
+///
+/// See [`el0_access`] and [`el0_access_set`].
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+pub struct El0Access {
+    /// Can EL0 read the Physical Count (CNTPCT)?
+    pub physical_counter: bool,
+    /// Can EL0 read the Virtual Count (CNTVCT)?
+    pub virtual_counter: bool,
+    /// Can EL0 use the EL1 Physical Timer?
+    pub physical_timer: bool,
+    /// Can EL0 use the EL1 Virtual Timer?
+    pub virtual_timer: bool,
+}
+
+/// Read which parts of the Generic Timer EL0 can use, from CNTKCTL
+pub fn el0_access() -> El0Access {
+    let cntkctl = register::Cntkctl::read();
+    El0Access {
+        physical_counter: cntkctl.el0pcten(),
+        virtual_counter: cntkctl.el0vcten(),
+        physical_timer: cntkctl.el0pten(),
+        virtual_timer: cntkctl.el0vten(),
+    }
+}
+
+/// Grant or deny EL0 access to parts of the Generic Timer, using CNTKCTL
+///
+/// Granting access to a counter lets user-mode tasks take cheap timestamps
+/// (with [`El0VirtualTimer::counter`](GenericTimer::counter), for example)
+/// without a system call. Either counter also gives read access to CNTFRQ.
+/// Anything you don't grant access to is UNDEFINED at EL0.
+///
+/// Only call this at EL1 or EL2. The event stream settings in CNTKCTL are left
+/// alone.
+pub fn el0_access_set(access: El0Access) {
+    register::Cntkctl::modify(|r| {
+        r.set_el0pcten(access.physical_counter);
+        r.set_el0vcten(access.virtual_counter);
+        r.set_el0pten(access.physical_timer);
+        r.set_el0vten(access.virtual_timer);
+    });
+}
+
 /// Describes the configuration for an Edvent
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct EventConfig {
@@ -0,0 +1,158 @@
+//! An RTIC v2 Monotonic using the EL1 Physical Timer
+//!
+//! Enable the `rtic-monotonic` feature to use this. You also need to select a
+//! `critical-section` implementation.
+//!
+//! Use [`generic_timer_monotonic!`](crate::generic_timer_monotonic) to make a
+//! Monotonic type, giving the frequency of your Generic Timer (as set in
+//! CNTFRQ):
+//!
+//! ```rust ignore
+//! cortex_ar::generic_timer_monotonic!(Mono, 100_000_000);
+//!
+//! // in your init function
+//! Mono::start(unsafe { El1PhysicalTimer::new() });
+//! ```
+//!
+//! The Monotonic takes over the EL1 Physical Timer, so don't use
+//! [`El1PhysicalTimer`] yourself, or at the same time as the `embassy-time`
+//! driver.
+//!
+//! ## Interrupts
+//!
+//! The Monotonic counts the 64-bit Physical Count (CNTPCT), and programs the
+//! 64-bit compare value (CNTP_CVAL) with the next deadline. When the count
+//! reaches the compare value, the timer raises its interrupt, which on a
+//! Cortex-R52 is PPI 14 (INTID 30). That interrupt is level-sensitive, and
+//! stays asserted until the compare value is moved into the future again,
+//! which [`on_interrupt`] does for you.
+//!
+//! So you need to:
+//!
+//! 1. Configure and enable the interrupt in the GIC Redistributor for this
+//!    core. With the `arm-gic` feature, `TimerInterrupt::register_with_gic`
+//!    will do this.
+//! 2. Call [`on_interrupt`] from your handler for that interrupt. With
+//!    `cortex-r-rt`, that can be an `#[interrupt]` function.
+//! 3. Unmask IRQs, with [`crate::interrupt::enable`].
+//!
+//! ```rust ignore
+//! // in your init function
+//! let mut timer = unsafe { El1PhysicalTimer::new() };
+//! timer.register_with_gic(&mut gic, 0x10);
+//! Mono::start(timer);
+//! unsafe { cortex_ar::interrupt::enable() };
+//!
+//! #[interrupt(PPI = 14)]
+//! fn mono_tick() {
+//!     unsafe { cortex_ar::generic_timer::rtic::on_interrupt() };
+//! }
+//! ```
+//!
+//! The GIC's priority mask must let the timer's priority through.
+
+use rtic_time::timer_queue::{TimerQueue, TimerQueueBackend};
+
+use super::{El1PhysicalTimer, GenericTimer};
+
+pub use fugit;
+pub use rtic_time;
+
+/// A [`TimerQueueBackend`] for the EL1 Physical Timer
+///
+/// Ticks are raw counter ticks.
+pub struct GenericTimerBackend;
+
+static TIMER_QUEUE: TimerQueue<GenericTimerBackend> = TimerQueue::new();
+
+impl GenericTimerBackend {
+    /// Start the timer, and the timer queue
+    ///
+    /// Use the `start` method of the type made by
+    /// [`generic_timer_monotonic!`](crate::generic_timer_monotonic) instead of
+    /// calling this.
+    pub fn _start(mut timer: El1PhysicalTimer) {
+        timer.counter_compare_set(u64::MAX);
+        timer.interrupt_mask(false);
+        timer.enable(true);
+        TIMER_QUEUE.initialize(GenericTimerBackend);
+    }
+
+    fn timer() -> El1PhysicalTimer {
+        // Safety: The timer belongs to the timer queue, and the timer queue
+        // only calls us from inside a critical section or the timer interrupt
+        unsafe { El1PhysicalTimer::new() }
+    }
+}
+
+impl TimerQueueBackend for GenericTimerBackend {
+    type Ticks = u64;
+
+    fn now() -> Self::Ticks {
+        Self::timer().counter()
+    }
+
+    fn set_compare(instant: Self::Ticks) {
+        Self::timer().counter_compare_set(instant);
+    }
+
+    fn clear_compare_flag() {
+        // The interrupt is level-sensitive, so it stays asserted until the
+        // compare value is in the future again
+        Self::timer().counter_compare_set(u64::MAX);
+    }
+
+    fn pend_interrupt() {
+        // A compare value in the past fires straight away
+        Self::timer().counter_compare_set(0);
+    }
+
+    fn timer_queue() -> &'static TimerQueue<Self> {
+        &TIMER_QUEUE
+    }
+}
+
+/// Wake any tasks whose time has come, and set the timer for the next one.
+///
+/// Call this from your EL1 Physical Timer interrupt handler.
+///
+/// # Safety
+///
+/// Only call this from the EL1 Physical Timer interrupt handler.
+pub unsafe fn on_interrupt() {
+    unsafe {
+        TIMER_QUEUE.on_monotonic_interrupt();
+    }
+}
+
+/// Make an RTIC Monotonic type, using the EL1 Physical Timer
+///
+/// Give the name of the type to create, and the frequency of the Generic Timer
+/// in Hz. The frequency is checked against CNTFRQ when you call `start`.
+///
+/// See the [`generic_timer::rtic`](crate::generic_timer::rtic) module for more
+/// details.
+#[macro_export]
+macro_rules! generic_timer_monotonic {
+    ($name:ident, $tick_rate_hz:expr) => {
+        /// A Monotonic using the EL1 Physical Timer
+        pub struct $name;
+
+        impl $name {
+            /// Start the Monotonic
+            ///
+            /// Panics if the timer doesn't run at the given frequency.
+            pub fn start(timer: $crate::generic_timer::El1PhysicalTimer) {
+                use $crate::generic_timer::GenericTimer;
+                assert_eq!(timer.frequency_hz(), $tick_rate_hz);
+                $crate::generic_timer::rtic::GenericTimerBackend::_start(timer);
+            }
+        }
+
+        impl $crate::generic_timer::rtic::rtic_time::monotonic::TimerQueueBasedMonotonic for $name {
+            type Backend = $crate::generic_timer::rtic::GenericTimerBackend;
+            type Instant = $crate::generic_timer::rtic::fugit::Instant<u64, 1, { $tick_rate_hz }>;
+            type Duration = $crate::generic_timer::rtic::fugit::Duration<u64, 1, { $tick_rate_hz }>;
+        }
+    };
+}
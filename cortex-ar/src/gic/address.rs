@@ -0,0 +1,105 @@
+//! Finding the GIC built into a processor
+
+use crate::register::Midr;
+
+/// Arm's implementer code in MIDR
+const ARM_IMPLEMENTER: u8 = 0x41;
+
+/// A processor with a GIC at a fixed offset from PERIPHBASE
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CpuFamily {
+    /// Cortex-A5 MPCore, with a GICv1 or GICv2
+    CortexA5,
+    /// Cortex-A7 MPCore, with a GIC-400
+    CortexA7,
+    /// Cortex-A9 MPCore, with a GICv1 or GICv2
+    CortexA9,
+    /// Cortex-A15 MPCore, with a GIC-400
+    CortexA15,
+    /// Cortex-R52, with a GICv3
+    CortexR52,
+}
+
+impl CpuFamily {
+    /// Work out which processor we are running on, by reading MIDR
+    ///
+    /// Returns `None` if it's not one we know about.
+    pub fn detect() -> Option<CpuFamily> {
+        let midr = Midr::read();
+        if midr.implementer() != ARM_IMPLEMENTER {
+            return None;
+        }
+        match midr.part_no().value() {
+            0xC05 => Some(CpuFamily::CortexA5),
+            0xC07 => Some(CpuFamily::CortexA7),
+            0xC09 => Some(CpuFamily::CortexA9),
+            0xC0F => Some(CpuFamily::CortexA15),
+            0xD13 => Some(CpuFamily::CortexR52),
+            _ => None,
+        }
+    }
+
+    /// Get the addresses of this processor's GIC, given its PERIPHBASE
+    pub fn gic_addresses(self, periphbase: *mut u32) -> GicAddresses {
+        let at = |offset: usize| periphbase.wrapping_byte_add(offset);
+        match self {
+            CpuFamily::CortexA5 | CpuFamily::CortexA9 => GicAddresses::V2 {
+                gicd: at(0x1000),
+                gicc: at(0x0100),
+            },
+            CpuFamily::CortexA7 | CpuFamily::CortexA15 => GicAddresses::V2 {
+                gicd: at(0x1000),
+                gicc: at(0x2000),
+            },
+            CpuFamily::CortexR52 => GicAddresses::V3 {
+                gicd: at(0x0000),
+                gicr: at(0x10_0000),
+            },
+        }
+    }
+}
+
+/// Where the parts of a GIC are
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GicAddresses {
+    /// A GICv2 (or GICv1) - see [`GicV2`](super::GicV2)
+    V2 {
+        /// The Distributor
+        gicd: *mut u32,
+        /// The CPU Interface
+        gicc: *mut u32,
+    },
+    /// A GICv3
+    V3 {
+        /// The Distributor
+        gicd: *mut u32,
+        /// The Redistributor for the first core. Each core has two 64 KiB
+        /// frames, so the next core's Redistributor is 128 KiB further on.
+        gicr: *mut u32,
+    },
+}
+
+/// Get PERIPHBASE, the base address of the processor's private peripherals
+/// (including the GIC)
+#[cfg(arm_architecture = "v8-r")]
+pub fn periphbase() -> *mut u32 {
+    crate::register::ImpCbar::read().periphbase()
+}
+
+/// Get PERIPHBASE, the base address of the processor's private peripherals
+/// (including the GIC)
+///
+/// Only the Cortex-A5, Cortex-A7, Cortex-A9 and Cortex-A15 have CBAR, so this
+/// isn't meaningful on other processors.
+#[cfg(arm_profile = "a")]
+pub fn periphbase() -> *mut u32 {
+    crate::register::Cbar::read().periphbase()
+}
+
+/// Get the addresses of the GIC built into this processor
+///
+/// Returns `None` if this isn't a processor we know about.
+#[cfg(any(arm_architecture = "v8-r", arm_profile = "a"))]
+pub fn gic_addresses() -> Option<GicAddresses> {
+    CpuFamily::detect().map(|family| family.gic_addresses(periphbase()))
+}
@@ -0,0 +1,56 @@
+//! Support for the Arm Generic Interrupt Controller (GIC)
+//!
+//! For a GICv3, only the parts required to acknowledge and complete interrupts
+//! are provided here. For configuring the Distributor and Redistributors, see
+//! the [`arm-gic`](https://crates.io/crates/arm-gic) crate.
+//!
+//! For a GICv2, which `arm-gic` doesn't cover, [`GicV2`] is a small driver for
+//! both the Distributor and the CPU Interface.
+//!
+//! [`gic_addresses`] finds the GIC built into a Cortex-A5, A7, A9, A15 or
+//! Cortex-R52, using PERIPHBASE from the CBAR register.
+
+use crate::interrupt::InterruptController;
+use crate::register::{IccEoir1, IccIar1};
+
+mod address;
+mod v2;
+
+#[cfg(any(arm_architecture = "v8-r", arm_profile = "a"))]
+pub use address::{gic_addresses, periphbase};
+pub use address::{CpuFamily, GicAddresses};
+pub use v2::{GicV2, SgiTarget};
+
+/// The lowest of the special INTIDs (1020 to 1023)
+///
+/// An acknowledge that returns one of these did not acknowledge a real
+/// interrupt.
+const SPECIAL_INTID_START: u32 = 1020;
+
+/// The System Register interface to a GICv3 CPU Interface
+///
+/// Acknowledges and completes Group 1 interrupts using `ICC_IAR1` and
+/// `ICC_EOIR1`. This is the interface used by the Cortex-R52, which only
+/// supports System Register access to its GIC.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct GicV3CpuInterface;
+
+impl InterruptController for GicV3CpuInterface {
+    #[inline]
+    fn acknowledge(&mut self) -> Option<u32> {
+        let intid = IccIar1::read().0 & 0x00FF_FFFF;
+        if (SPECIAL_INTID_START..1024).contains(&intid) {
+            None
+        } else {
+            Some(intid)
+        }
+    }
+
+    #[inline]
+    fn end_of_interrupt(&mut self, id: u32) {
+        // Safety: We're only completing an interrupt we acknowledged
+        unsafe {
+            IccEoir1::write(IccEoir1(id));
+        }
+    }
+}
@@ -0,0 +1,214 @@
+//! A driver for a memory-mapped GICv2 (e.g. the GIC-390 or GIC-400)
+
+use super::SPECIAL_INTID_START;
+use crate::interrupt::InterruptController;
+
+/// Distributor Control Register
+const GICD_CTLR: usize = 0x000;
+/// Interrupt Controller Type Register
+const GICD_TYPER: usize = 0x004;
+/// Interrupt Set-Enable Registers
+const GICD_ISENABLER: usize = 0x100;
+/// Interrupt Clear-Enable Registers
+const GICD_ICENABLER: usize = 0x180;
+/// Interrupt Priority Registers (byte accessible)
+const GICD_IPRIORITYR: usize = 0x400;
+/// Interrupt Processor Targets Registers (byte accessible)
+const GICD_ITARGETSR: usize = 0x800;
+/// Software Generated Interrupt Register
+const GICD_SGIR: usize = 0xF00;
+
+/// CPU Interface Control Register
+const GICC_CTLR: usize = 0x000;
+/// Interrupt Priority Mask Register
+const GICC_PMR: usize = 0x004;
+/// Interrupt Acknowledge Register
+const GICC_IAR: usize = 0x00C;
+/// End of Interrupt Register
+const GICC_EOIR: usize = 0x010;
+
+/// The number of Software Generated Interrupts
+const NUM_SGIS: usize = 16;
+
+/// The first Shared Peripheral Interrupt
+const SPI_START: u32 = 32;
+
+/// The priority we give every SPI in [`GicV2::init_distributor`]
+const DEFAULT_PRIORITY: u8 = 0xA0;
+
+/// Which cores a Software Generated Interrupt is sent to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SgiTarget {
+    /// The cores whose bits are set in this mask (bit 0 is CPU Interface 0)
+    List(u8),
+    /// Every core except this one
+    AllOthers,
+    /// Only this core
+    Current,
+}
+
+/// A GICv2 Distributor and CPU Interface
+///
+/// Most Armv7-A processors (Cortex-A5, A7, A9 and A15), and some Cortex-R5
+/// systems, have one of these. It only uses Group 0, signalled as IRQ, which
+/// is what you get by default when the processor has no Security Extensions,
+/// or when you run in Secure state.
+///
+/// The Distributor is shared between cores, but the CPU Interface (and the
+/// SGI and PPI parts of the Distributor) are banked per core, so each core
+/// needs its own `GicV2` to call [`GicV2::init_cpu_interface`] and
+/// [`InterruptController::acknowledge`] on.
+#[derive(Debug)]
+pub struct GicV2 {
+    gicd: *mut u8,
+    gicc: *mut u8,
+    /// The core which sent each SGI we have acknowledged but not completed
+    sgi_source: [u8; NUM_SGIS],
+}
+
+// Safety: It's just a couple of MMIO addresses, and the per-core banking is
+// the caller's problem, as described above.
+unsafe impl Send for GicV2 {}
+
+impl GicV2 {
+    /// Create a driver for the GIC at the given addresses
+    ///
+    /// # Safety
+    ///
+    /// `gicd_base` must be the base address of a GICv2 Distributor, and
+    /// `gicc_base` the base address of its CPU Interface, both mapped as
+    /// Device memory.
+    pub const unsafe fn new(gicd_base: *mut u32, gicc_base: *mut u32) -> GicV2 {
+        GicV2 {
+            gicd: gicd_base.cast(),
+            gicc: gicc_base.cast(),
+            sgi_source: [0; NUM_SGIS],
+        }
+    }
+
+    /// Set up the Distributor
+    ///
+    /// Every SPI is disabled, given a middling priority and routed to this
+    /// core. Then the Distributor is enabled. Only do this once, on one core.
+    pub fn init_distributor(&mut self) {
+        self.gicd_write(GICD_CTLR, 0);
+        // ITLinesNumber says how many blocks of 32 interrupts there are
+        let lines = 32 * ((self.gicd_read(GICD_TYPER) & 0x1F) + 1);
+        // Reading any of the first eight target bytes gives us our own core
+        let this_core = self.gicd_read_byte(GICD_ITARGETSR);
+        for id in (SPI_START..lines).step_by(32) {
+            self.gicd_write(GICD_ICENABLER + (id as usize / 32) * 4, 0xFFFF_FFFF);
+        }
+        for id in SPI_START..lines {
+            self.gicd_write_byte(GICD_IPRIORITYR + id as usize, DEFAULT_PRIORITY);
+            self.gicd_write_byte(GICD_ITARGETSR + id as usize, this_core);
+        }
+        self.gicd_write(GICD_CTLR, 1);
+    }
+
+    /// Set up the CPU Interface for this core
+    ///
+    /// Lets every priority through, and enables the interface. Do this on
+    /// each core.
+    pub fn init_cpu_interface(&mut self) {
+        self.set_priority_mask(0xFF);
+        self.gicc_write(GICC_CTLR, 1);
+    }
+
+    /// Only signal interrupts with a higher priority (lower value) than `mask`
+    pub fn set_priority_mask(&mut self, mask: u8) {
+        self.gicc_write(GICC_PMR, u32::from(mask));
+    }
+
+    /// Enable or disable an interrupt
+    pub fn enable_interrupt(&mut self, id: u32, enable: bool) {
+        let offset = if enable {
+            GICD_ISENABLER
+        } else {
+            GICD_ICENABLER
+        };
+        self.gicd_write(offset + (id as usize / 32) * 4, 1 << (id % 32));
+    }
+
+    /// Set the priority of an interrupt
+    ///
+    /// Lower values are more urgent. The GIC may ignore some of the low bits.
+    pub fn set_priority(&mut self, id: u32, priority: u8) {
+        self.gicd_write_byte(GICD_IPRIORITYR + id as usize, priority);
+    }
+
+    /// Route an SPI to the cores whose bits are set in `cores`
+    pub fn set_targets(&mut self, id: u32, cores: u8) {
+        self.gicd_write_byte(GICD_ITARGETSR + id as usize, cores);
+    }
+
+    /// Send Software Generated Interrupt `id` (0 to 15)
+    pub fn send_sgi(&mut self, id: u32, target: SgiTarget) {
+        let (filter, list) = match target {
+            SgiTarget::List(list) => (0, list),
+            SgiTarget::AllOthers => (1, 0),
+            SgiTarget::Current => (2, 0),
+        };
+        // Make sure the target core sees anything we wrote before the SGI
+        #[cfg(target_arch = "arm")]
+        crate::asm::dsb();
+        self.gicd_write(
+            GICD_SGIR,
+            (filter << 24) | (u32::from(list) << 16) | (id & 0xF),
+        );
+    }
+
+    fn gicd_read(&self, offset: usize) -> u32 {
+        // Safety: `new` says gicd points at a Distributor, and offset is one
+        // of its registers
+        unsafe { self.gicd.add(offset).cast::<u32>().read_volatile() }
+    }
+
+    fn gicd_read_byte(&self, offset: usize) -> u8 {
+        // Safety: As above
+        unsafe { self.gicd.add(offset).read_volatile() }
+    }
+
+    fn gicd_write(&mut self, offset: usize, value: u32) {
+        // Safety: As above
+        unsafe { self.gicd.add(offset).cast::<u32>().write_volatile(value) }
+    }
+
+    fn gicd_write_byte(&mut self, offset: usize, value: u8) {
+        // Safety: As above
+        unsafe { self.gicd.add(offset).write_volatile(value) }
+    }
+
+    fn gicc_read(&self, offset: usize) -> u32 {
+        // Safety: `new` says gicc points at a CPU Interface, and offset is one
+        // of its registers
+        unsafe { self.gicc.add(offset).cast::<u32>().read_volatile() }
+    }
+
+    fn gicc_write(&mut self, offset: usize, value: u32) {
+        // Safety: As above
+        unsafe { self.gicc.add(offset).cast::<u32>().write_volatile(value) }
+    }
+}
+
+impl InterruptController for GicV2 {
+    #[inline]
+    fn acknowledge(&mut self) -> Option<u32> {
+        let iar = self.gicc_read(GICC_IAR);
+        let intid = iar & 0x3FF;
+        if (SPECIAL_INTID_START..1024).contains(&intid) {
+            return None;
+        }
+        if let Some(source) = self.sgi_source.get_mut(intid as usize) {
+            // We have to hand this back when we complete the SGI
+            *source = ((iar >> 10) & 0x7) as u8;
+        }
+        Some(intid)
+    }
+
+    #[inline]
+    fn end_of_interrupt(&mut self, id: u32) {
+        let source = self.sgi_source.get(id as usize).copied().unwrap_or(0);
+        self.gicc_write(GICC_EOIR, (u32::from(source) << 10) | id);
+    }
+}
@@ -37,23 +37,194 @@ pub fn disable() {
     compiler_fence(Ordering::SeqCst);
 }
 
+/// Enable fast interrupts (FIQ)
+///
+/// * Doesn't work in User mode.
+/// * Doesn't enable IRQ.
+///
+/// # Safety
+///
+/// Do not call this function inside an FIQ-based critical section
+#[inline]
+pub unsafe fn enable_fiq() {
+    // Ensure no preceeding memory accesses are reordered to after FIQs are enabled.
+    compiler_fence(Ordering::SeqCst);
+    // Safety: We're atomically setting a bit in a special register, and we're
+    // in an unsafe function that places restrictions on when you can call it
+    #[cfg(target_arch = "arm")]
+    unsafe {
+        core::arch::asm!("dsb", "cpsie f", options(nomem, nostack, preserves_flags));
+    };
+}
+
+/// Disable fast interrupts (FIQ)
+///
+/// * Doesn't work in User mode.
+/// * Doesn't disable IRQ.
+#[inline]
+pub fn disable_fiq() {
+    // Safety: We're atomically clearing a bit in a special register
+    #[cfg(target_arch = "arm")]
+    unsafe {
+        core::arch::asm!("cpsid f", "dsb", options(nomem, nostack, preserves_flags));
+    };
+    // Ensure no subsequent memory accesses are reordered to before FIQs are disabled.
+    compiler_fence(Ordering::SeqCst);
+}
+
+/// Enable both interrupts (IRQ) and fast interrupts (FIQ)
+///
+/// * Doesn't work in User mode.
+///
+/// # Safety
+///
+/// Do not call this function inside an IRQ-based or FIQ-based critical section
+#[inline]
+pub unsafe fn enable_all() {
+    // Ensure no preceeding memory accesses are reordered to after interrupts are enabled.
+    compiler_fence(Ordering::SeqCst);
+    // Safety: We're atomically setting bits in a special register, and we're
+    // in an unsafe function that places restrictions on when you can call it
+    #[cfg(target_arch = "arm")]
+    unsafe {
+        core::arch::asm!("dsb", "cpsie if", options(nomem, nostack, preserves_flags));
+    };
+}
+
+/// Disable both interrupts (IRQ) and fast interrupts (FIQ)
+///
+/// * Doesn't work in User mode.
+#[inline]
+pub fn disable_all() {
+    // Safety: We're atomically clearing bits in a special register
+    #[cfg(target_arch = "arm")]
+    unsafe {
+        core::arch::asm!("cpsid if", "dsb", options(nomem, nostack, preserves_flags));
+    };
+    // Ensure no subsequent memory accesses are reordered to before interrupts are disabled.
+    compiler_fence(Ordering::SeqCst);
+}
+
 /// Run with interrupts disabled
 ///
+/// Interrupts are restored to how they were beforehand, so this can be
+/// nested. Like `cortex_m::interrupt::free`, but without the `CriticalSection`
+/// token - use the `critical-section` crate if you need one.
+///
 /// * Doesn't work in User mode.
-/// * Doesn't disable FIQ.
+/// * Doesn't disable FIQ - see [`free_all`].
 #[inline]
 pub fn free<F, T>(f: F) -> T
 where
     F: FnOnce() -> T,
 {
+    let _guard = mask();
+    f()
+}
+
+/// Run with interrupts and fast interrupts disabled
+///
+/// Like [`free`], but also masks FIQ. The previous state of both is restored
+/// afterwards.
+///
+/// * Doesn't work in User mode.
+///
+/// ```rust ignore
+/// let value = cortex_ar::interrupt::free_all(|| SHARED_WITH_FIQ.load(Ordering::Relaxed));
+/// ```
+#[inline]
+pub fn free_all<F, T>(f: F) -> T
+where
+    F: FnOnce() -> T,
+{
+    let _guard = mask_all();
+    f()
+}
+
+/// Disable interrupts until the returned guard is dropped
+///
+/// * Doesn't work in User mode.
+/// * Doesn't disable FIQ.
+///
+/// ```rust ignore
+/// fn update() -> Result<(), Error> {
+///     let _guard = cortex_ar::interrupt::mask();
+///     step_one()?;
+///     step_two()
+/// }
+/// ```
+#[inline]
+pub fn mask() -> MaskGuard {
     let cpsr = crate::register::Cpsr::read();
     disable();
-    let result = f();
-    if cpsr.i() {
-        // Safety: We're only turning them back on if they were on previously
-        unsafe {
-            enable();
+    MaskGuard {
+        // the i bit means "masked"
+        was_enabled: !cpsr.i(),
+        fiq_was_enabled: false,
+        _not_send: core::marker::PhantomData,
+    }
+}
+
+/// Disable interrupts and fast interrupts until the returned guard is dropped
+///
+/// Like [`mask`], but also masks FIQ, for code that shares data with an FIQ
+/// handler.
+///
+/// * Doesn't work in User mode.
+#[inline]
+pub fn mask_all() -> MaskGuard {
+    let cpsr = crate::register::Cpsr::read();
+    disable_all();
+    MaskGuard {
+        // the i and f bits mean "masked"
+        was_enabled: !cpsr.i(),
+        fiq_was_enabled: !cpsr.f(),
+        _not_send: core::marker::PhantomData,
+    }
+}
+
+/// Restores the previous interrupt state when dropped
+///
+/// Created by [`mask`] or [`mask_all`].
+#[must_use = "interrupts are restored as soon as the guard is dropped"]
+pub struct MaskGuard {
+    was_enabled: bool,
+    fiq_was_enabled: bool,
+    // Interrupt masking is per-core, so this must be dropped where it was created
+    _not_send: core::marker::PhantomData<*const ()>,
+}
+
+impl Drop for MaskGuard {
+    #[inline]
+    fn drop(&mut self) {
+        if self.fiq_was_enabled {
+            // Safety: We're only turning them back on if they were on previously
+            unsafe {
+                enable_fiq();
+            }
+        }
+        if self.was_enabled {
+            // Safety: We're only turning them back on if they were on previously
+            unsafe {
+                enable();
+            }
         }
     }
-    result
+}
+
+/// Something that can tell us which interrupt is pending
+///
+/// Typically implemented by an interrupt controller driver, such as the
+/// [GICv3 CPU Interface](crate::gic::GicV3CpuInterface).
+pub trait InterruptController {
+    /// Acknowledge the highest priority pending interrupt
+    ///
+    /// Returns the ID of the interrupt, or `None` if no interrupt was pending.
+    fn acknowledge(&mut self) -> Option<u32>;
+
+    /// Signal that we have finished handling the given interrupt
+    ///
+    /// Must only be passed an ID previously returned by
+    /// [`InterruptController::acknowledge`].
+    fn end_of_interrupt(&mut self, id: u32);
 }
@@ -2,24 +2,77 @@
 
 #![no_std]
 
+#[cfg(all(feature = "mock", not(target_arch = "arm")))]
+extern crate std;
+
 mod critical_section;
 
+pub mod addr;
+
 #[cfg(target_arch = "arm")]
 pub mod asm;
 
+#[cfg(any(target_arch = "arm", feature = "critical-section"))]
+pub mod atomic64;
+
+#[cfg(target_arch = "arm")]
+pub mod cache;
+
+#[cfg(feature = "critical-section-watchdog")]
+pub mod cs_watchdog;
+
+pub mod dcc;
+
+#[cfg(target_arch = "arm")]
+pub mod delay;
+
+#[cfg(target_arch = "arm")]
+pub mod debug;
+
+pub mod exception;
+pub mod fault;
+pub mod features;
+pub mod gic;
 pub mod interrupt;
+pub mod memory;
 pub mod mmu;
+
+#[cfg(target_arch = "arm")]
+pub mod pmu;
+
 pub mod register;
 
+pub mod sync;
+
+pub mod task;
+
+#[cfg(any(test, arm_profile = "r"))]
+pub mod tcm;
+
 #[cfg(any(test, arm_architecture = "v7-r"))]
 pub mod pmsav7;
 
-#[cfg(any(test, arm_architecture = "v8-r"))]
+#[cfg(any(test, arm_architecture = "v8-r", arm_profile = "a"))]
 pub mod generic_timer;
 
 #[cfg(any(test, arm_architecture = "v8-r"))]
 pub mod pmsav8;
 
+#[cfg(any(test, arm_profile = "a"))]
+pub mod psci;
+
+#[cfg(any(test, arm_profile = "a"))]
+pub mod smccc;
+
+#[cfg(all(target_arch = "arm", any(arm_architecture = "v8-r", arm_profile = "a")))]
+pub mod vectors;
+
+#[cfg(arm_profile = "a")]
+pub mod security;
+
+#[cfg(all(target_arch = "arm", arm_architecture = "v8-r"))]
+pub mod vmid;
+
 /// Generate an SVC call with the given argument.
 ///
 /// Safe to call even in Supervisor (SupervisorCall) mode, as long as your Svc handler
@@ -32,3 +85,17 @@ macro_rules! svc {
         }
     }
 }
+
+/// Generate an HVC call with the given argument.
+///
+/// Only for Armv8-R, or Armv7-A with the Virtualization Extensions, and only
+/// from EL1 (a Non-secure PL1 mode). The argument is a 16-bit immediate, which
+/// the hypervisor can read from HSR.
+#[macro_export]
+macro_rules! hvc {
+    ($imm:expr) => {
+        unsafe {
+            core::arch::asm!(".arch_extension virt", "hvc {arg}", arg = const $imm);
+        }
+    }
+}
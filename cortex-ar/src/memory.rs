@@ -0,0 +1,211 @@
+//! Copying memory which might not be there
+//!
+//! [`try_copy`] copies bytes like `core::ptr::copy_nonoverlapping`, except
+//! that if either buffer causes a Data Abort (because it's unmapped, or it's a
+//! peripheral that doesn't respond) you get an `Err` instead of a crash. This
+//! is handy for on-target debuggers, for copying system call arguments out of
+//! a task's memory, and for memory scrubbers.
+//!
+//! It works with your Data Abort handler, which must call
+//! [`recovery_address`] with the address of the faulting instruction. If that
+//! returns `Some`, return that address from the handler, and execution resumes
+//! there. The default Data Abort handlers in `cortex-r-rt` and `cortex-a-rt`
+//! already do this.
+//!
+//! ```rust ignore
+//! #[exception(DataAbort)]
+//! fn data_abort_handler(addr: usize) -> usize {
+//!     if let Some(fixup) = cortex_ar::memory::recovery_address(addr) {
+//!         return fixup;
+//!     }
+//!     panic!("Data Abort at {:#x}", addr);
+//! }
+//! ```
+//!
+//! You can register your own assembly language routines in the same way, with
+//! [`set_recovery_table`].
+
+use core::sync::atomic::{AtomicPtr, Ordering};
+
+use crate::register::Dfsr;
+
+/// A memory access in [`try_copy`] caused a Data Abort
+#[derive(Debug, Clone, Copy)]
+pub struct Fault {
+    /// The address that could not be accessed (from DFAR)
+    pub address: usize,
+    /// Why it could not be accessed
+    pub dfsr: Dfsr,
+}
+
+/// A range of code that may fault, and where to go if it does
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RecoveryEntry {
+    /// The address of the first instruction that may fault
+    pub start: usize,
+    /// The address just after the last instruction that may fault
+    pub end: usize,
+    /// Where to resume execution if an instruction in `start..end` faults
+    pub fixup: usize,
+}
+
+/// A set of [`RecoveryEntry`] values, registered with [`set_recovery_table`]
+#[derive(Debug)]
+pub struct RecoveryTable {
+    /// The entries in the table
+    pub entries: &'static [RecoveryEntry],
+}
+
+/// The table registered with [`set_recovery_table`], or null
+static RECOVERY_TABLE: AtomicPtr<RecoveryTable> = AtomicPtr::new(core::ptr::null_mut());
+
+/// Register a table of code ranges that may fault
+///
+/// [`recovery_address`] looks in this table, as well as checking for faults in
+/// [`try_copy`]. Returns the table previously registered, if any.
+pub fn set_recovery_table(table: &'static RecoveryTable) -> Option<&'static RecoveryTable> {
+    let old = RECOVERY_TABLE.swap(
+        table as *const RecoveryTable as *mut RecoveryTable,
+        Ordering::AcqRel,
+    );
+    // Safety: we only ever store null pointers, or a `&'static RecoveryTable`
+    unsafe { old.as_ref() }
+}
+
+/// Find where to resume after a Data Abort at the given instruction
+///
+/// Returns `None` if the instruction isn't one we know how to recover from.
+pub fn recovery_address(addr: usize) -> Option<usize> {
+    #[cfg(target_arch = "arm")]
+    if let Some(fixup) = try_copy_recovery().fixup_for(addr) {
+        return Some(fixup);
+    }
+    let table = RECOVERY_TABLE.load(Ordering::Acquire);
+    // Safety: we only ever store null pointers, or a `&'static RecoveryTable`
+    let table = unsafe { table.as_ref() }?;
+    table.entries.iter().find_map(|entry| entry.fixup_for(addr))
+}
+
+impl RecoveryEntry {
+    /// Get the fixup address, if `addr` is in this entry's range
+    fn fixup_for(&self, addr: usize) -> Option<usize> {
+        (self.start..self.end).contains(&addr).then_some(self.fixup)
+    }
+}
+
+/// Copy `len` bytes from `src` to `dst`, stopping if either causes a Data Abort
+///
+/// The bytes are copied one at a time, in order, so if you get an `Err`, some
+/// of them may have been copied.
+///
+/// # Safety
+///
+/// As for `core::ptr::copy_nonoverlapping`, except that either buffer may be
+/// unmapped or otherwise inaccessible. Your Data Abort handler must call
+/// [`recovery_address`].
+#[cfg(target_arch = "arm")]
+pub unsafe fn try_copy(dst: *mut u8, src: *const u8, len: usize) -> Result<(), Fault> {
+    extern "C" {
+        fn __cortex_ar_try_copy(dst: *mut u8, src: *const u8, len: usize) -> u32;
+    }
+    // Safety: Any fault is caught and turned into a non-zero return value
+    if unsafe { __cortex_ar_try_copy(dst, src, len) } == 0 {
+        Ok(())
+    } else {
+        // The Data Abort handler has returned, so these are still ours
+        Err(Fault {
+            address: crate::register::Dfar::read().0 as usize,
+            dfsr: Dfsr::read(),
+        })
+    }
+}
+
+/// The recovery entry for the copy loop in `__cortex_ar_try_copy`
+#[cfg(target_arch = "arm")]
+fn try_copy_recovery() -> RecoveryEntry {
+    extern "C" {
+        fn __cortex_ar_try_copy_start();
+        fn __cortex_ar_try_copy_end();
+        fn __cortex_ar_try_copy_fixup();
+    }
+    // Clear the Thumb bit, in case we were assembled as T32
+    RecoveryEntry {
+        start: __cortex_ar_try_copy_start as *const () as usize & !1,
+        end: __cortex_ar_try_copy_end as *const () as usize & !1,
+        fixup: __cortex_ar_try_copy_fixup as *const () as usize & !1,
+    }
+}
+
+#[cfg(target_arch = "arm")]
+core::arch::global_asm!(
+    r#"
+    // `extern "C" fn __cortex_ar_try_copy(dst: *mut u8, src: *const u8, len: usize) -> u32;`
+    //
+    // Returns 0 if the copy worked, or 1 if it faulted.
+    .section .text.__cortex_ar_try_copy
+    .global __cortex_ar_try_copy
+    .type __cortex_ar_try_copy, %function
+    __cortex_ar_try_copy:
+        cmp     r2, #0
+        beq     __cortex_ar_try_copy_end
+    .global __cortex_ar_try_copy_start
+    __cortex_ar_try_copy_start:
+        // only these instructions can fault
+        ldrb    r3, [r1], #1
+        strb    r3, [r0], #1
+        subs    r2, r2, #1
+        bne     __cortex_ar_try_copy_start
+    .global __cortex_ar_try_copy_end
+    __cortex_ar_try_copy_end:
+        mov     r0, #0
+        bx      lr
+    // The Data Abort handler returns here if the copy faults
+    .global __cortex_ar_try_copy_fixup
+    __cortex_ar_try_copy_fixup:
+        mov     r0, #1
+        bx      lr
+    .size __cortex_ar_try_copy, . - __cortex_ar_try_copy
+    "#
+);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const ENTRY: RecoveryEntry = RecoveryEntry {
+        start: 0x1000,
+        end: 0x1010,
+        fixup: 0x2000,
+    };
+
+    #[test]
+    fn entry_range() {
+        assert_eq!(ENTRY.fixup_for(0x0FFF), None);
+        assert_eq!(ENTRY.fixup_for(0x1000), Some(0x2000));
+        assert_eq!(ENTRY.fixup_for(0x100C), Some(0x2000));
+        // `end` is just after the last instruction that may fault
+        assert_eq!(ENTRY.fixup_for(0x1010), None);
+    }
+
+    #[test]
+    fn table_lookup() {
+        static TABLE: RecoveryTable = RecoveryTable {
+            entries: &[
+                ENTRY,
+                RecoveryEntry {
+                    start: 0x3000,
+                    end: 0x3004,
+                    fixup: 0x4000,
+                },
+            ],
+        };
+        // This is the only test which registers a table
+        assert_eq!(recovery_address(0x1000), None);
+        assert!(set_recovery_table(&TABLE).is_none());
+        assert_eq!(recovery_address(0x1004), Some(0x2000));
+        assert_eq!(recovery_address(0x3000), Some(0x4000));
+        assert_eq!(recovery_address(0x3004), None);
+        assert_eq!(recovery_address(0x2000), None);
+        assert!(core::ptr::eq(set_recovery_table(&TABLE).unwrap(), &TABLE));
+    }
+}
@@ -1,5 +1,7 @@
 use arbitrary_int::{u12, u2, u3, u4};
 
+use crate::addr::{PhysAddr, VirtAddr};
+
 #[derive(Debug, Copy, Clone)]
 #[repr(u8)]
 pub enum AccessPermissions {
@@ -197,13 +199,25 @@ impl L1Section {
     ///
     /// # Panics
     ///
-    /// Physcal address not aligned to 1 MB.
-    pub const fn new(phys_addr: u32, section_attrs: SectionAttributes) -> Self {
+    /// Physcal address not aligned to 1 MB. See [`L1Section::try_new`] for a
+    /// version which doesn't panic.
+    pub const fn new(phys_addr: PhysAddr, section_attrs: SectionAttributes) -> Self {
+        match Self::try_new(phys_addr, section_attrs) {
+            Some(section) => section,
+            None => panic!("physical base address for L1 section must be aligned to 1 MB"),
+        }
+    }
+
+    /// Generates a new L1 section from a physical address and section attributes.
+    ///
+    /// Like [`L1Section::new`], except it returns `None` if the physical address
+    /// is not aligned to 1 MB.
+    pub const fn try_new(phys_addr: PhysAddr, section_attrs: SectionAttributes) -> Option<Self> {
         // Must be aligned to 1 MB
-        if phys_addr & 0x000F_FFFF != 0 {
-            panic!("physical base address for L1 section must be aligned to 1 MB");
+        if !phys_addr.is_aligned(0x10_0000) {
+            return None;
         }
-        let higher_bits = phys_addr >> 20;
+        let higher_bits = phys_addr.as_u32() >> 20;
         let raw = (higher_bits << 20)
             | ((section_attrs.non_global as u32) << 17)
             | ((section_attrs.shareable as u32) << 16)
@@ -216,6 +230,677 @@ impl L1Section {
             | ((section_attrs.memory_attrs.c as u32) << 3)
             | ((section_attrs.memory_attrs.b as u32) << 2)
             | L1EntryType::Section as u32;
-        Self::new_with_raw_value(raw)
+        Some(Self::new_with_raw_value(raw))
+    }
+
+    /// The physical address of the 1 MB section this entry maps to
+    pub const fn phys_addr(&self) -> PhysAddr {
+        PhysAddr::new((self.raw_value() & 0xFFF0_0000) as usize)
+    }
+}
+
+/// 16 MB supersection translation entry, mapping a 16 MB region to a physical
+/// address.
+///
+/// A supersection has to be written to 16 consecutive entries in the L1
+/// table - [`L1Table::set_supersection`] does that for you. Supersections are
+/// always in domain 0, because the domain bits hold extended address bits
+/// instead (which we set to zero).
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct L1Supersection(u32);
+
+impl L1Supersection {
+    /// Generates a new L1 supersection from a physical address and section
+    /// attributes.
+    ///
+    /// The `domain` attribute is ignored.
+    ///
+    /// # Panics
+    ///
+    /// Physcal address not aligned to 16 MB. See [`L1Supersection::try_new`]
+    /// for a version which doesn't panic.
+    pub const fn new(phys_addr: PhysAddr, section_attrs: SectionAttributes) -> Self {
+        match Self::try_new(phys_addr, section_attrs) {
+            Some(supersection) => supersection,
+            None => panic!("physical base address for L1 supersection must be aligned to 16 MB"),
+        }
+    }
+
+    /// Generates a new L1 supersection from a physical address and section
+    /// attributes.
+    ///
+    /// Like [`L1Supersection::new`], except it returns `None` if the physical
+    /// address is not aligned to 16 MB.
+    pub const fn try_new(phys_addr: PhysAddr, section_attrs: SectionAttributes) -> Option<Self> {
+        if !phys_addr.is_aligned(SUPERSECTION_SIZE) {
+            return None;
+        }
+        let raw = (phys_addr.as_u32() & 0xFF00_0000)
+            | (1 << 18)
+            | ((section_attrs.non_global as u32) << 17)
+            | ((section_attrs.shareable as u32) << 16)
+            | ((section_attrs.access.apx() as u32) << 15)
+            | ((section_attrs.memory_attrs.type_extensions as u32) << 12)
+            | ((section_attrs.access.ap() as u32) << 10)
+            | ((section_attrs.p_bit as u32) << 9)
+            | ((section_attrs.execute_never as u32) << 4)
+            | ((section_attrs.memory_attrs.c as u32) << 3)
+            | ((section_attrs.memory_attrs.b as u32) << 2)
+            | L1EntryType::Section as u32;
+        Some(Self(raw))
+    }
+
+    /// The raw value of this entry
+    pub const fn raw_value(&self) -> u32 {
+        self.0
+    }
+
+    /// The physical address of the 16 MB supersection this entry maps to
+    pub const fn phys_addr(&self) -> PhysAddr {
+        PhysAddr::new((self.0 & 0xFF00_0000) as usize)
+    }
+}
+
+/// The size of a section, in bytes
+pub const SECTION_SIZE: usize = 1024 * 1024;
+
+/// The size of a supersection, in bytes
+pub const SUPERSECTION_SIZE: usize = 16 * SECTION_SIZE;
+
+/// Ways the [`L1Table`] API can fail
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    /// An address or length was not a multiple of the section (or
+    /// supersection) size
+    Unaligned,
+    /// The range goes past the end of the 4 GB address space
+    OutOfRange,
+}
+
+/// An L1 short-descriptor translation table, covering all 4 GB of address
+/// space with 1 MB entries.
+///
+/// It's 16 KB in size, and aligned to 16 KB as TTBR0 requires, so you
+/// probably want to put it in a `static`. Every entry starts off as a fault
+/// entry, so any address you don't map gives a translation fault.
+///
+/// ```rust
+/// use cortex_ar::addr::{PhysAddr, VirtAddr};
+/// use cortex_ar::mmu::{
+///     AccessPermissions, L1Table, MemoryRegionAttributes, SectionAttributes,
+/// };
+///
+/// const NORMAL: SectionAttributes = SectionAttributes {
+///     non_global: false,
+///     p_bit: false,
+///     shareable: false,
+///     access: AccessPermissions::FullAccess,
+///     memory_attrs: MemoryRegionAttributes::OuterAndInnerWriteBackWriteAlloc.as_raw(),
+///     domain: 0,
+///     execute_never: false,
+/// };
+///
+/// let mut table = L1Table::new();
+/// // Identity map the first 128 MB
+/// table
+///     .map_range(VirtAddr::new(0), PhysAddr::new(0), 128 * 1024 * 1024, NORMAL)
+///     .unwrap();
+/// ```
+#[repr(C, align(16384))]
+pub struct L1Table {
+    entries: [u32; L1Table::NUM_ENTRIES],
+}
+
+impl L1Table {
+    /// How many entries there are in the table
+    pub const NUM_ENTRIES: usize = 4096;
+
+    /// Make a table where every entry is a fault entry
+    pub const fn new() -> L1Table {
+        L1Table {
+            entries: [0; L1Table::NUM_ENTRIES],
+        }
+    }
+
+    /// Get the raw entry which covers the given virtual address
+    pub fn entry(&self, virt_addr: VirtAddr) -> u32 {
+        self.entries[virt_addr.as_usize() / SECTION_SIZE]
+    }
+
+    /// Map the 1 MB at the given virtual address with a section
+    ///
+    /// Returns [`Error::Unaligned`] if the virtual address isn't aligned to
+    /// 1 MB.
+    pub fn set_section(&mut self, virt_addr: VirtAddr, section: L1Section) -> Result<(), Error> {
+        if !virt_addr.is_aligned(SECTION_SIZE) {
+            return Err(Error::Unaligned);
+        }
+        self.entries[virt_addr.as_usize() / SECTION_SIZE] = section.raw_value();
+        Ok(())
+    }
+
+    /// Map the 16 MB at the given virtual address with a supersection
+    ///
+    /// Returns [`Error::Unaligned`] if the virtual address isn't aligned to
+    /// 16 MB.
+    pub fn set_supersection(
+        &mut self,
+        virt_addr: VirtAddr,
+        supersection: L1Supersection,
+    ) -> Result<(), Error> {
+        if !virt_addr.is_aligned(SUPERSECTION_SIZE) {
+            return Err(Error::Unaligned);
+        }
+        let first = virt_addr.as_usize() / SECTION_SIZE;
+        let count = SUPERSECTION_SIZE / SECTION_SIZE;
+        self.entries[first..first + count].fill(supersection.raw_value());
+        Ok(())
+    }
+
+    /// Map `len` bytes at `virt_addr` to `phys_addr`, with the given
+    /// attributes
+    ///
+    /// Uses supersections where the virtual and physical addresses allow it,
+    /// and sections everywhere else. The addresses and the length must be
+    /// multiples of 1 MB.
+    pub fn map_range(
+        &mut self,
+        virt_addr: VirtAddr,
+        phys_addr: PhysAddr,
+        len: usize,
+        attrs: SectionAttributes,
+    ) -> Result<(), Error> {
+        let range = Self::check_range(virt_addr, len)?;
+        if len == 0 {
+            return Ok(());
+        }
+        if !phys_addr.is_aligned(SECTION_SIZE) {
+            return Err(Error::Unaligned);
+        }
+        if phys_addr.checked_add(len - 1).is_none() {
+            return Err(Error::OutOfRange);
+        }
+        let mut offset = 0;
+        while offset < len {
+            let virt = virt_addr.as_usize() + offset;
+            let phys = phys_addr.as_usize() + offset;
+            let idx = range.start + offset / SECTION_SIZE;
+            if virt % SUPERSECTION_SIZE == 0
+                && phys % SUPERSECTION_SIZE == 0
+                && len - offset >= SUPERSECTION_SIZE
+            {
+                let entry = L1Supersection::new(PhysAddr::new(phys), attrs).raw_value();
+                self.entries[idx..idx + SUPERSECTION_SIZE / SECTION_SIZE].fill(entry);
+                offset += SUPERSECTION_SIZE;
+            } else {
+                self.entries[idx] = L1Section::new(PhysAddr::new(phys), attrs).raw_value();
+                offset += SECTION_SIZE;
+            }
+        }
+        Ok(())
+    }
+
+    /// Turn `len` bytes at `virt_addr` back into fault entries
+    ///
+    /// The address and length must be multiples of 1 MB. If you unmap part
+    /// of a supersection, you must unmap the rest of it too.
+    pub fn unmap_range(&mut self, virt_addr: VirtAddr, len: usize) -> Result<(), Error> {
+        let range = Self::check_range(virt_addr, len)?;
+        self.entries[range].fill(0);
+        Ok(())
+    }
+
+    /// Get a pointer to the table, for writing to TTBR0
+    pub fn as_ptr(&self) -> *const u32 {
+        self.entries.as_ptr()
+    }
+
+    /// Check a virtual address range, and convert it into a range of entries
+    fn check_range(virt_addr: VirtAddr, len: usize) -> Result<core::ops::Range<usize>, Error> {
+        if !virt_addr.is_aligned(SECTION_SIZE) || len % SECTION_SIZE != 0 {
+            return Err(Error::Unaligned);
+        }
+        let first = virt_addr.as_usize() / SECTION_SIZE;
+        let last = first + len / SECTION_SIZE;
+        if last > L1Table::NUM_ENTRIES {
+            return Err(Error::OutOfRange);
+        }
+        Ok(first..last)
+    }
+}
+
+impl Default for L1Table {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Turn on the MMU (and the L1 caches) using the given translation table
+///
+/// We:
+///
+/// 1. invalidate the data and instruction caches, the branch predictor and
+///    the TLBs, because they may hold junk from before reset
+/// 2. make every domain a client, so the access permissions are checked
+/// 3. point TTBR0 at the table (and set TTBCR so only TTBR0 is used)
+/// 4. enable the MMU, the data and instruction caches and branch prediction
+///
+/// with barriers in between, so each step has taken effect before the next.
+///
+/// # Safety
+///
+/// The MMU and data cache must be off, because anything in the data cache is
+/// thrown away. The table must map the code that is running, its stack, and
+/// anything else you are using, at the same addresses they have now.
+#[cfg(target_arch = "arm")]
+pub unsafe fn enable(table: &'static L1Table) {
+    use crate::register::{Dacr, Sctlr, Tlbiall, Ttbcr, Ttbr0};
+
+    // Safety: Up to the caller
+    unsafe {
+        crate::cache::invalidate_dcache_all();
+    }
+    crate::cache::invalidate_icache_all();
+    // Safety: Up to the caller
+    unsafe {
+        Tlbiall::write(Tlbiall(0));
+        // Every domain is a client
+        Dacr::write(Dacr(0x5555_5555));
+        // Only use TTBR0
+        Ttbcr::write(Ttbcr(0));
+        Ttbr0::write(Ttbr0(table.as_ptr() as u32));
+    }
+    crate::asm::dsb();
+    crate::asm::isb();
+    Sctlr::modify(|w| {
+        w.set_m(true);
+        w.set_c(true);
+        w.set_i(true);
+        w.set_z(true);
+    });
+    crate::asm::isb();
+}
+
+/// ASID 0 is reserved for use whilst switching address spaces
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReservedAsid;
+
+/// Switch to a different set of translation tables and ASID
+///
+/// `ttbr0` is the value to write to TTBR0 (the address of the L1 table, plus
+/// the table walk attribute bits), and `asid` is the Address Space Identifier
+/// for the new address space. The PROCID bits in CONTEXTIDR are kept.
+///
+/// The TTBR0 and ASID can't be changed at the same time, so this uses the
+/// sequence recommended in the Arm Architecture Reference Manual (B3.10.4):
+/// switch to the reserved ASID 0, change TTBR0, then switch to the new ASID.
+/// That stops the processor from making TLB entries for the new ASID from the
+/// old tables (or the other way around) in between. It then invalidates the
+/// branch predictor, which may not be tagged with the ASID.
+///
+/// Returns `Err` (and changes nothing) if `asid` is 0, because you must not
+/// use ASID 0 for anything else, or mark any translations as non-global when
+/// TTBR0 points at tables which might be used with it.
+///
+/// # Safety
+///
+/// `ttbr0` must point to a valid set of translation tables, which map the code
+/// that is running (and its stack) the same way the current tables do.
+#[cfg(target_arch = "arm")]
+pub unsafe fn switch_address_space(ttbr0: u32, asid: u8) -> Result<(), ReservedAsid> {
+    if asid == 0 {
+        return Err(ReservedAsid);
+    }
+    // Safety: up to the caller
+    unsafe {
+        switch_ttbr0_and_asid(ttbr0, asid, false);
+    }
+    Ok(())
+}
+
+/// Switch TTBR0 and the ASID, via the reserved ASID 0
+///
+/// If `invalidate_tlb` is set, the whole TLB is invalidated whilst we're on
+/// ASID 0, so nothing can be looked up between the invalidate and the switch.
+#[cfg(target_arch = "arm")]
+unsafe fn switch_ttbr0_and_asid(ttbr0: u32, asid: u8, invalidate_tlb: bool) {
+    use crate::register::{Bpiall, Contextidr, Tlbiall, Ttbr0};
+
+    let procid = Contextidr::read().0 & !0xFF;
+    // Safety: ASID 0 is reserved, so no TLB entries are made for it
+    unsafe {
+        Contextidr::write(Contextidr(procid));
+    }
+    crate::asm::isb();
+    // Safety: up to the caller
+    unsafe {
+        Ttbr0::write(Ttbr0(ttbr0));
+    }
+    if invalidate_tlb {
+        // Safety: this only throws away cached translations
+        unsafe {
+            Tlbiall::write(Tlbiall(0));
+        }
+        crate::asm::dsb();
+    }
+    crate::asm::isb();
+    // Safety: the new tables are in place, so we can use the new ASID
+    unsafe {
+        Contextidr::write(Contextidr(procid | u32::from(asid)));
+        Bpiall::write(Bpiall(0));
+    }
+    crate::asm::dsb();
+    crate::asm::isb();
+}
+
+/// An Address Space Identifier, handed out by an [`AsidAllocator`]
+///
+/// Keep one of these with each address space (e.g. each process), starting
+/// with [`Asid::UNALLOCATED`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Asid {
+    value: u8,
+    generation: u32,
+}
+
+impl Asid {
+    /// An ASID which hasn't been allocated yet
+    pub const UNALLOCATED: Asid = Asid {
+        value: 0,
+        generation: 0,
+    };
+
+    /// The value that goes in CONTEXTIDR
+    ///
+    /// This is zero if the ASID hasn't been allocated.
+    pub const fn value(self) -> u8 {
+        self.value
+    }
+}
+
+/// Hands out ASIDs to address spaces, and switches between them
+///
+/// There are only 255 ASIDs to give out (ASID 0 is reserved). When they run
+/// out, the allocator starts a new *generation*: every ASID handed out before
+/// then becomes stale, and the TLB is invalidated so that no translations
+/// tagged with an old ASID survive. An address space with a stale ASID gets a
+/// new one the next time it is switched to.
+///
+/// Use one allocator for the whole system. It only invalidates the TLB on the
+/// core that is switching, so it is only suitable for single-core systems.
+#[derive(Debug, Clone)]
+pub struct AsidAllocator {
+    next: u16,
+    generation: u32,
+}
+
+impl AsidAllocator {
+    /// Make a new allocator, which hasn't handed out any ASIDs
+    pub const fn new() -> AsidAllocator {
+        AsidAllocator {
+            next: 1,
+            generation: 1,
+        }
+    }
+
+    /// Is this ASID from the current generation?
+    pub const fn is_current(&self, asid: Asid) -> bool {
+        asid.value != 0 && asid.generation == self.generation
+    }
+
+    /// Make sure `asid` is from the current generation, allocating a new one
+    /// if it isn't
+    ///
+    /// Returns `true` if the ASIDs ran out and a new generation was started.
+    /// If so, you must invalidate the TLB before using the new ASID -
+    /// [`AsidAllocator::switch`] does that for you.
+    pub fn refresh(&mut self, asid: &mut Asid) -> bool {
+        if self.is_current(*asid) {
+            return false;
+        }
+        let rolled_over = self.next > u16::from(u8::MAX);
+        if rolled_over {
+            // Skip generation 0, which is used by Asid::UNALLOCATED
+            self.generation = self.generation.wrapping_add(1).max(1);
+            self.next = 1;
+        }
+        *asid = Asid {
+            value: self.next as u8,
+            generation: self.generation,
+        };
+        self.next += 1;
+        rolled_over
+    }
+
+    /// Switch to the translation tables in `ttbr0`, with the ASID in `asid`
+    ///
+    /// If `asid` is stale (or unallocated), a new one is allocated and written
+    /// back. If that starts a new generation, the TLB is invalidated as part
+    /// of the switch. See [`switch_address_space`] for how the switch is done.
+    ///
+    /// # Safety
+    ///
+    /// `ttbr0` must point to a valid set of translation tables, which map the
+    /// code that is running (and its stack) the same way the current tables
+    /// do.
+    #[cfg(target_arch = "arm")]
+    pub unsafe fn switch(&mut self, ttbr0: u32, asid: &mut Asid) {
+        let rolled_over = self.refresh(asid);
+        // Safety: up to the caller, and `refresh` never gives out ASID 0
+        unsafe {
+            switch_ttbr0_and_asid(ttbr0, asid.value, rolled_over);
+        }
+    }
+}
+
+impl Default for AsidAllocator {
+    fn default() -> Self {
+        AsidAllocator::new()
+    }
+}
+
+/// The sort of access to check, when translating an address with [`translate`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccessKind {
+    /// A read at PL1
+    PrivilegedRead,
+    /// A write at PL1
+    PrivilegedWrite,
+    /// A read at PL0 (User mode)
+    UnprivilegedRead,
+    /// A write at PL0 (User mode)
+    UnprivilegedWrite,
+}
+
+/// The result of a successful [`translate`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Translation {
+    /// The physical address the virtual address maps to
+    pub phys_addr: PhysAddr,
+    /// The PAR value, which also describes the memory attributes
+    pub par: crate::register::Par,
+}
+
+/// Ask the MMU what a virtual address maps to
+///
+/// This uses the address translation operations (ATS1CPR and friends), which
+/// walk the translation tables just like a real access would, except that a
+/// fault is reported back to us instead of causing an abort. That's handy for
+/// finding the physical address of a DMA buffer, or for debugging.
+///
+/// If the access would fault, returns the fault status (see
+/// [`Par::fault_status`](crate::register::Par::fault_status)) instead.
+#[cfg(target_arch = "arm")]
+pub fn translate(virt_addr: VirtAddr, kind: AccessKind) -> Result<Translation, u8> {
+    use crate::register::{Ats1cpr, Ats1cpw, Ats1cur, Ats1cuw, Par};
+
+    let value = virt_addr.as_u32();
+    // An interrupt handler could do a translation of its own, and overwrite PAR
+    let par = crate::interrupt::free(|| {
+        // Safety: Address translation operations only write to PAR
+        unsafe {
+            match kind {
+                AccessKind::PrivilegedRead => Ats1cpr::write(Ats1cpr(value)),
+                AccessKind::PrivilegedWrite => Ats1cpw::write(Ats1cpw(value)),
+                AccessKind::UnprivilegedRead => Ats1cur::write(Ats1cur(value)),
+                AccessKind::UnprivilegedWrite => Ats1cuw::write(Ats1cuw(value)),
+            }
+        }
+        crate::asm::isb();
+        Par::read()
+    });
+    match (par.page_addr(), par.fault_status()) {
+        (Some(page_addr), _) => {
+            let page_size = if par.supersection() {
+                SUPERSECTION_SIZE
+            } else {
+                4096
+            };
+            let offset = virt_addr.as_usize() & (page_size - 1);
+            Ok(Translation {
+                phys_addr: PhysAddr::new(page_addr.as_usize() | offset),
+                par,
+            })
+        }
+        (None, status) => Err(status.unwrap_or(0)),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const NORMAL: SectionAttributes = SectionAttributes {
+        non_global: false,
+        p_bit: false,
+        shareable: false,
+        access: AccessPermissions::FullAccess,
+        memory_attrs: MemoryRegionAttributes::OuterAndInnerWriteBackWriteAlloc.as_raw(),
+        domain: 0,
+        execute_never: false,
+    };
+
+    #[test]
+    fn supersection() {
+        let supersection = L1Supersection::new(PhysAddr::new(0x1000_0000), NORMAL);
+        assert_eq!(supersection.raw_value(), 0x1004_1C0E);
+        assert_eq!(supersection.phys_addr(), PhysAddr::new(0x1000_0000));
+        assert_eq!(
+            L1Supersection::try_new(PhysAddr::new(0x1010_0000), NORMAL),
+            None
+        );
+    }
+
+    #[test]
+    fn map_range() {
+        let mut table = L1Table::new();
+        // 17 MB, so one supersection and one section
+        table
+            .map_range(
+                VirtAddr::new(0),
+                PhysAddr::new(0x2000_0000),
+                17 * SECTION_SIZE,
+                NORMAL,
+            )
+            .unwrap();
+        let supersection = L1Supersection::new(PhysAddr::new(0x2000_0000), NORMAL).raw_value();
+        for idx in 0..16 {
+            assert_eq!(table.entry(VirtAddr::new(idx * SECTION_SIZE)), supersection);
+        }
+        assert_eq!(
+            table.entry(VirtAddr::new(16 * SECTION_SIZE)),
+            L1Section::new(PhysAddr::new(0x2100_0000), NORMAL).raw_value()
+        );
+        assert_eq!(table.entry(VirtAddr::new(17 * SECTION_SIZE)), 0);
+
+        // Not 16 MB aligned, so all sections
+        table
+            .map_range(
+                VirtAddr::new(0x4010_0000),
+                PhysAddr::new(0x4010_0000),
+                16 * SECTION_SIZE,
+                NORMAL,
+            )
+            .unwrap();
+        assert_eq!(
+            table.entry(VirtAddr::new(0x4010_0000)),
+            L1Section::new(PhysAddr::new(0x4010_0000), NORMAL).raw_value()
+        );
+
+        table
+            .unmap_range(VirtAddr::new(0), SUPERSECTION_SIZE)
+            .unwrap();
+        assert_eq!(table.entry(VirtAddr::new(0)), 0);
+    }
+
+    #[test]
+    fn map_range_errors() {
+        let mut table = L1Table::new();
+        assert_eq!(
+            table.map_range(
+                VirtAddr::new(0x1000),
+                PhysAddr::new(0),
+                SECTION_SIZE,
+                NORMAL
+            ),
+            Err(Error::Unaligned)
+        );
+        assert_eq!(
+            table.map_range(
+                VirtAddr::new(0),
+                PhysAddr::new(0x1000),
+                SECTION_SIZE,
+                NORMAL
+            ),
+            Err(Error::Unaligned)
+        );
+        assert_eq!(
+            table.map_range(VirtAddr::new(0), PhysAddr::new(0), 0x1000, NORMAL),
+            Err(Error::Unaligned)
+        );
+        assert_eq!(
+            table.map_range(
+                VirtAddr::new(0xFFF0_0000),
+                PhysAddr::new(0),
+                2 * SECTION_SIZE,
+                NORMAL
+            ),
+            Err(Error::OutOfRange)
+        );
+    }
+
+    #[test]
+    fn asid_allocation() {
+        let mut allocator = AsidAllocator::new();
+        let mut a = Asid::UNALLOCATED;
+        let mut b = Asid::UNALLOCATED;
+        assert!(!allocator.refresh(&mut a));
+        assert!(!allocator.refresh(&mut b));
+        assert_eq!(a.value(), 1);
+        assert_eq!(b.value(), 2);
+        // Already current, so it doesn't change
+        assert!(!allocator.refresh(&mut a));
+        assert_eq!(a.value(), 1);
+    }
+
+    #[test]
+    fn asid_rollover() {
+        let mut allocator = AsidAllocator::new();
+        let mut first = Asid::UNALLOCATED;
+        allocator.refresh(&mut first);
+        for _ in 2..=255 {
+            let mut asid = Asid::UNALLOCATED;
+            assert!(!allocator.refresh(&mut asid));
+        }
+        // All 255 are in use, so the next one starts a new generation
+        let mut next = Asid::UNALLOCATED;
+        assert!(allocator.refresh(&mut next));
+        assert_eq!(next.value(), 1);
+        assert!(!allocator.is_current(first));
+        assert!(allocator.is_current(next));
+        // The old one gets a new ASID when refreshed
+        assert!(!allocator.refresh(&mut first));
+        assert_eq!(first.value(), 2);
     }
 }
@@ -5,7 +5,7 @@
 //!
 //! [armv7]: https://developer.arm.com/documentation/ddi0406/latest
 
-use crate::register;
+use crate::{addr::PhysAddr, register};
 
 use arbitrary_int::{u2, u3};
 #[doc(inline)]
@@ -17,7 +17,7 @@ pub enum Error {
     /// Found too many regions
     TooManyRegions,
     /// Found a region with invalid alignment
-    UnalignedRegion(*mut u8),
+    UnalignedRegion(PhysAddr),
 }
 
 /// Represents our PMSAv7 MPU
@@ -55,7 +55,7 @@ impl Mpu {
             return None;
         }
         register::Rgnr::write(register::Rgnr(idx as u32));
-        let base = register::Irbar::read().0;
+        let base = PhysAddr::new(register::Irbar::read().0 as usize);
         let rsr = register::Irsr::read();
         let racr = register::Iracr::read();
 
@@ -67,12 +67,14 @@ impl Mpu {
         };
 
         let mem_attr = mem_attr_bits.decode()?;
+        let access = AccessPerms::from_bits(racr.ap())?;
 
         Some(Region {
             base,
             size: rsr.region_size(),
             subregion_mask: rsr.subregion_mask(),
             enabled: rsr.enabled(),
+            access,
             no_exec: racr.nx(),
             mem_attr,
         })
@@ -84,7 +86,7 @@ impl Mpu {
             return None;
         }
         register::Rgnr::write(register::Rgnr(idx as u32));
-        let base = register::Drbar::read().0;
+        let base = PhysAddr::new(register::Drbar::read().0 as usize);
         let rsr = register::Drsr::read();
         let racr = register::Dracr::read();
 
@@ -95,18 +97,129 @@ impl Mpu {
             s: racr.s(),
         };
         let mem_attr = mem_attr_bits.decode()?;
+        let access = AccessPerms::from_bits(racr.ap())?;
 
         Some(Region {
             base,
             size: rsr.region_size(),
             subregion_mask: rsr.subregion_mask(),
             enabled: rsr.enabled(),
+            access,
             no_exec: racr.nx(),
             mem_attr,
         })
     }
 
+    /// Iterate through the instruction regions, as currently programmed
+    ///
+    /// Gives you the index of each region along with the region itself, so
+    /// you can check (or print out) what the MPU actually contains. Regions
+    /// with memory attributes or access permissions we cannot decode are
+    /// skipped.
+    pub fn iregions(&mut self) -> impl Iterator<Item = (u8, Region)> + '_ {
+        (0..self.num_iregions()).filter_map(move |idx| self.get_iregion(idx).map(|r| (idx, r)))
+    }
+
+    /// Iterate through the data/unified regions, as currently programmed
+    ///
+    /// Gives you the index of each region along with the region itself, so
+    /// you can check (or print out) what the MPU actually contains. Regions
+    /// with memory attributes or access permissions we cannot decode are
+    /// skipped.
+    pub fn dregions(&mut self) -> impl Iterator<Item = (u8, Region)> + '_ {
+        (0..self.num_dregions()).filter_map(move |idx| self.get_dregion(idx).map(|r| (idx, r)))
+    }
+
+    /// Write a single instruction region
+    ///
+    /// ## Errors
+    ///
+    /// Returns:
+    /// - [Error::TooManyRegions] if there is no instruction region `idx`.
+    /// - [Error::UnalignedRegion] if the region's base address is not aligned
+    ///   to its size.
+    pub fn set_iregion(&mut self, idx: u8, region: &Region) -> Result<(), Error> {
+        if idx >= self.num_iregions() {
+            return Err(Error::TooManyRegions);
+        }
+        if !region.size.is_aligned(region.base) {
+            return Err(Error::UnalignedRegion(region.base));
+        }
+        register::Rgnr::write(register::Rgnr(idx as u32));
+        register::Irbar::write(register::Irbar(region.base.as_usize() as *mut u8));
+        register::Irsr::write({
+            let mut out = register::Irsr::new_with_raw_value(0);
+            out.set_enabled(region.enabled);
+            out.set_region_size(region.size);
+            out.set_subregion_mask(region.subregion_mask);
+            out
+        });
+        register::Iracr::write({
+            let mut out = register::Iracr::new_with_raw_value(0);
+            let mem_attr_bits = region.mem_attr.to_bits();
+            out.set_tex(mem_attr_bits.tex);
+            out.set_c(mem_attr_bits.c);
+            out.set_b(mem_attr_bits.b);
+            out.set_s(mem_attr_bits.s);
+            out.set_nx(region.no_exec);
+            out.set_ap(region.access.to_bits());
+            out
+        });
+        Ok(())
+    }
+
+    /// Write a single data/unified region
+    ///
+    /// ## Errors
+    ///
+    /// Returns:
+    /// - [Error::TooManyRegions] if there is no data/unified region `idx`.
+    /// - [Error::UnalignedRegion] if the region's base address is not aligned
+    ///   to its size.
+    pub fn set_dregion(&mut self, idx: u8, region: &Region) -> Result<(), Error> {
+        if idx >= self.num_dregions() {
+            return Err(Error::TooManyRegions);
+        }
+        if !region.size.is_aligned(region.base) {
+            return Err(Error::UnalignedRegion(region.base));
+        }
+        register::Rgnr::write(register::Rgnr(idx as u32));
+        register::Drbar::write(register::Drbar(region.base.as_usize() as *mut u8));
+        register::Drsr::write({
+            let mut out = register::Drsr::new_with_raw_value(0);
+            out.set_enabled(region.enabled);
+            out.set_region_size(region.size);
+            out.set_subregion_mask(region.subregion_mask);
+            out
+        });
+        register::Dracr::write({
+            let mut out = register::Dracr::new_with_raw_value(0);
+            let mem_attr_bits = region.mem_attr.to_bits();
+            out.set_tex(mem_attr_bits.tex);
+            out.set_c(mem_attr_bits.c);
+            out.set_b(mem_attr_bits.b);
+            out.set_s(mem_attr_bits.s);
+            out.set_nx(region.no_exec);
+            out.set_ap(region.access.to_bits());
+            out
+        });
+        Ok(())
+    }
+
+    /// Enable or disable the background region
+    ///
+    /// When enabled, privileged accesses which don't match any region use the
+    /// default memory map.
+    pub fn background_region_enable(&mut self, enable: bool) {
+        register::Sctlr::modify(|r| {
+            r.set_br(enable);
+        });
+    }
+
     /// Configure the EL1 MPU
+    ///
+    /// Write the instruction and data/unified regions (starting from index 0)
+    /// and enable/disable the background region with a single [Config] struct.
     pub fn configure(&mut self, config: &Config) -> Result<(), Error> {
         if config.iregions.len() > self.num_iregions() as usize {
             return Err(Error::TooManyRegions);
@@ -115,58 +228,12 @@ impl Mpu {
             return Err(Error::TooManyRegions);
         }
         for (idx, region) in config.iregions.iter().enumerate() {
-            register::Rgnr::write(register::Rgnr(idx as u32));
-            if !region.size.is_aligned(region.base) {
-                return Err(Error::UnalignedRegion(region.base));
-            }
-            register::Irbar::write(register::Irbar(region.base));
-            register::Irsr::write({
-                let mut out = register::Irsr::new_with_raw_value(0);
-                out.set_enabled(region.enabled);
-                out.set_region_size(region.size);
-                out.set_subregion_mask(region.subregion_mask);
-                out
-            });
-            register::Iracr::write({
-                let mut out = register::Iracr::new_with_raw_value(0);
-                let mem_attr_bits = region.mem_attr.to_bits();
-                out.set_tex(mem_attr_bits.tex);
-                out.set_c(mem_attr_bits.c);
-                out.set_b(mem_attr_bits.b);
-                out.set_s(mem_attr_bits.s);
-                out.set_nx(region.no_exec);
-                // out.with_ap(region.access_perms);
-                out
-            });
+            self.set_iregion(idx as u8, region)?;
         }
         for (idx, region) in config.dregions.iter().enumerate() {
-            if !region.size.is_aligned(region.base) {
-                return Err(Error::UnalignedRegion(region.base));
-            }
-            register::Rgnr::write(register::Rgnr(idx as u32));
-            register::Drbar::write(register::Drbar(region.base));
-            register::Drsr::write({
-                let mut out = register::Drsr::new_with_raw_value(0);
-                out.set_enabled(region.enabled);
-                out.set_region_size(region.size);
-                out.set_subregion_mask(region.subregion_mask);
-                out
-            });
-            register::Dracr::write({
-                let mut out = register::Dracr::new_with_raw_value(0);
-                let mem_attr_bits = region.mem_attr.to_bits();
-                out.set_tex(mem_attr_bits.tex);
-                out.set_c(mem_attr_bits.c);
-                out.set_b(mem_attr_bits.b);
-                out.set_s(mem_attr_bits.s);
-                out.set_nx(region.no_exec);
-                // out.with_ap(region.access_perms);
-                out
-            });
+            self.set_dregion(idx as u8, region)?;
         }
-        register::Sctlr::modify(|r| {
-            r.set_br(config.background_config);
-        });
+        self.background_region_enable(config.background_config);
         Ok(())
     }
 
@@ -204,7 +271,7 @@ pub struct Region {
     /// The base address of this region.
     ///
     /// Must be aligned to the size of the region.
-    pub base: *mut u8,
+    pub base: PhysAddr,
     /// The size of this region
     pub size: RegionSize,
     /// Sub-region bitmask
@@ -218,15 +285,71 @@ pub struct Region {
     pub subregion_mask: u8,
     /// Is this region enabled?
     pub enabled: bool,
+    /// Who can read and write this region
+    pub access: AccessPerms,
     /// No-Execute in this region
     pub no_exec: bool,
     /// Attributes for this region
     pub mem_attr: MemAttr,
 }
 
-// Creating a static Region is fine - the pointers within it
-// only go to the MPU and aren't accessed via Rust code
-unsafe impl Sync for Region {}
+#[cfg(feature = "defmt")]
+impl defmt::Format for Region {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "Region {{ base=0x{=usize:08x}, size={}, subregion_mask=0b{=u8:08b}, enabled={=bool}, access={}, no_exec={=bool}, mem_attr={} }}",
+            self.base.as_usize(),
+            defmt::Debug2Format(&self.size),
+            self.subregion_mask,
+            self.enabled,
+            defmt::Debug2Format(&self.access),
+            self.no_exec,
+            defmt::Debug2Format(&self.mem_attr)
+        )
+    }
+}
+
+/// Access permissions for a region
+///
+/// Privileged means PL1 (e.g. SVC or SYS mode), and User means PL0 (USR mode).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccessPerms {
+    /// No access at all
+    NoAccess = 0b000,
+    /// Read-Write when privileged, no access for User
+    ReadWriteNoUser = 0b001,
+    /// Read-Write when privileged, Read-Only for User
+    ReadWriteUserReadOnly = 0b010,
+    /// Read-Write for everyone
+    ReadWrite = 0b011,
+    /// Read-Only when privileged, no access for User
+    ReadOnlyNoUser = 0b101,
+    /// Read-Only for everyone
+    ReadOnly = 0b110,
+}
+
+impl AccessPerms {
+    /// Convert to the value for the AP field of IRACR or DRACR
+    const fn to_bits(self) -> u3 {
+        u3::from_u8(self as u8)
+    }
+
+    /// Convert from the AP field of IRACR or DRACR
+    ///
+    /// Returns `None` for the reserved values.
+    const fn from_bits(bits: u3) -> Option<AccessPerms> {
+        match bits.value() {
+            0b000 => Some(AccessPerms::NoAccess),
+            0b001 => Some(AccessPerms::ReadWriteNoUser),
+            0b010 => Some(AccessPerms::ReadWriteUserReadOnly),
+            0b011 => Some(AccessPerms::ReadWrite),
+            0b101 => Some(AccessPerms::ReadOnlyNoUser),
+            0b110 => Some(AccessPerms::ReadOnly),
+            _ => None,
+        }
+    }
+}
 
 /// Describes the memory ordering and cacheability of a region
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -300,7 +423,7 @@ impl MemAttr {
                 s: *shareable,
             },
             MemAttr::WriteBackWriteAllocate { shareable } => MemAttrBits {
-                tex: u3::from_u8(0b000),
+                tex: u3::from_u8(0b001),
                 c: true,
                 b: true,
                 s: *shareable,
@@ -415,4 +538,36 @@ mod test {
         let mem_attr2 = mem_attr_bits.decode();
         assert_eq!(Some(mem_attr), mem_attr2);
     }
+    #[test]
+    fn mem_attr_write_back_write_allocate() {
+        let mem_attr = MemAttr::WriteBackWriteAllocate { shareable: false };
+        let mem_attr_bits = mem_attr.to_bits();
+        assert_eq!(
+            mem_attr_bits,
+            MemAttrBits {
+                tex: u3::from_u8(0b001),
+                c: true,
+                b: true,
+                s: false
+            }
+        );
+        let mem_attr2 = mem_attr_bits.decode();
+        assert_eq!(Some(mem_attr), mem_attr2);
+    }
+
+    #[test]
+    fn access_perms() {
+        for access in [
+            AccessPerms::NoAccess,
+            AccessPerms::ReadWriteNoUser,
+            AccessPerms::ReadWriteUserReadOnly,
+            AccessPerms::ReadWrite,
+            AccessPerms::ReadOnlyNoUser,
+            AccessPerms::ReadOnly,
+        ] {
+            assert_eq!(AccessPerms::from_bits(access.to_bits()), Some(access));
+        }
+        assert_eq!(AccessPerms::from_bits(u3::from_u8(0b100)), None);
+        assert_eq!(AccessPerms::from_bits(u3::from_u8(0b111)), None);
+    }
 }
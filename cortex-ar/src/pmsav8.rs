@@ -8,7 +8,7 @@
 
 use arbitrary_int::{u26, u3};
 
-use crate::register;
+use crate::{addr::PhysAddr, register};
 
 #[doc(inline)]
 pub use register::prbar::{AccessPerms, Shareability};
@@ -20,8 +20,10 @@ pub enum Error {
     TooManyRegions,
     /// Found an invalid MAIR selector (only 0..=7 is valid)
     InvalidMair(u8),
+    /// Found too many memory attributes (only 8 fit in MAIR0 and MAIR1)
+    TooManyAttributes,
     /// Found a region with invalid alignment
-    UnalignedRegion(core::ops::RangeInclusive<*mut u8>),
+    UnalignedRegion(core::ops::RangeInclusive<PhysAddr>),
 }
 
 /// Represents our PMSAv8-32 EL1 MPU
@@ -51,8 +53,8 @@ impl El1Mpu {
         register::Prselr::write(register::Prselr(idx as u32));
         let prbar = register::Prbar::read();
         let prlar = register::Prlar::read();
-        let start_addr = (prbar.base().value() << 6) as *mut u8;
-        let end_addr = ((prlar.limit().value() << 6) | 0x3F) as *mut u8;
+        let start_addr = PhysAddr::new((prbar.base().value() << 6) as usize);
+        let end_addr = PhysAddr::new(((prlar.limit().value() << 6) | 0x3F) as usize);
         Some(Region {
             range: start_addr..=end_addr,
             shareability: prbar.shareability(),
@@ -63,6 +65,14 @@ impl El1Mpu {
         })
     }
 
+    /// Iterate through the regions, as currently programmed
+    ///
+    /// Gives you the index of each region along with the region itself, so
+    /// you can check (or print out) what the MPU actually contains.
+    pub fn regions(&mut self) -> impl Iterator<Item = (u8, Region)> + '_ {
+        (0..self.num_regions()).filter_map(move |idx| self.get_region(idx).map(|r| (idx, r)))
+    }
+
     /// Write a single region to the EL1 MPU
     ///
     /// ## Arguments
@@ -73,16 +83,21 @@ impl El1Mpu {
     /// ## Errors
     ///
     /// Returns:
+    /// - [Error::TooManyRegions] if there is no region `idx`.
     /// - [Error::UnalignedRegion] if the region's start address is not 64-byte aligned.
-    /// - [Error::UnalignedRegion] if the region's end address is not 63-byte aligned.
+    /// - [Error::UnalignedRegion] if the region's end address is not one byte
+    ///   before a 64-byte boundary.
     /// - [Error::InvalidMair] if the region's MAIR index is invalid (greater than 7).
     pub fn set_region(&mut self, idx: u8, region: &Region) -> Result<(), Error> {
-        let start = *(region.range.start()) as usize as u32;
+        if idx >= self.num_regions() {
+            return Err(Error::TooManyRegions);
+        }
+        let start = region.range.start().as_u32();
         // Check for 64-byte alignment (0x3F is six bits)
         if start & 0x3F != 0 {
             return Err(Error::UnalignedRegion(region.range.clone()));
         }
-        let end = *(region.range.end()) as usize as u32;
+        let end = region.range.end().as_u32();
         if end & 0x3F != 0x3F {
             return Err(Error::UnalignedRegion(region.range.clone()));
         }
@@ -133,22 +148,23 @@ impl El1Mpu {
     }
 
     /// Set the memory attributes to MAIR0 and MAIR1
-    pub fn set_attributes(&mut self, memattrs: &[MemAttr]) {
-        let mem_attr0 = memattrs.get(0).map(|m| m.to_bits()).unwrap_or(0) as u32;
-        let mem_attr1 = memattrs.get(1).map(|m| m.to_bits()).unwrap_or(0) as u32;
-        let mem_attr2 = memattrs.get(2).map(|m| m.to_bits()).unwrap_or(0) as u32;
-        let mem_attr3 = memattrs.get(3).map(|m| m.to_bits()).unwrap_or(0) as u32;
-        let mair0 = mem_attr3 << 24 | mem_attr2 << 16 | mem_attr1 << 8 | mem_attr0;
-        unsafe {
-            register::Mair0::write(register::Mair0(mair0));
-        }
-        let mem_attr0 = memattrs.get(4).map(|m| m.to_bits()).unwrap_or(0) as u32;
-        let mem_attr1 = memattrs.get(5).map(|m| m.to_bits()).unwrap_or(0) as u32;
-        let mem_attr2 = memattrs.get(6).map(|m| m.to_bits()).unwrap_or(0) as u32;
-        let mem_attr3 = memattrs.get(7).map(|m| m.to_bits()).unwrap_or(0) as u32;
-        let mair1 = mem_attr3 << 24 | mem_attr2 << 16 | mem_attr1 << 8 | mem_attr0;
+    ///
+    /// The first attribute is index 0, and so on. Returns
+    /// [Error::TooManyAttributes] (and changes nothing) if there are more than
+    /// eight.
+    pub fn set_attributes(&mut self, memattrs: &[MemAttr]) -> Result<(), Error> {
+        let table = MemAttrTable::from_slice(memattrs)?;
+        self.set_attribute_table(&table);
+        Ok(())
+    }
+
+    /// Set MAIR0 and MAIR1 from a [MemAttrTable]
+    ///
+    /// Any slots the table hasn't used are set to Strongly-ordered.
+    pub fn set_attribute_table(&mut self, table: &MemAttrTable) {
         unsafe {
-            register::Mair1::write(register::Mair1(mair1));
+            register::Mair0::write(register::Mair0(table.mair0()));
+            register::Mair1::write(register::Mair1(table.mair1()));
         }
     }
 
@@ -165,7 +181,7 @@ impl El1Mpu {
     pub fn configure(&mut self, config: &Config) -> Result<(), Error> {
         self.set_regions(0, config.regions)?;
 
-        self.set_attributes(config.memory_attributes);
+        self.set_attributes(config.memory_attributes)?;
 
         self.background_region_enable(config.background_config);
 
@@ -211,7 +227,7 @@ pub struct Region {
     ///
     /// * The first address must be a multiple of 32.
     /// * The length must be a multiple of 32.
-    pub range: core::ops::RangeInclusive<*mut u8>,
+    pub range: core::ops::RangeInclusive<PhysAddr>,
     /// Shareability of the region
     pub shareability: Shareability,
     /// Access for the region
@@ -228,9 +244,117 @@ pub struct Region {
     pub enable: bool,
 }
 
-// Creating a static Region is fine - the pointers within it
-// only go to the MPU and aren't accessed via Rust code
-unsafe impl Sync for Region {}
+#[cfg(feature = "defmt")]
+impl defmt::Format for Region {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "Region {{ range=0x{=usize:08x}..=0x{=usize:08x}, shareability={}, access={}, no_exec={=bool}, mair={=u8}, enable={=bool} }}",
+            self.range.start().as_usize(),
+            self.range.end().as_usize(),
+            defmt::Debug2Format(&self.shareability),
+            defmt::Debug2Format(&self.access),
+            self.no_exec,
+            self.mair,
+            self.enable
+        )
+    }
+}
+
+/// The memory attributes that go in MAIR0 and MAIR1
+///
+/// A [Region] picks its memory attributes with an index into this table. Use
+/// [MemAttrTable::index_of] to find the index for some attributes, which adds
+/// them to the table if they aren't already there. That way the indices in
+/// your regions always match what ends up in MAIR0 and MAIR1.
+///
+/// ```rust ignore
+/// let mut table = MemAttrTable::new();
+/// let region = Region {
+///     mair: table.index_of(&MemAttr::DeviceMemory)?,
+///     ..
+/// };
+/// mpu.set_attribute_table(&table);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MemAttrTable {
+    attrs: [u8; 8],
+    len: u8,
+}
+
+impl MemAttrTable {
+    /// Make an empty table
+    pub const fn new() -> MemAttrTable {
+        MemAttrTable {
+            attrs: [0; 8],
+            len: 0,
+        }
+    }
+
+    /// Make a table holding the given attributes, in order
+    ///
+    /// Duplicates are kept, so the index of each attribute is its position in
+    /// the slice.
+    pub fn from_slice(memattrs: &[MemAttr]) -> Result<MemAttrTable, Error> {
+        let mut table = MemAttrTable::new();
+        if memattrs.len() > table.attrs.len() {
+            return Err(Error::TooManyAttributes);
+        }
+        for (slot, memattr) in table.attrs.iter_mut().zip(memattrs) {
+            *slot = memattr.to_bits();
+        }
+        table.len = memattrs.len() as u8;
+        Ok(table)
+    }
+
+    /// Get the index of the given attributes, adding them if they are new
+    ///
+    /// Returns [Error::TooManyAttributes] if they are new and the table is
+    /// full.
+    pub fn index_of(&mut self, memattr: &MemAttr) -> Result<u8, Error> {
+        if let Some(idx) = self.find(memattr) {
+            return Ok(idx);
+        }
+        let idx = self.len;
+        let slot = self
+            .attrs
+            .get_mut(idx as usize)
+            .ok_or(Error::TooManyAttributes)?;
+        *slot = memattr.to_bits();
+        self.len += 1;
+        Ok(idx)
+    }
+
+    /// Get the index of the given attributes, if they are in the table
+    pub fn find(&self, memattr: &MemAttr) -> Option<u8> {
+        let bits = memattr.to_bits();
+        self.attrs
+            .iter()
+            .take(self.len as usize)
+            .position(|b| *b == bits)
+            .map(|idx| idx as u8)
+    }
+
+    /// How many attributes are in the table?
+    pub const fn len(&self) -> u8 {
+        self.len
+    }
+
+    /// Is the table empty?
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The value for MAIR0, which holds attributes 0 to 3
+    pub const fn mair0(&self) -> u32 {
+        u32::from_le_bytes([self.attrs[0], self.attrs[1], self.attrs[2], self.attrs[3]])
+    }
+
+    /// The value for MAIR1, which holds attributes 4 to 7
+    pub const fn mair1(&self) -> u32 {
+        u32::from_le_bytes([self.attrs[4], self.attrs[5], self.attrs[6], self.attrs[7]])
+    }
+}
 
 /// Describes the memory ordering and cacheability of a region
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -301,6 +425,37 @@ pub enum RwAllocPolicy {
 mod test {
     use super::*;
 
+    #[test]
+    fn mem_attr_table() {
+        let normal = MemAttr::NormalMemory {
+            outer: Cacheable::WriteBackNonTransient(RwAllocPolicy::RW),
+            inner: Cacheable::WriteBackNonTransient(RwAllocPolicy::RW),
+        };
+        let mut table = MemAttrTable::new();
+        assert_eq!(table.index_of(&normal), Ok(0));
+        assert_eq!(table.index_of(&MemAttr::DeviceMemory), Ok(1));
+        // Asking again gives the same slot
+        assert_eq!(table.index_of(&normal), Ok(0));
+        assert_eq!(table.len(), 2);
+        assert_eq!(table.mair0(), 0x0000_04FF);
+        assert_eq!(table.mair1(), 0);
+    }
+
+    #[test]
+    fn mem_attr_table_full() {
+        const DEVICE: MemAttr = MemAttr::DeviceMemory;
+        let mut table = MemAttrTable::from_slice(&[DEVICE; 8]).unwrap();
+        assert_eq!(table.mair1(), 0x0404_0404);
+        assert_eq!(
+            table.index_of(&MemAttr::StronglyOrdered),
+            Err(Error::TooManyAttributes)
+        );
+        assert_eq!(
+            MemAttrTable::from_slice(&[DEVICE; 9]),
+            Err(Error::TooManyAttributes)
+        );
+    }
+
     #[test]
     fn mem_attr_strong() {
         let mem_attr = MemAttr::StronglyOrdered;
@@ -0,0 +1,326 @@
+//! Code for driving the Performance Monitors Unit
+//!
+//! The PMU has a 32-bit cycle counter, and a number of 32-bit event counters
+//! (see [`num_event_counters`]) which can each count one [`Event`]. Every
+//! counter can set an overflow flag, and raise an interrupt, when it wraps.
+//!
+//! Nothing counts until you call [`enable`], and then enable the individual
+//! counters you want.
+//!
+//! The event counters are accessed indirectly through PMSELR, so those
+//! functions briefly disable interrupts. Don't touch PMSELR, PMXEVTYPER or
+//! PMXEVCNTR yourself whilst using this module.
+
+use crate::register;
+
+/// Ways this API can fail
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Error {
+    /// This processor doesn't have the given event counter
+    NoSuchCounter,
+}
+
+/// One of the PMU's counters
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Counter {
+    /// The cycle counter, PMCCNTR
+    Cycle,
+    /// One of the event counters, numbered from zero
+    Event(u8),
+}
+
+impl Counter {
+    /// The bit for this counter in PMCNTENSET, PMOVSR, PMINTENSET and friends
+    fn mask(self) -> Result<u32, Error> {
+        match self {
+            Counter::Cycle => Ok(1 << 31),
+            Counter::Event(n) if n < num_event_counters() => Ok(1 << n),
+            Counter::Event(_) => Err(Error::NoSuchCounter),
+        }
+    }
+}
+
+/// Architectural events which an event counter can count
+///
+/// Not every processor implements every event - check PMCEID0 and PMCEID1, or
+/// your processor's Technical Reference Manual. Use [`Event::Other`] for
+/// anything implementation defined.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Event {
+    /// Writes to PMSWINC
+    SoftwareIncrement,
+    /// Level 1 instruction cache refills
+    L1ICacheRefill,
+    /// Level 1 instruction TLB refills
+    L1ITlbRefill,
+    /// Level 1 data cache refills
+    L1DCacheRefill,
+    /// Level 1 data cache accesses
+    L1DCacheAccess,
+    /// Level 1 data TLB refills
+    L1DTlbRefill,
+    /// Instructions architecturally executed that read memory
+    MemRead,
+    /// Instructions architecturally executed that write memory
+    MemWrite,
+    /// Instructions architecturally executed
+    InstructionRetired,
+    /// Exceptions taken
+    ExceptionTaken,
+    /// Exception returns architecturally executed
+    ExceptionReturn,
+    /// Writes to CONTEXTIDR architecturally executed
+    ContextIdWrite,
+    /// Software changes of the PC architecturally executed
+    PcWrite,
+    /// Immediate branches architecturally executed
+    BranchImmediate,
+    /// Procedure returns architecturally executed
+    BranchReturn,
+    /// Unaligned loads and stores architecturally executed
+    UnalignedLoadStore,
+    /// Branches mispredicted, or not predicted
+    BranchMispredicted,
+    /// Processor cycles
+    CpuCycles,
+    /// Predictable branches speculatively executed
+    BranchPredicted,
+    /// Data memory accesses
+    MemAccess,
+    /// Level 1 instruction cache accesses
+    L1ICacheAccess,
+    /// Level 1 data cache write-backs
+    L1DCacheWriteBack,
+    /// Level 2 data cache accesses
+    L2DCacheAccess,
+    /// Level 2 data cache refills
+    L2DCacheRefill,
+    /// Level 2 data cache write-backs
+    L2DCacheWriteBack,
+    /// Bus accesses
+    BusAccess,
+    /// Local memory errors
+    MemoryError,
+    /// Instructions speculatively executed
+    InstructionSpeculated,
+    /// Writes to TTBR architecturally executed
+    TtbrWrite,
+    /// Bus cycles
+    BusCycles,
+    /// Some other event, by number
+    Other(u8),
+}
+
+impl Event {
+    /// The event number to put in PMXEVTYPER
+    pub const fn number(self) -> u8 {
+        match self {
+            Event::SoftwareIncrement => 0x00,
+            Event::L1ICacheRefill => 0x01,
+            Event::L1ITlbRefill => 0x02,
+            Event::L1DCacheRefill => 0x03,
+            Event::L1DCacheAccess => 0x04,
+            Event::L1DTlbRefill => 0x05,
+            Event::MemRead => 0x06,
+            Event::MemWrite => 0x07,
+            Event::InstructionRetired => 0x08,
+            Event::ExceptionTaken => 0x09,
+            Event::ExceptionReturn => 0x0A,
+            Event::ContextIdWrite => 0x0B,
+            Event::PcWrite => 0x0C,
+            Event::BranchImmediate => 0x0D,
+            Event::BranchReturn => 0x0E,
+            Event::UnalignedLoadStore => 0x0F,
+            Event::BranchMispredicted => 0x10,
+            Event::CpuCycles => 0x11,
+            Event::BranchPredicted => 0x12,
+            Event::MemAccess => 0x13,
+            Event::L1ICacheAccess => 0x14,
+            Event::L1DCacheWriteBack => 0x15,
+            Event::L2DCacheAccess => 0x16,
+            Event::L2DCacheRefill => 0x17,
+            Event::L2DCacheWriteBack => 0x18,
+            Event::BusAccess => 0x19,
+            Event::MemoryError => 0x1A,
+            Event::InstructionSpeculated => 0x1B,
+            Event::TtbrWrite => 0x1C,
+            Event::BusCycles => 0x1D,
+            Event::Other(n) => n,
+        }
+    }
+}
+
+/// How many event counters this processor has
+pub fn num_event_counters() -> u8 {
+    register::Pmcr::read().n().value()
+}
+
+/// Start the PMU counting
+///
+/// Only the counters which are also enabled with [`enable_counter`] actually
+/// count.
+pub fn enable() {
+    // Safety: Turning on the PMU doesn't affect anything else
+    unsafe {
+        register::Pmcr::modify(|w| w.set_e(true));
+    }
+    crate::asm::isb();
+}
+
+/// Stop all the counters
+pub fn disable() {
+    // Safety: Turning off the PMU doesn't affect anything else
+    unsafe {
+        register::Pmcr::modify(|w| w.set_e(false));
+    }
+    crate::asm::isb();
+}
+
+/// Reset the cycle counter, and all the event counters, to zero
+pub fn reset_counters() {
+    // Safety: The C and P bits just reset the counters
+    unsafe {
+        register::Pmcr::modify(|w| {
+            w.set_c(true);
+            w.set_p(true);
+        });
+    }
+    crate::asm::isb();
+}
+
+/// Make the cycle counter count once every 64 cycles, rather than every cycle
+pub fn set_cycle_divider(divide_by_64: bool) {
+    // Safety: This only affects the cycle counter
+    unsafe {
+        register::Pmcr::modify(|w| w.set_d(divide_by_64));
+    }
+    crate::asm::isb();
+}
+
+/// Read the cycle counter
+#[inline]
+pub fn cycle_count() -> u32 {
+    register::Pmccntr::read().0
+}
+
+/// Choose what an event counter counts
+///
+/// The counter's value is left as it is.
+pub fn set_event(counter: u8, event: Event) -> Result<(), Error> {
+    Counter::Event(counter).mask()?;
+    with_selected(counter, || {
+        // Safety: We checked the counter exists, and any event number is
+        // allowed (unimplemented ones just don't count)
+        unsafe {
+            register::Pmxevtyper::write(register::Pmxevtyper(u32::from(event.number())));
+        }
+    });
+    Ok(())
+}
+
+/// Read a counter
+pub fn read(counter: Counter) -> Result<u32, Error> {
+    counter.mask()?;
+    match counter {
+        Counter::Cycle => Ok(cycle_count()),
+        Counter::Event(n) => Ok(with_selected(n, || register::Pmxevcntr::read().0)),
+    }
+}
+
+/// Set a counter to the given value
+///
+/// For example, set it to `u32::MAX - n` to have it overflow after `n + 1`
+/// events.
+pub fn write(counter: Counter, value: u32) -> Result<(), Error> {
+    counter.mask()?;
+    match counter {
+        // Safety: Nothing else depends on the cycle counter
+        Counter::Cycle => unsafe { register::Pmccntr::write(register::Pmccntr(value)) },
+        Counter::Event(n) => with_selected(n, || {
+            // Safety: Nothing else depends on the event counters
+            unsafe { register::Pmxevcntr::write(register::Pmxevcntr(value)) }
+        }),
+    }
+    Ok(())
+}
+
+/// Let a counter count (as long as the PMU is [enabled](enable))
+pub fn enable_counter(counter: Counter) -> Result<(), Error> {
+    let mask = counter.mask()?;
+    // Safety: Only the bit we write a one to is affected
+    unsafe {
+        register::Pmcntenset::write(register::Pmcntenset(mask));
+    }
+    Ok(())
+}
+
+/// Stop a counter from counting
+pub fn disable_counter(counter: Counter) -> Result<(), Error> {
+    let mask = counter.mask()?;
+    // Safety: Only the bit we write a one to is affected
+    unsafe {
+        register::Pmcntenclr::write(register::Pmcntenclr(mask));
+    }
+    Ok(())
+}
+
+/// Has a counter overflowed since its overflow flag was last cleared?
+pub fn has_overflowed(counter: Counter) -> Result<bool, Error> {
+    let mask = counter.mask()?;
+    Ok(register::Pmovsr::read().0 & mask != 0)
+}
+
+/// Clear a counter's overflow flag
+///
+/// If you enabled the overflow interrupt, this is how you acknowledge it.
+pub fn clear_overflow(counter: Counter) -> Result<(), Error> {
+    let mask = counter.mask()?;
+    // Safety: Only the bit we write a one to is cleared
+    unsafe {
+        register::Pmovsr::write(register::Pmovsr(mask));
+    }
+    Ok(())
+}
+
+/// Raise the PMU interrupt when a counter overflows
+///
+/// Which interrupt that is depends on your chip.
+pub fn enable_overflow_interrupt(counter: Counter) -> Result<(), Error> {
+    let mask = counter.mask()?;
+    // Safety: Only the bit we write a one to is affected
+    unsafe {
+        register::Pmintenset::write(register::Pmintenset(mask));
+    }
+    Ok(())
+}
+
+/// Stop raising the PMU interrupt when a counter overflows
+pub fn disable_overflow_interrupt(counter: Counter) -> Result<(), Error> {
+    let mask = counter.mask()?;
+    // Safety: Only the bit we write a one to is affected
+    unsafe {
+        register::Pmintenclr::write(register::Pmintenclr(mask));
+    }
+    Ok(())
+}
+
+/// Select an event counter with PMSELR, and then call `f`
+///
+/// Interrupts are off in between, so nobody else can change PMSELR under our
+/// feet.
+fn with_selected<F, T>(counter: u8, f: F) -> T
+where
+    F: FnOnce() -> T,
+{
+    crate::interrupt::free(|| {
+        // Safety: Selecting a counter has no side-effects
+        unsafe {
+            register::Pmselr::write(register::Pmselr(u32::from(counter)));
+        }
+        crate::asm::isb();
+        f()
+    })
+}
@@ -0,0 +1,109 @@
+//! Power State Coordination Interface (PSCI) client
+//!
+//! Asks the secure monitor (e.g. Arm Trusted Firmware) to start and stop cores,
+//! and to reset or power off the system, using the [SMC32 calling
+//! convention](crate::smccc).
+//!
+//! See the [Arm Power State Coordination Interface][psci] specification.
+//!
+//! [psci]: https://developer.arm.com/documentation/den0022/latest/
+
+/// Function ID for CPU_OFF
+const CPU_OFF: u32 = 0x8400_0002;
+/// Function ID for CPU_ON (SMC32)
+const CPU_ON: u32 = 0x8400_0003;
+/// Function ID for SYSTEM_OFF
+const SYSTEM_OFF: u32 = 0x8400_0008;
+/// Function ID for SYSTEM_RESET
+const SYSTEM_RESET: u32 = 0x8400_0009;
+
+/// Errors that a PSCI call can return
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// The function isn't implemented
+    NotSupported,
+    /// One of the arguments was invalid
+    InvalidParameters,
+    /// The caller isn't allowed to do that
+    Denied,
+    /// The core is already on
+    AlreadyOn,
+    /// The core is already being turned on
+    OnPending,
+    /// The firmware had an internal failure
+    InternalFailure,
+    /// The core doesn't exist
+    NotPresent,
+    /// The core is disabled
+    Disabled,
+    /// The entry point address was invalid
+    InvalidAddress,
+    /// Some other (unexpected) return code
+    Unknown(i32),
+}
+
+impl Error {
+    /// Convert a PSCI return code into a result
+    fn check(code: i32) -> Result<(), Error> {
+        match code {
+            0 => Ok(()),
+            -1 => Err(Error::NotSupported),
+            -2 => Err(Error::InvalidParameters),
+            -3 => Err(Error::Denied),
+            -4 => Err(Error::AlreadyOn),
+            -5 => Err(Error::OnPending),
+            -6 => Err(Error::InternalFailure),
+            -7 => Err(Error::NotPresent),
+            -8 => Err(Error::Disabled),
+            -9 => Err(Error::InvalidAddress),
+            n => Err(Error::Unknown(n)),
+        }
+    }
+}
+
+/// Start the core with the given MPIDR affinity value
+///
+/// The core starts executing at `entry_point` in Supervisor mode, with the MMU
+/// and caches off, and with `context_id` in R0.
+///
+/// # Safety
+///
+/// `entry_point` must be the address of some code that can start a core.
+pub unsafe fn cpu_on(target_cpu: u32, entry_point: usize, context_id: u32) -> Result<(), Error> {
+    Error::check(smc_call(CPU_ON, target_cpu, entry_point as u32, context_id))
+}
+
+/// Turn off the calling core
+///
+/// Only returns if the call fails.
+pub fn cpu_off() -> Error {
+    match Error::check(smc_call(CPU_OFF, 0, 0, 0)) {
+        Err(e) => e,
+        Ok(()) => Error::Unknown(0),
+    }
+}
+
+/// Power off the system
+///
+/// Only returns if the call fails.
+pub fn system_off() -> Error {
+    match Error::check(smc_call(SYSTEM_OFF, 0, 0, 0)) {
+        Err(e) => e,
+        Ok(()) => Error::Unknown(0),
+    }
+}
+
+/// Reset the system
+///
+/// Only returns if the call fails.
+pub fn system_reset() -> Error {
+    match Error::check(smc_call(SYSTEM_RESET, 0, 0, 0)) {
+        Err(e) => e,
+        Ok(()) => Error::Unknown(0),
+    }
+}
+
+/// Make an SMC32 call with up to three arguments
+fn smc_call(function: u32, arg1: u32, arg2: u32, arg3: u32) -> i32 {
+    crate::smccc::smccc_call(function, [arg1, arg2, arg3, 0, 0, 0])[0] as i32
+}
@@ -1,8 +1,13 @@
 //! Code for managing ACTLR (*Auxiliary Control Register*)
+//!
+//! The contents of ACTLR are IMPLEMENTATION DEFINED, so the bits here only
+//! mean what they say on the processors listed against them. Check your
+//! processor's Technical Reference Manual before setting anything else.
 
 use crate::register::{SysReg, SysRegRead, SysRegWrite};
 
 /// ACTLR (*Auxiliary Control Register*)
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub struct Actlr(pub u32);
 impl SysReg for Actlr {
     const CP: u32 = 15;
@@ -32,4 +37,136 @@ impl Actlr {
             <Self as SysRegWrite>::write_raw(value.0);
         }
     }
+
+    #[inline]
+    /// Modify ACTLR (*Auxiliary Control Register*)
+    ///
+    /// # Safety
+    ///
+    /// See docs for [Self::write].
+    pub unsafe fn modify<F>(f: F)
+    where
+        F: FnOnce(&mut Self),
+    {
+        let mut value = Self::read();
+        f(&mut value);
+        unsafe {
+            Self::write(value);
+        }
+    }
+}
+
+impl Actlr {
+    /// The SMP bit, on the Cortex-A5, A7, A9, A15 and A17
+    ///
+    /// Set this before turning on the data cache and MMU, so this core takes
+    /// part in coherency with the other cores in its cluster. On some systems
+    /// it can only be written from the Secure state.
+    pub const SMP: u32 = 1 << 6;
+
+    /// The FW (*Cache and TLB maintenance broadcast*) bit, on the Cortex-A5
+    /// and A9
+    pub const FW: u32 = 1 << 0;
+
+    /// ATCM external error enable, on the Cortex-R4 and R5
+    pub const R5_ATCMECEN: u32 = 1 << 0;
+
+    /// B0TCM external error enable, on the Cortex-R4 and R5
+    pub const R5_B0TCMECEN: u32 = 1 << 1;
+
+    /// B1TCM external error enable, on the Cortex-R4 and R5
+    pub const R5_B1TCMECEN: u32 = 1 << 2;
+
+    /// The shift of the three-bit CEC (*Cache error checking*) field, on the
+    /// Cortex-R4 and R5
+    pub const R5_CEC_SHIFT: u32 = 3;
+
+    /// The mask for the CEC field, on the Cortex-R4 and R5
+    pub const R5_CEC_MASK: u32 = 0b111 << Self::R5_CEC_SHIFT;
+
+    /// ATCM parity or ECC check enable, on the Cortex-R4 and R5
+    pub const R5_ATCMPCEN: u32 = 1 << 25;
+
+    /// B0TCM parity or ECC check enable, on the Cortex-R4 and R5
+    pub const R5_B0TCMPCEN: u32 = 1 << 26;
+
+    /// B1TCM parity or ECC check enable, on the Cortex-R4 and R5
+    pub const R5_B1TCMPCEN: u32 = 1 << 27;
+
+    /// Are all the bits in `mask` set?
+    pub const fn is_set(&self, mask: u32) -> bool {
+        (self.0 & mask) == mask
+    }
+
+    /// Set the bits in `mask`
+    pub fn set(&mut self, mask: u32) {
+        self.0 |= mask;
+    }
+
+    /// Clear the bits in `mask`
+    pub fn clear(&mut self, mask: u32) {
+        self.0 &= !mask;
+    }
+
+    /// Is the [`SMP`](Actlr::SMP) bit set?
+    pub const fn smp(&self) -> bool {
+        self.is_set(Self::SMP)
+    }
+
+    /// The Cortex-R4 and R5 CEC (*Cache error checking*) field
+    ///
+    /// See the Technical Reference Manual for what each value means - it
+    /// depends on whether the caches were built with parity or ECC.
+    pub const fn r5_cec(&self) -> u8 {
+        ((self.0 & Self::R5_CEC_MASK) >> Self::R5_CEC_SHIFT) as u8
+    }
+
+    /// Set the Cortex-R4 and R5 CEC (*Cache error checking*) field
+    ///
+    /// Only the bottom three bits of `cec` are used.
+    pub fn set_r5_cec(&mut self, cec: u8) {
+        self.0 = (self.0 & !Self::R5_CEC_MASK)
+            | (((cec as u32) << Self::R5_CEC_SHIFT) & Self::R5_CEC_MASK);
+    }
+}
+
+/// Turn on the SMP bit in ACTLR, on the Cortex-A5, A7, A9, A15 and A17
+///
+/// Do this on each core, before turning on its data cache and MMU.
+///
+/// # Safety
+///
+/// Only call this on one of the processors listed above, as ACTLR bit 6 means
+/// something else (or nothing) on other processors.
+#[cfg(target_arch = "arm")]
+pub unsafe fn enable_smp() {
+    unsafe {
+        Actlr::modify(|actlr| actlr.set(Actlr::SMP));
+    }
+    crate::asm::isb();
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn r5_cec() {
+        let mut actlr = Actlr(0xFFFF_FFFF);
+        actlr.set_r5_cec(0b010);
+        assert_eq!(actlr.r5_cec(), 0b010);
+        assert_eq!(actlr.0, 0xFFFF_FFD7);
+        actlr.clear(Actlr::R5_ATCMPCEN | Actlr::R5_B0TCMPCEN);
+        assert!(!actlr.is_set(Actlr::R5_ATCMPCEN));
+        assert!(actlr.is_set(Actlr::R5_B1TCMPCEN));
+    }
+
+    #[test]
+    fn smp() {
+        let mut actlr = Actlr(0);
+        assert!(!actlr.smp());
+        actlr.set(Actlr::SMP | Actlr::FW);
+        assert!(actlr.smp());
+        assert_eq!(actlr.0, 0x41);
+    }
 }
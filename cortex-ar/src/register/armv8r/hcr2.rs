@@ -3,7 +3,22 @@
 use crate::register::{SysReg, SysRegRead, SysRegWrite};
 
 /// HCR2 (*Hyp Configuration Register 2*)
-pub struct Hcr2(pub u32);
+#[bitbybit::bitfield(u32)]
+pub struct Hcr2 {
+    /// Trap External Aborts (RAS Extension only)
+    #[bits(5..=5, rw)]
+    tea: bool,
+    /// Trap Error record accesses (RAS Extension only)
+    #[bits(4..=4, rw)]
+    terr: bool,
+    /// Stage 2 Instruction access cacheability disable
+    #[bits(1..=1, rw)]
+    id: bool,
+    /// Stage 2 Data access cacheability disable
+    #[bits(0..=0, rw)]
+    cd: bool,
+}
+
 impl SysReg for Hcr2 {
     const CP: u32 = 15;
     const CRN: u32 = 1;
@@ -16,7 +31,7 @@ impl Hcr2 {
     #[inline]
     /// Reads HCR2 (*Hyp Configuration Register 2*)
     pub fn read() -> Hcr2 {
-        unsafe { Self(<Self as SysRegRead>::read_raw()) }
+        unsafe { Self::new_with_raw_value(<Self as SysRegRead>::read_raw()) }
     }
 }
 impl crate::register::SysRegWrite for Hcr2 {}
@@ -26,10 +41,39 @@ impl Hcr2 {
     ///
     /// # Safety
     ///
-    /// Ensure that this value is appropriate for this register
+    /// Ensure that this value is appropriate for this register. You can only
+    /// write it from EL2.
     pub unsafe fn write(value: Self) {
         unsafe {
-            <Self as SysRegWrite>::write_raw(value.0);
+            <Self as SysRegWrite>::write_raw(value.raw_value());
         }
     }
+
+    #[inline]
+    /// Modify HCR2 (*Hyp Configuration Register 2*)
+    ///
+    /// # Safety
+    ///
+    /// See docs for [Self::write].
+    pub unsafe fn modify<F>(f: F)
+    where
+        F: FnOnce(&mut Self),
+    {
+        let mut value = Self::read();
+        f(&mut value);
+        unsafe {
+            Self::write(value);
+        }
+    }
+}
+
+impl core::fmt::Debug for Hcr2 {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Hcr2")
+            .field("tea", &self.tea())
+            .field("terr", &self.terr())
+            .field("id", &self.id())
+            .field("cd", &self.cd())
+            .finish()
+    }
 }
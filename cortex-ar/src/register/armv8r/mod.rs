@@ -1,37 +1,16 @@
 //! Access registers for Armv8-R only
 
-pub mod cntfrq;
-pub mod cnthctl;
-pub mod cnthp_ctl;
-pub mod cnthp_cval;
-pub mod cnthp_tval;
-pub mod cntkctl;
-pub mod cntp_ctl;
-pub mod cntp_cval;
-pub mod cntp_tval;
-pub mod cntpct;
-pub mod cntv_ctl;
-pub mod cntv_cval;
-pub mod cntv_tval;
-pub mod cntvct;
-pub mod cntvoff;
 pub mod hacr;
 pub mod hactlr;
 pub mod hactlr2;
-pub mod hadfsr;
-pub mod haifsr;
 pub mod hamair0;
 pub mod hamair1;
 pub mod hcptr;
-pub mod hcr;
 pub mod hcr2;
 pub mod hdcr;
-pub mod hdfar;
-pub mod hifar;
 pub mod hmair0;
 pub mod hmair1;
 pub mod hmpuir;
-pub mod hpfar;
 pub mod hprbar;
 pub mod hprbar0;
 pub mod hprbar1;
@@ -69,10 +48,8 @@ pub mod hprlar8;
 pub mod hprlar9;
 pub mod hprselr;
 pub mod hsctlr;
-pub mod hsr;
 pub mod hstr;
 pub mod htpidr;
-pub mod hvbar;
 pub mod prbar;
 pub mod prbar0;
 pub mod prbar1;
@@ -108,40 +85,18 @@ pub mod prlar7;
 pub mod prlar8;
 pub mod prlar9;
 pub mod prselr;
-pub mod vbar;
 
-pub use cntfrq::Cntfrq;
-pub use cnthctl::Cnthctl;
-pub use cnthp_ctl::CnthpCtl;
-pub use cnthp_cval::CnthpCval;
-pub use cnthp_tval::CnthpTval;
-pub use cntkctl::Cntkctl;
-pub use cntp_ctl::CntpCtl;
-pub use cntp_cval::CntpCval;
-pub use cntp_tval::CntpTval;
-pub use cntpct::CntPct;
-pub use cntv_ctl::CntvCtl;
-pub use cntv_cval::CntvCval;
-pub use cntv_tval::CntvTval;
-pub use cntvct::CntVct;
-pub use cntvoff::CntVoff;
 pub use hacr::Hacr;
 pub use hactlr::Hactlr;
 pub use hactlr2::Hactlr2;
-pub use hadfsr::Hadfsr;
-pub use haifsr::Haifsr;
 pub use hamair0::Hamair0;
 pub use hamair1::Hamair1;
 pub use hcptr::Hcptr;
-pub use hcr::Hcr;
 pub use hcr2::Hcr2;
 pub use hdcr::Hdcr;
-pub use hdfar::Hdfar;
-pub use hifar::Hifar;
 pub use hmair0::Hmair0;
 pub use hmair1::Hmair1;
 pub use hmpuir::Hmpuir;
-pub use hpfar::Hpfar;
 pub use hprbar::Hprbar;
 pub use hprbar0::Hprbar0;
 pub use hprbar1::Hprbar1;
@@ -179,10 +134,8 @@ pub use hprlar8::Hprlar8;
 pub use hprlar9::Hprlar9;
 pub use hprselr::Hprselr;
 pub use hsctlr::Hsctlr;
-pub use hsr::Hsr;
 pub use hstr::Hstr;
 pub use htpidr::Htpidr;
-pub use hvbar::Hvbar;
 pub use prbar::Prbar;
 pub use prbar0::Prbar0;
 pub use prbar1::Prbar1;
@@ -218,4 +171,3 @@ pub use prlar7::Prlar7;
 pub use prlar8::Prlar8;
 pub use prlar9::Prlar9;
 pub use prselr::Prselr;
-pub use vbar::Vbar;
@@ -0,0 +1,38 @@
+//! Code for managing ATCMRR (*ATCM Region Register*)
+
+use crate::register::{SysReg, SysRegRead, SysRegWrite};
+
+/// ATCMRR (*ATCM Region Register*)
+///
+/// Found on the Cortex-R4 and Cortex-R5. The Cortex-R52 uses
+/// [`ImpAtcmregionr`](crate::register::ImpAtcmregionr) and friends instead.
+pub struct Atcmrr(pub u32);
+impl SysReg for Atcmrr {
+    const CP: u32 = 15;
+    const CRN: u32 = 9;
+    const OP1: u32 = 0;
+    const CRM: u32 = 1;
+    const OP2: u32 = 1;
+}
+impl crate::register::SysRegRead for Atcmrr {}
+impl Atcmrr {
+    #[inline]
+    /// Reads ATCMRR (*ATCM Region Register*)
+    pub fn read() -> Atcmrr {
+        unsafe { Self(<Self as SysRegRead>::read_raw()) }
+    }
+}
+impl crate::register::SysRegWrite for Atcmrr {}
+impl Atcmrr {
+    #[inline]
+    /// Writes ATCMRR (*ATCM Region Register*)
+    ///
+    /// # Safety
+    ///
+    /// Ensure that this value is appropriate for this register
+    pub unsafe fn write(value: Self) {
+        unsafe {
+            <Self as SysRegWrite>::write_raw(value.0);
+        }
+    }
+}
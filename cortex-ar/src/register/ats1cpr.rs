@@ -0,0 +1,31 @@
+//! Code for managing ATS1CPR (*Address Translate Stage 1 Current state PL1 Read*)
+
+use crate::register::{SysReg, SysRegWrite};
+
+/// ATS1CPR (*Address Translate Stage 1 Current state PL1 Read*)
+///
+/// Translates the virtual address written to it, as if for a PL1 read, and
+/// puts the result in [`Par`](crate::register::Par). Older documents call
+/// this V2PCWPR.
+pub struct Ats1cpr(pub u32);
+impl SysReg for Ats1cpr {
+    const CP: u32 = 15;
+    const CRN: u32 = 7;
+    const OP1: u32 = 0;
+    const CRM: u32 = 8;
+    const OP2: u32 = 0;
+}
+impl crate::register::SysRegWrite for Ats1cpr {}
+impl Ats1cpr {
+    #[inline]
+    /// Writes ATS1CPR (*Address Translate Stage 1 Current state PL1 Read*)
+    ///
+    /// # Safety
+    ///
+    /// Ensure that this value is appropriate for this register
+    pub unsafe fn write(value: Self) {
+        unsafe {
+            <Self as SysRegWrite>::write_raw(value.0);
+        }
+    }
+}
@@ -0,0 +1,31 @@
+//! Code for managing ATS1CPW (*Address Translate Stage 1 Current state PL1 Write*)
+
+use crate::register::{SysReg, SysRegWrite};
+
+/// ATS1CPW (*Address Translate Stage 1 Current state PL1 Write*)
+///
+/// Translates the virtual address written to it, as if for a PL1 write, and
+/// puts the result in [`Par`](crate::register::Par). Older documents call
+/// this V2PCWPW.
+pub struct Ats1cpw(pub u32);
+impl SysReg for Ats1cpw {
+    const CP: u32 = 15;
+    const CRN: u32 = 7;
+    const OP1: u32 = 0;
+    const CRM: u32 = 8;
+    const OP2: u32 = 1;
+}
+impl crate::register::SysRegWrite for Ats1cpw {}
+impl Ats1cpw {
+    #[inline]
+    /// Writes ATS1CPW (*Address Translate Stage 1 Current state PL1 Write*)
+    ///
+    /// # Safety
+    ///
+    /// Ensure that this value is appropriate for this register
+    pub unsafe fn write(value: Self) {
+        unsafe {
+            <Self as SysRegWrite>::write_raw(value.0);
+        }
+    }
+}
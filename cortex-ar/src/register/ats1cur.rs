@@ -0,0 +1,31 @@
+//! Code for managing ATS1CUR (*Address Translate Stage 1 Current state Unprivileged Read*)
+
+use crate::register::{SysReg, SysRegWrite};
+
+/// ATS1CUR (*Address Translate Stage 1 Current state Unprivileged Read*)
+///
+/// Translates the virtual address written to it, as if for an unprivileged
+/// read, and puts the result in [`Par`](crate::register::Par). Older
+/// documents call this V2PCWUR.
+pub struct Ats1cur(pub u32);
+impl SysReg for Ats1cur {
+    const CP: u32 = 15;
+    const CRN: u32 = 7;
+    const OP1: u32 = 0;
+    const CRM: u32 = 8;
+    const OP2: u32 = 2;
+}
+impl crate::register::SysRegWrite for Ats1cur {}
+impl Ats1cur {
+    #[inline]
+    /// Writes ATS1CUR (*Address Translate Stage 1 Current state Unprivileged Read*)
+    ///
+    /// # Safety
+    ///
+    /// Ensure that this value is appropriate for this register
+    pub unsafe fn write(value: Self) {
+        unsafe {
+            <Self as SysRegWrite>::write_raw(value.0);
+        }
+    }
+}
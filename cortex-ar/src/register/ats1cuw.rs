@@ -0,0 +1,31 @@
+//! Code for managing ATS1CUW (*Address Translate Stage 1 Current state Unprivileged Write*)
+
+use crate::register::{SysReg, SysRegWrite};
+
+/// ATS1CUW (*Address Translate Stage 1 Current state Unprivileged Write*)
+///
+/// Translates the virtual address written to it, as if for an unprivileged
+/// write, and puts the result in [`Par`](crate::register::Par). Older
+/// documents call this V2PCWUW.
+pub struct Ats1cuw(pub u32);
+impl SysReg for Ats1cuw {
+    const CP: u32 = 15;
+    const CRN: u32 = 7;
+    const OP1: u32 = 0;
+    const CRM: u32 = 8;
+    const OP2: u32 = 3;
+}
+impl crate::register::SysRegWrite for Ats1cuw {}
+impl Ats1cuw {
+    #[inline]
+    /// Writes ATS1CUW (*Address Translate Stage 1 Current state Unprivileged Write*)
+    ///
+    /// # Safety
+    ///
+    /// Ensure that this value is appropriate for this register
+    pub unsafe fn write(value: Self) {
+        unsafe {
+            <Self as SysRegWrite>::write_raw(value.0);
+        }
+    }
+}
@@ -0,0 +1,27 @@
+//! Code for managing BPIALL (*Branch Predictor Invalidate All*)
+
+use crate::register::{SysReg, SysRegWrite};
+
+/// BPIALL (*Branch Predictor Invalidate All*)
+pub struct Bpiall(pub u32);
+impl SysReg for Bpiall {
+    const CP: u32 = 15;
+    const CRN: u32 = 7;
+    const OP1: u32 = 0;
+    const CRM: u32 = 5;
+    const OP2: u32 = 6;
+}
+impl crate::register::SysRegWrite for Bpiall {}
+impl Bpiall {
+    #[inline]
+    /// Writes BPIALL (*Branch Predictor Invalidate All*)
+    ///
+    /// # Safety
+    ///
+    /// Ensure that this value is appropriate for this register
+    pub unsafe fn write(value: Self) {
+        unsafe {
+            <Self as SysRegWrite>::write_raw(value.0);
+        }
+    }
+}
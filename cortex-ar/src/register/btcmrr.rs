@@ -0,0 +1,38 @@
+//! Code for managing BTCMRR (*BTCM Region Register*)
+
+use crate::register::{SysReg, SysRegRead, SysRegWrite};
+
+/// BTCMRR (*BTCM Region Register*)
+///
+/// Found on the Cortex-R4 and Cortex-R5. The Cortex-R52 uses
+/// [`ImpAtcmregionr`](crate::register::ImpAtcmregionr) and friends instead.
+pub struct Btcmrr(pub u32);
+impl SysReg for Btcmrr {
+    const CP: u32 = 15;
+    const CRN: u32 = 9;
+    const OP1: u32 = 0;
+    const CRM: u32 = 1;
+    const OP2: u32 = 0;
+}
+impl crate::register::SysRegRead for Btcmrr {}
+impl Btcmrr {
+    #[inline]
+    /// Reads BTCMRR (*BTCM Region Register*)
+    pub fn read() -> Btcmrr {
+        unsafe { Self(<Self as SysRegRead>::read_raw()) }
+    }
+}
+impl crate::register::SysRegWrite for Btcmrr {}
+impl Btcmrr {
+    #[inline]
+    /// Writes BTCMRR (*BTCM Region Register*)
+    ///
+    /// # Safety
+    ///
+    /// Ensure that this value is appropriate for this register
+    pub unsafe fn write(value: Self) {
+        unsafe {
+            <Self as SysRegWrite>::write_raw(value.0);
+        }
+    }
+}
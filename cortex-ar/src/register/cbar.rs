@@ -0,0 +1,51 @@
+//! Code for managing CBAR (*Configuration Base Address Register*)
+
+use crate::register::{SysReg, SysRegRead};
+
+/// CBAR (*Configuration Base Address Register*)
+///
+/// This is the encoding used by the Cortex-A5, Cortex-A7, Cortex-A9 and
+/// Cortex-A15. The Cortex-R52 has [`ImpCbar`](crate::register::ImpCbar)
+/// instead.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct Cbar(u32);
+
+impl SysReg for Cbar {
+    const CP: u32 = 15;
+    const CRN: u32 = 15;
+    const OP1: u32 = 4;
+    const CRM: u32 = 0;
+    const OP2: u32 = 0;
+}
+
+impl SysRegRead for Cbar {}
+
+impl Cbar {
+    /// Read CBAR (*Configuration Base Address Register*)
+    #[inline]
+    pub fn read() -> Cbar {
+        // Safety: this read has no side-effects
+        unsafe { Self(<Self as SysRegRead>::read_raw()) }
+    }
+
+    /// Get the periphbase address
+    ///
+    /// Only the bits every processor agrees on are kept, so any bits of
+    /// PERIPHBASE above bit 31 (on a processor with LPAE) are dropped.
+    pub fn periphbase(self) -> *mut u32 {
+        (self.0 & 0xFFFF_E000) as *mut u32
+    }
+}
+
+impl core::fmt::Debug for Cbar {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "CBAR {{ {:010p} }}", self.periphbase())
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for Cbar {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(f, "CBAR {{ 0x{=usize:08x} }}", self.0 as usize)
+    }
+}
@@ -1,9 +1,37 @@
 //! Code for managing CCSIDR (*Current Cache Size ID Register*)
 
+use arbitrary_int::{u10, u15, u3};
+
 use crate::register::{SysReg, SysRegRead};
 
 /// CCSIDR (*Current Cache Size ID Register*)
-pub struct Ccsidr(pub u32);
+///
+/// Describes the cache selected with CSSELR.
+#[bitbybit::bitfield(u32)]
+pub struct Ccsidr {
+    /// Supports Write-Through
+    #[bits(31..=31, r)]
+    wt: bool,
+    /// Supports Write-Back
+    #[bits(30..=30, r)]
+    wb: bool,
+    /// Supports Read-Allocate
+    #[bits(29..=29, r)]
+    ra: bool,
+    /// Supports Write-Allocate
+    #[bits(28..=28, r)]
+    wa: bool,
+    /// The number of sets, minus one
+    #[bits(13..=27, r)]
+    num_sets: u15,
+    /// The associativity (the number of ways), minus one
+    #[bits(3..=12, r)]
+    associativity: u10,
+    /// log2 of the number of words in a line, minus two
+    #[bits(0..=2, r)]
+    line_size: u3,
+}
+
 impl SysReg for Ccsidr {
     const CP: u32 = 15;
     const CRN: u32 = 0;
@@ -16,6 +44,80 @@ impl Ccsidr {
     #[inline]
     /// Reads CCSIDR (*Current Cache Size ID Register*)
     pub fn read() -> Ccsidr {
-        unsafe { Self(<Self as SysRegRead>::read_raw()) }
+        unsafe { Self::new_with_raw_value(<Self as SysRegRead>::read_raw()) }
+    }
+
+    /// How many sets the cache has
+    pub fn sets(&self) -> u32 {
+        u32::from(self.num_sets().value()) + 1
+    }
+
+    /// How many ways the cache has
+    pub fn ways(&self) -> u32 {
+        u32::from(self.associativity().value()) + 1
+    }
+
+    /// log2 of the size of a cache line, in bytes
+    pub fn line_shift(&self) -> u32 {
+        u32::from(self.line_size().value()) + 4
+    }
+
+    /// The size of a cache line, in bytes
+    pub fn line_size_bytes(&self) -> usize {
+        1 << self.line_shift()
+    }
+
+    /// The total size of the cache, in bytes
+    pub fn size_bytes(&self) -> usize {
+        self.sets() as usize * self.ways() as usize * self.line_size_bytes()
+    }
+}
+
+impl core::fmt::Debug for Ccsidr {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Ccsidr")
+            .field("wt", &self.wt())
+            .field("wb", &self.wb())
+            .field("ra", &self.ra())
+            .field("wa", &self.wa())
+            .field("sets", &self.sets())
+            .field("ways", &self.ways())
+            .field("line_size_bytes", &self.line_size_bytes())
+            .finish()
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for Ccsidr {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "CCSIDR {{ wt={=bool}, wb={=bool}, ra={=bool}, wa={=bool}, sets={=u32}, ways={=u32}, line_size_bytes={=usize} }}",
+            self.wt(),
+            self.wb(),
+            self.ra(),
+            self.wa(),
+            self.sets(),
+            self.ways(),
+            self.line_size_bytes()
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn cortex_a9_l1_dcache() {
+        // 32 KB, 4-way, 32 byte lines, Write-Back, Read-Allocate and
+        // Write-Allocate
+        let ccsidr = Ccsidr::new_with_raw_value(0x701F_E019);
+        assert_eq!(ccsidr.sets(), 256);
+        assert_eq!(ccsidr.ways(), 4);
+        assert_eq!(ccsidr.line_size_bytes(), 32);
+        assert_eq!(ccsidr.size_bytes(), 32 * 1024);
+        assert!(!ccsidr.wt());
+        assert!(ccsidr.wb());
     }
 }
@@ -1,9 +1,39 @@
 //! Code for managing CLIDR (*Cache Level ID Register*)
 
+use arbitrary_int::u3;
+
 use crate::register::{SysReg, SysRegRead};
 
 /// CLIDR (*Cache Level ID Register*)
-pub struct Clidr(pub u32);
+#[bitbybit::bitfield(u32)]
+pub struct Clidr {
+    /// Level of Unification Uniprocessor
+    #[bits(27..=29, r)]
+    louu: u3,
+    /// Level of Coherency
+    #[bits(24..=26, r)]
+    loc: u3,
+    /// Level of Unification Inner Shareable
+    #[bits(21..=23, r)]
+    louis: u3,
+}
+
+/// What kind of cache is implemented at a level
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum CacheType {
+    /// There is no cache at this level (or any further out)
+    NoCache = 0b000,
+    /// Only an instruction cache
+    InstructionOnly = 0b001,
+    /// Only a data cache
+    DataOnly = 0b010,
+    /// Separate instruction and data caches
+    Separate = 0b011,
+    /// A unified cache, holding both instructions and data
+    Unified = 0b100,
+}
+
 impl SysReg for Clidr {
     const CP: u32 = 15;
     const CRN: u32 = 0;
@@ -16,6 +46,68 @@ impl Clidr {
     #[inline]
     /// Reads CLIDR (*Cache Level ID Register*)
     pub fn read() -> Clidr {
-        unsafe { Self(<Self as SysRegRead>::read_raw()) }
+        unsafe { Self::new_with_raw_value(<Self as SysRegRead>::read_raw()) }
+    }
+
+    /// What kind of cache is at the given level
+    ///
+    /// Levels are numbered from zero (for the Level 1 caches) up to six, like
+    /// the `level` field in CSSELR. Returns `None` for levels which don't
+    /// exist, or which hold a reserved value.
+    pub fn cache_type(&self, level: u8) -> Option<CacheType> {
+        if level >= 7 {
+            return None;
+        }
+        match (self.raw_value() >> (level * 3)) & 0b111 {
+            0b000 => Some(CacheType::NoCache),
+            0b001 => Some(CacheType::InstructionOnly),
+            0b010 => Some(CacheType::DataOnly),
+            0b011 => Some(CacheType::Separate),
+            0b100 => Some(CacheType::Unified),
+            _ => None,
+        }
+    }
+}
+
+impl core::fmt::Debug for Clidr {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Clidr")
+            .field("louu", &self.louu())
+            .field("loc", &self.loc())
+            .field("louis", &self.louis())
+            .field("ctype1", &self.cache_type(0))
+            .field("ctype2", &self.cache_type(1))
+            .finish()
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for Clidr {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "CLIDR {{ louu={=u8}, loc={=u8}, louis={=u8}, ctype1={}, ctype2={} }}",
+            self.louu().value(),
+            self.loc().value(),
+            self.louis().value(),
+            self.cache_type(0),
+            self.cache_type(1)
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn cache_types() {
+        // Separate L1 caches, a unified L2 cache, and LoC is 2
+        let clidr = Clidr::new_with_raw_value((2 << 24) | (0b100 << 3) | 0b011);
+        assert_eq!(clidr.loc().value(), 2);
+        assert_eq!(clidr.cache_type(0), Some(CacheType::Separate));
+        assert_eq!(clidr.cache_type(1), Some(CacheType::Unified));
+        assert_eq!(clidr.cache_type(2), Some(CacheType::NoCache));
+        assert_eq!(clidr.cache_type(7), None);
     }
 }
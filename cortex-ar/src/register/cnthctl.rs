@@ -31,16 +31,32 @@ pub struct Cnthctl {
     el0pten: bool,
     #[bits(8..=8, rw)]
     el0vten: bool,
+    /// Selects which bit of CNTPCT is the trigger for the event stream
+    /// generated from the physical counter, when that stream is enabled.
     #[bits(4..=7, rw)]
     evnti: u4,
+    /// Controls which transition of the CNTPCT trigger bit, defined by EVNTI,
+    /// generates an event, when the event stream is enabled.
+    ///
+    /// * true: a 1-0 transition
+    /// * false: a 0-1 transition
     #[bits(3..=3, rw)]
     evntdir: bool,
+    /// Enables the generation of an event stream from the physical counter.
     #[bits(2..=2, rw)]
     evnten: bool,
+    /// Controls whether the EL1 physical timer registers (CNTP_CTL, CNTP_CVAL
+    /// and CNTP_TVAL) are accessible from EL1 and EL0 modes.
+    ///
+    /// If false, accesses trap to EL2.
     #[bits(1..=1, rw)]
-    el0vcten: bool,
+    pl1pcen: bool,
+    /// Controls whether the physical counter, CNTPCT, is accessible from EL1
+    /// and EL0 modes.
+    ///
+    /// If false, accesses trap to EL2.
     #[bits(0..=0, rw)]
-    el0pcten: bool,
+    pl1pcten: bool,
 }
 
 impl SysReg for Cnthctl {
@@ -71,4 +87,15 @@ impl Cnthctl {
             <Self as SysRegWrite>::write_raw(value.raw_value());
         }
     }
+
+    /// Modify CNTHCTL (*Hyp Counter-timer Control Register*)
+    #[inline]
+    pub fn modify<F>(f: F)
+    where
+        F: FnOnce(&mut Self),
+    {
+        let mut value = Self::read();
+        f(&mut value);
+        Self::write(value);
+    }
 }
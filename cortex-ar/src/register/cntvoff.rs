@@ -3,6 +3,8 @@
 use crate::register::{SysReg64, SysRegRead64, SysRegWrite64};
 
 /// CNTVOFF (*Virtual Counter-timer Offset Register*)
+///
+/// Only accessible at EL2 (or in Monitor mode with `SCR.NS` set, on Armv7-A).
 pub struct CntVoff(pub u64);
 
 impl SysReg64 for CntVoff {
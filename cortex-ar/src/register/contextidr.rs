@@ -19,6 +19,17 @@ impl Contextidr {
         unsafe { Self(<Self as SysRegRead>::read_raw()) }
     }
 }
+impl Contextidr {
+    /// The Address Space Identifier, in bits 7:0
+    pub const fn asid(&self) -> u8 {
+        self.0 as u8
+    }
+
+    /// The process identifier, in bits 31:8
+    pub const fn procid(&self) -> u32 {
+        self.0 >> 8
+    }
+}
 impl crate::register::SysRegWrite for Contextidr {}
 impl Contextidr {
     #[inline]
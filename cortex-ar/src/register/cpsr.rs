@@ -120,6 +120,61 @@ impl Cpsr {
     }
 }
 
+impl Cpsr {
+    /// Are asynchronous aborts (SErrors) masked?
+    ///
+    /// This is the A bit.
+    #[inline]
+    pub fn async_abort_masked(&self) -> bool {
+        self.a()
+    }
+
+    /// Are data accesses big-endian?
+    ///
+    /// This is the E bit.
+    #[inline]
+    pub fn big_endian(&self) -> bool {
+        self.e()
+    }
+
+    /// Are we in a privileged mode (anything other than User mode)?
+    ///
+    /// An unrecognised mode counts as privileged.
+    #[inline]
+    pub fn is_privileged(&self) -> bool {
+        !matches!(self.mode(), Ok(ProcessorMode::Usr))
+    }
+
+    /// Are we in one of the modes entered by taking an exception?
+    ///
+    /// That's anything other than User mode or System mode - i.e. FIQ, IRQ,
+    /// Supervisor, Monitor, Abort, Hyp or Undefined mode.
+    #[inline]
+    pub fn in_exception(&self) -> bool {
+        !matches!(self.mode(), Ok(ProcessorMode::Usr | ProcessorMode::Sys))
+    }
+
+    /// Switch to a different processor mode, by writing CPSR
+    ///
+    /// # Safety
+    ///
+    /// Most modes have their own stack pointer and link register, so after
+    /// the switch the compiler's idea of the stack will be wrong - unless both
+    /// modes share them (like System mode and User mode) or you have set the
+    /// new mode's SP to match. You cannot leave User mode this way, and you
+    /// cannot enter or leave Hyp mode or Monitor mode this way either.
+    ///
+    /// Switching to User mode means you can't switch back.
+    #[inline]
+    pub unsafe fn change_mode(mode: ProcessorMode) {
+        unsafe {
+            Self::modify(|cpsr| cpsr.set_mode(mode));
+        }
+        #[cfg(target_arch = "arm")]
+        crate::asm::isb();
+    }
+}
+
 impl core::fmt::Debug for Cpsr {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(
@@ -147,3 +202,26 @@ impl defmt::Format for Cpsr {
         defmt::write!(f, "CPSR {{ N={0=31..32} Z={0=30..31} C={0=29..30} V={0=28..29} Q={0=27..28} J={0=24..25} E={0=9..10} A={0=8..9} I={0=7..8} F={0=6..7} T={0=5..6} MODE={0=0..5} }}", self.raw_value())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn modes() {
+        let svc = Cpsr::new_with_raw_value(0x0000_01D3);
+        assert!(svc.is_privileged());
+        assert!(svc.in_exception());
+        assert!(svc.async_abort_masked());
+        assert!(!svc.big_endian());
+
+        let sys = Cpsr::new_with_raw_value(0x0000_001F);
+        assert!(sys.is_privileged());
+        assert!(!sys.in_exception());
+
+        let usr = Cpsr::new_with_raw_value(0x0000_0210);
+        assert!(!usr.is_privileged());
+        assert!(!usr.in_exception());
+        assert!(usr.big_endian());
+    }
+}
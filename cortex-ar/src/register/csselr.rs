@@ -1,9 +1,22 @@
 //! Code for managing CSSELR (*Cache Size Selection Register*)
 
+use arbitrary_int::u3;
+
 use crate::register::{SysReg, SysRegRead, SysRegWrite};
 
 /// CSSELR (*Cache Size Selection Register*)
-pub struct Csselr(pub u32);
+///
+/// Selects which cache CCSIDR describes.
+#[bitbybit::bitfield(u32)]
+pub struct Csselr {
+    /// The cache level, where zero is Level 1
+    #[bits(1..=3, rw)]
+    level: u3,
+    /// Select the instruction cache, rather than the data or unified cache
+    #[bits(0..=0, rw)]
+    ind: bool,
+}
+
 impl SysReg for Csselr {
     const CP: u32 = 15;
     const CRN: u32 = 0;
@@ -16,7 +29,7 @@ impl Csselr {
     #[inline]
     /// Reads CSSELR (*Cache Size Selection Register*)
     pub fn read() -> Csselr {
-        unsafe { Self(<Self as SysRegRead>::read_raw()) }
+        unsafe { Self::new_with_raw_value(<Self as SysRegRead>::read_raw()) }
     }
 }
 impl crate::register::SysRegWrite for Csselr {}
@@ -29,7 +42,28 @@ impl Csselr {
     /// Ensure that this value is appropriate for this register
     pub unsafe fn write(value: Self) {
         unsafe {
-            <Self as SysRegWrite>::write_raw(value.0);
+            <Self as SysRegWrite>::write_raw(value.raw_value());
         }
     }
 }
+
+impl core::fmt::Debug for Csselr {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Csselr")
+            .field("level", &self.level())
+            .field("ind", &self.ind())
+            .finish()
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for Csselr {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "CSSELR {{ level={=u8}, ind={=bool} }}",
+            self.level().value(),
+            self.ind()
+        )
+    }
+}
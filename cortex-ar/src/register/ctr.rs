@@ -1,9 +1,34 @@
 //! Code for managing CTR (*Cache Type Register*)
 
+use arbitrary_int::{u2, u3, u4};
+
 use crate::register::{SysReg, SysRegRead};
 
 /// CTR (*Cache Type Register*)
-pub struct Ctr(pub u32);
+#[bitbybit::bitfield(u32)]
+pub struct Ctr {
+    /// Which format this register is in (`0b100` for Armv7 and later)
+    #[bits(29..=31, r)]
+    format: u3,
+    /// Cache Writeback Granule - log2 of the number of words in the largest
+    /// line that can be written back
+    #[bits(24..=27, r)]
+    cwg: u4,
+    /// Exclusives Reservation Granule - log2 of the number of words in the
+    /// block that LDREX and STREX reserve
+    #[bits(20..=23, r)]
+    erg: u4,
+    /// log2 of the number of words in the smallest data cache line
+    #[bits(16..=19, r)]
+    dminline: u4,
+    /// Level 1 instruction cache policy
+    #[bits(14..=15, r)]
+    l1ip: u2,
+    /// log2 of the number of words in the smallest instruction cache line
+    #[bits(0..=3, r)]
+    iminline: u4,
+}
+
 impl SysReg for Ctr {
     const CP: u32 = 15;
     const CRN: u32 = 0;
@@ -16,6 +41,45 @@ impl Ctr {
     #[inline]
     /// Reads CTR (*Cache Type Register*)
     pub fn read() -> Ctr {
-        unsafe { Self(<Self as SysRegRead>::read_raw()) }
+        unsafe { Self::new_with_raw_value(<Self as SysRegRead>::read_raw()) }
+    }
+
+    /// The size of the smallest data cache line, in bytes
+    pub fn dcache_line_size(&self) -> usize {
+        4 << self.dminline().value()
+    }
+
+    /// The size of the smallest instruction cache line, in bytes
+    pub fn icache_line_size(&self) -> usize {
+        4 << self.iminline().value()
+    }
+}
+
+impl core::fmt::Debug for Ctr {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Ctr")
+            .field("format", &self.format())
+            .field("cwg", &self.cwg())
+            .field("erg", &self.erg())
+            .field("dminline", &self.dminline())
+            .field("l1ip", &self.l1ip())
+            .field("iminline", &self.iminline())
+            .finish()
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for Ctr {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "CTR {{ format={=u8:#b}, cwg={=u8}, erg={=u8}, dminline={=u8}, l1ip={=u8:#b}, iminline={=u8} }}",
+            self.format().value(),
+            self.cwg().value(),
+            self.erg().value(),
+            self.dminline().value(),
+            self.l1ip().value(),
+            self.iminline().value()
+        )
     }
 }
@@ -0,0 +1,35 @@
+//! Code for managing DACR (*Domain Access Control Register*)
+
+use crate::register::{SysReg, SysRegRead, SysRegWrite};
+
+/// DACR (*Domain Access Control Register*)
+pub struct Dacr(pub u32);
+impl SysReg for Dacr {
+    const CP: u32 = 15;
+    const CRN: u32 = 3;
+    const OP1: u32 = 0;
+    const CRM: u32 = 0;
+    const OP2: u32 = 0;
+}
+impl crate::register::SysRegRead for Dacr {}
+impl Dacr {
+    #[inline]
+    /// Reads DACR (*Domain Access Control Register*)
+    pub fn read() -> Dacr {
+        unsafe { Self(<Self as SysRegRead>::read_raw()) }
+    }
+}
+impl crate::register::SysRegWrite for Dacr {}
+impl Dacr {
+    #[inline]
+    /// Writes DACR (*Domain Access Control Register*)
+    ///
+    /// # Safety
+    ///
+    /// Ensure that this value is appropriate for this register
+    pub unsafe fn write(value: Self) {
+        unsafe {
+            <Self as SysRegWrite>::write_raw(value.0);
+        }
+    }
+}
@@ -0,0 +1,64 @@
+//! Code for managing DBGDSCRint (*Debug Status and Control Register, internal view*)
+
+use crate::register::{SysReg, SysRegRead};
+
+/// DBGDSCRint (*Debug Status and Control Register, internal view*)
+///
+/// Only the Debug Communications Channel flags, and the debug enable flags,
+/// are described here.
+#[bitbybit::bitfield(u32)]
+pub struct Dbgdscrint {
+    /// DBGDTRRX register full - the debugger has sent us a word
+    #[bits(30..=30, r)]
+    rxfull: bool,
+    /// DBGDTRTX register full - the debugger hasn't collected our last word
+    #[bits(29..=29, r)]
+    txfull: bool,
+    /// Monitor debug-mode enable
+    #[bits(15..=15, r)]
+    mdbgen: bool,
+    /// Halting debug-mode enable - set by a debugger that wants to halt us
+    #[bits(14..=14, r)]
+    hdbgen: bool,
+}
+
+impl SysReg for Dbgdscrint {
+    const CP: u32 = 14;
+    const CRN: u32 = 0;
+    const OP1: u32 = 0;
+    const CRM: u32 = 1;
+    const OP2: u32 = 0;
+}
+impl crate::register::SysRegRead for Dbgdscrint {}
+impl Dbgdscrint {
+    #[inline]
+    /// Reads DBGDSCRint (*Debug Status and Control Register, internal view*)
+    pub fn read() -> Dbgdscrint {
+        unsafe { Self::new_with_raw_value(<Self as SysRegRead>::read_raw()) }
+    }
+}
+
+impl core::fmt::Debug for Dbgdscrint {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Dbgdscrint")
+            .field("rxfull", &self.rxfull())
+            .field("txfull", &self.txfull())
+            .field("mdbgen", &self.mdbgen())
+            .field("hdbgen", &self.hdbgen())
+            .finish()
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for Dbgdscrint {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "DBGDSCRint {{ rxfull={=bool}, txfull={=bool}, mdbgen={=bool}, hdbgen={=bool} }}",
+            self.rxfull(),
+            self.txfull(),
+            self.mdbgen(),
+            self.hdbgen()
+        )
+    }
+}
@@ -0,0 +1,21 @@
+//! Code for managing DBGDTRRXint (*Host to Target Data Transfer Register, internal view*)
+
+use crate::register::{SysReg, SysRegRead};
+
+/// DBGDTRRXint (*Host to Target Data Transfer Register, internal view*)
+pub struct Dbgdtrrxint(pub u32);
+impl SysReg for Dbgdtrrxint {
+    const CP: u32 = 14;
+    const CRN: u32 = 0;
+    const OP1: u32 = 0;
+    const CRM: u32 = 5;
+    const OP2: u32 = 0;
+}
+impl crate::register::SysRegRead for Dbgdtrrxint {}
+impl Dbgdtrrxint {
+    #[inline]
+    /// Reads DBGDTRRXint (*Host to Target Data Transfer Register, internal view*)
+    pub fn read() -> Dbgdtrrxint {
+        unsafe { Self(<Self as SysRegRead>::read_raw()) }
+    }
+}
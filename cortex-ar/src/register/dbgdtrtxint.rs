@@ -0,0 +1,27 @@
+//! Code for managing DBGDTRTXint (*Target to Host Data Transfer Register, internal view*)
+
+use crate::register::{SysReg, SysRegWrite};
+
+/// DBGDTRTXint (*Target to Host Data Transfer Register, internal view*)
+pub struct Dbgdtrtxint(pub u32);
+impl SysReg for Dbgdtrtxint {
+    const CP: u32 = 14;
+    const CRN: u32 = 0;
+    const OP1: u32 = 0;
+    const CRM: u32 = 5;
+    const OP2: u32 = 0;
+}
+impl crate::register::SysRegWrite for Dbgdtrtxint {}
+impl Dbgdtrtxint {
+    #[inline]
+    /// Writes DBGDTRTXint (*Target to Host Data Transfer Register, internal view*)
+    ///
+    /// # Safety
+    ///
+    /// Ensure that this value is appropriate for this register
+    pub unsafe fn write(value: Self) {
+        unsafe {
+            <Self as SysRegWrite>::write_raw(value.0);
+        }
+    }
+}
@@ -0,0 +1,27 @@
+//! Code for managing DCCIMVAC (*Data Cache line Clean and Invalidate by VA to PoC*)
+
+use crate::register::{SysReg, SysRegWrite};
+
+/// DCCIMVAC (*Data Cache line Clean and Invalidate by VA to PoC*)
+pub struct Dccimvac(pub u32);
+impl SysReg for Dccimvac {
+    const CP: u32 = 15;
+    const CRN: u32 = 7;
+    const OP1: u32 = 0;
+    const CRM: u32 = 14;
+    const OP2: u32 = 1;
+}
+impl crate::register::SysRegWrite for Dccimvac {}
+impl Dccimvac {
+    #[inline]
+    /// Writes DCCIMVAC (*Data Cache line Clean and Invalidate by VA to PoC*)
+    ///
+    /// # Safety
+    ///
+    /// Ensure that this value is appropriate for this register
+    pub unsafe fn write(value: Self) {
+        unsafe {
+            <Self as SysRegWrite>::write_raw(value.0);
+        }
+    }
+}
@@ -0,0 +1,27 @@
+//! Code for managing DCCISW (*Clean and Invalidate Data Cache line by Set/Way*)
+
+use crate::register::{SysReg, SysRegWrite};
+
+/// DCCISW (*Clean and Invalidate Data Cache line by Set/Way*)
+pub struct Dccisw(pub u32);
+impl SysReg for Dccisw {
+    const CP: u32 = 15;
+    const CRN: u32 = 7;
+    const OP1: u32 = 0;
+    const CRM: u32 = 14;
+    const OP2: u32 = 2;
+}
+impl crate::register::SysRegWrite for Dccisw {}
+impl Dccisw {
+    #[inline]
+    /// Writes DCCISW (*Clean and Invalidate Data Cache line by Set/Way*)
+    ///
+    /// # Safety
+    ///
+    /// Ensure that this value is appropriate for this register
+    pub unsafe fn write(value: Self) {
+        unsafe {
+            <Self as SysRegWrite>::write_raw(value.0);
+        }
+    }
+}
@@ -0,0 +1,27 @@
+//! Code for managing DCCMVAC (*Data Cache line Clean by VA to PoC*)
+
+use crate::register::{SysReg, SysRegWrite};
+
+/// DCCMVAC (*Data Cache line Clean by VA to PoC*)
+pub struct Dccmvac(pub u32);
+impl SysReg for Dccmvac {
+    const CP: u32 = 15;
+    const CRN: u32 = 7;
+    const OP1: u32 = 0;
+    const CRM: u32 = 10;
+    const OP2: u32 = 1;
+}
+impl crate::register::SysRegWrite for Dccmvac {}
+impl Dccmvac {
+    #[inline]
+    /// Writes DCCMVAC (*Data Cache line Clean by VA to PoC*)
+    ///
+    /// # Safety
+    ///
+    /// Ensure that this value is appropriate for this register
+    pub unsafe fn write(value: Self) {
+        unsafe {
+            <Self as SysRegWrite>::write_raw(value.0);
+        }
+    }
+}
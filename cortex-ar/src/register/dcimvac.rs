@@ -0,0 +1,27 @@
+//! Code for managing DCIMVAC (*Data Cache line Invalidate by VA to PoC*)
+
+use crate::register::{SysReg, SysRegWrite};
+
+/// DCIMVAC (*Data Cache line Invalidate by VA to PoC*)
+pub struct Dcimvac(pub u32);
+impl SysReg for Dcimvac {
+    const CP: u32 = 15;
+    const CRN: u32 = 7;
+    const OP1: u32 = 0;
+    const CRM: u32 = 6;
+    const OP2: u32 = 1;
+}
+impl crate::register::SysRegWrite for Dcimvac {}
+impl Dcimvac {
+    #[inline]
+    /// Writes DCIMVAC (*Data Cache line Invalidate by VA to PoC*)
+    ///
+    /// # Safety
+    ///
+    /// Ensure that this value is appropriate for this register
+    pub unsafe fn write(value: Self) {
+        unsafe {
+            <Self as SysRegWrite>::write_raw(value.0);
+        }
+    }
+}
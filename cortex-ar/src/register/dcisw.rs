@@ -0,0 +1,27 @@
+//! Code for managing DCISW (*Invalidate Data Cache line by Set/Way*)
+
+use crate::register::{SysReg, SysRegWrite};
+
+/// DCISW (*Invalidate Data Cache line by Set/Way*)
+pub struct Dcisw(pub u32);
+impl SysReg for Dcisw {
+    const CP: u32 = 15;
+    const CRN: u32 = 7;
+    const OP1: u32 = 0;
+    const CRM: u32 = 6;
+    const OP2: u32 = 2;
+}
+impl crate::register::SysRegWrite for Dcisw {}
+impl Dcisw {
+    #[inline]
+    /// Writes DCISW (*Invalidate Data Cache line by Set/Way*)
+    ///
+    /// # Safety
+    ///
+    /// Ensure that this value is appropriate for this register
+    pub unsafe fn write(value: Self) {
+        unsafe {
+            <Self as SysRegWrite>::write_raw(value.0);
+        }
+    }
+}
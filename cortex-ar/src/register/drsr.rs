@@ -2,7 +2,10 @@
 
 use arbitrary_int::Number;
 
-use crate::register::{SysReg, SysRegRead, SysRegWrite};
+use crate::{
+    addr::PhysAddr,
+    register::{SysReg, SysRegRead, SysRegWrite},
+};
 
 /// The size of a region
 #[derive(Debug, PartialEq, Eq)]
@@ -75,8 +78,8 @@ pub enum RegionSize {
 }
 
 impl RegionSize {
-    pub fn is_aligned(&self, addr: *const u8) -> bool {
-        let addr = addr as usize;
+    pub fn is_aligned(&self, addr: PhysAddr) -> bool {
+        let addr = addr.as_usize();
         if *self == RegionSize::_4G {
             // only one address allowed for 4GB region size
             addr == 0
@@ -143,23 +146,23 @@ mod test {
     use super::*;
     #[test]
     fn aligned_4g() {
-        let addr: *const u8 = core::ptr::null();
+        let addr = PhysAddr::new(0);
         assert!(RegionSize::_4G.is_aligned(addr));
     }
 
     #[test]
     fn aligned_1g() {
-        let addr = 0x4000_0000 as *const u8;
+        let addr = PhysAddr::new(0x4000_0000);
         assert!(RegionSize::_1G.is_aligned(addr));
-        let addr = 0x4000_0001 as *const u8;
+        let addr = PhysAddr::new(0x4000_0001);
         assert!(!RegionSize::_1G.is_aligned(addr));
     }
 
     #[test]
     fn aligned_256b() {
-        let addr = 0x100 as *const u8;
+        let addr = PhysAddr::new(0x100);
         assert!(RegionSize::_256B.is_aligned(addr));
-        let addr = 0x80 as *const u8;
+        let addr = PhysAddr::new(0x80);
         assert!(!RegionSize::_256B.is_aligned(addr));
     }
 }
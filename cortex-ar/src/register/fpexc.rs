@@ -0,0 +1,94 @@
+//! Code for managing FPEXC (*Floating-Point Exception Control Register*)
+//!
+//! FPEXC isn't a coprocessor register, so it's accessed with `VMRS` and `VMSR`.
+//! These are only available on targets with a hard-float ABI.
+
+/// FPEXC (*Floating-Point Exception Control Register*)
+#[bitbybit::bitfield(u32)]
+pub struct Fpexc {
+    /// Exception bit - the rest of the register holds the state of an
+    /// asynchronous floating-point exception (subarchitecture defined)
+    #[bits(31..=31, rw)]
+    ex: bool,
+    /// Enable bit - turns the FPU (and Advanced SIMD) on
+    #[bits(30..=30, rw)]
+    en: bool,
+    /// Defined synchronous exception (subarchitecture defined)
+    #[bits(29..=29, rw)]
+    dex: bool,
+}
+
+#[cfg(any(target_abi = "eabihf", not(target_arch = "arm")))]
+impl Fpexc {
+    /// Read FPEXC (*Floating-Point Exception Control Register*)
+    #[inline]
+    pub fn read() -> Self {
+        let r: u32;
+        // Safety: Reading this register has no side-effects and is atomic
+        #[cfg(target_arch = "arm")]
+        unsafe {
+            core::arch::asm!("vmrs {}, fpexc", out(reg) r, options(nomem, nostack, preserves_flags));
+        }
+        #[cfg(not(target_arch = "arm"))]
+        {
+            r = 0;
+        }
+        Self::new_with_raw_value(r)
+    }
+
+    /// Write FPEXC (*Floating-Point Exception Control Register*)
+    ///
+    /// # Safety
+    ///
+    /// Clearing the EN bit makes every floating-point instruction Undefined,
+    /// and the compiler is free to use them anywhere on a hard-float target.
+    /// Only do so if nothing will use the FPU until it is turned back on.
+    #[inline]
+    pub unsafe fn write(_value: Self) {
+        // Safety: up to the caller
+        #[cfg(target_arch = "arm")]
+        unsafe {
+            core::arch::asm!("vmsr fpexc, {}", in(reg) _value.raw_value(), options(nomem, nostack, preserves_flags));
+        }
+    }
+
+    /// Modify FPEXC (*Floating-Point Exception Control Register*)
+    ///
+    /// # Safety
+    ///
+    /// See docs for [Self::write].
+    #[inline]
+    pub unsafe fn modify<F>(f: F)
+    where
+        F: FnOnce(&mut Self),
+    {
+        let mut value = Self::read();
+        f(&mut value);
+        unsafe {
+            Self::write(value);
+        }
+    }
+}
+
+impl core::fmt::Debug for Fpexc {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "FPEXC {{ EX={} EN={} DEX={} }}",
+            self.ex() as u8,
+            self.en() as u8,
+            self.dex() as u8,
+        )
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for Fpexc {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "FPEXC {{ EX={0=31..32} EN={0=30..31} DEX={0=29..30} }}",
+            self.raw_value()
+        )
+    }
+}
@@ -0,0 +1,242 @@
+//! Code for managing FPSCR (*Floating-Point Status and Control Register*)
+//!
+//! FPSCR isn't a coprocessor register, so it's accessed with `VMRS` and `VMSR`.
+//! These are only available on targets with a hard-float ABI, as those are the
+//! ones where we know the FPU has been turned on.
+
+use arbitrary_int::{u2, u3};
+
+/// The rounding mode used by floating-point instructions
+#[derive(Debug, PartialEq, Eq)]
+#[bitbybit::bitenum(u2, exhaustive = true)]
+pub enum RoundingMode {
+    /// Round to Nearest (the default)
+    Nearest = 0b00,
+    /// Round towards Plus Infinity
+    PlusInfinity = 0b01,
+    /// Round towards Minus Infinity
+    MinusInfinity = 0b10,
+    /// Round towards Zero
+    Zero = 0b11,
+}
+
+/// FPSCR (*Floating-Point Status and Control Register*)
+///
+/// The trap enable bits are optional, and on most processors (including the
+/// Cortex-R and Cortex-A processors) they read as zero and ignore writes.
+#[bitbybit::bitfield(u32)]
+pub struct Fpscr {
+    /// Negative condition flag, from the last comparison
+    #[bits(31..=31, rw)]
+    n: bool,
+    /// Zero condition flag, from the last comparison
+    #[bits(30..=30, rw)]
+    z: bool,
+    /// Carry condition flag, from the last comparison
+    #[bits(29..=29, rw)]
+    c: bool,
+    /// Overflow condition flag, from the last comparison
+    #[bits(28..=28, rw)]
+    v: bool,
+    /// Cumulative saturation (Advanced SIMD only)
+    #[bits(27..=27, rw)]
+    qc: bool,
+    /// Alternative Half-Precision format
+    #[bits(26..=26, rw)]
+    ahp: bool,
+    /// Default NaN mode
+    #[bits(25..=25, rw)]
+    dn: bool,
+    /// Flush-to-zero mode
+    #[bits(24..=24, rw)]
+    fz: bool,
+    /// Rounding mode
+    #[bits(22..=23, rw)]
+    rmode: RoundingMode,
+    /// Vector stride (VFP short vectors, which are deprecated)
+    #[bits(20..=21, rw)]
+    stride: u2,
+    /// Vector length (VFP short vectors, which are deprecated)
+    #[bits(16..=18, rw)]
+    len: u3,
+    /// Input Denormal trap enable
+    #[bits(15..=15, rw)]
+    ide: bool,
+    /// Inexact trap enable
+    #[bits(12..=12, rw)]
+    ixe: bool,
+    /// Underflow trap enable
+    #[bits(11..=11, rw)]
+    ufe: bool,
+    /// Overflow trap enable
+    #[bits(10..=10, rw)]
+    ofe: bool,
+    /// Division by Zero trap enable
+    #[bits(9..=9, rw)]
+    dze: bool,
+    /// Invalid Operation trap enable
+    #[bits(8..=8, rw)]
+    ioe: bool,
+    /// Input Denormal cumulative exception flag
+    #[bits(7..=7, rw)]
+    idc: bool,
+    /// Inexact cumulative exception flag
+    #[bits(4..=4, rw)]
+    ixc: bool,
+    /// Underflow cumulative exception flag
+    #[bits(3..=3, rw)]
+    ufc: bool,
+    /// Overflow cumulative exception flag
+    #[bits(2..=2, rw)]
+    ofc: bool,
+    /// Division by Zero cumulative exception flag
+    #[bits(1..=1, rw)]
+    dzc: bool,
+    /// Invalid Operation cumulative exception flag
+    #[bits(0..=0, rw)]
+    ioc: bool,
+}
+
+impl Fpscr {
+    /// The bits holding the cumulative exception flags
+    pub const EXCEPTION_FLAGS: u32 = 0x0000_009F;
+
+    /// Are any of the cumulative exception flags set?
+    pub fn any_exception(&self) -> bool {
+        (self.raw_value() & Self::EXCEPTION_FLAGS) != 0
+    }
+
+    /// A copy of this value with all the cumulative exception flags cleared
+    pub fn without_exceptions(&self) -> Fpscr {
+        Fpscr::new_with_raw_value(self.raw_value() & !Self::EXCEPTION_FLAGS)
+    }
+}
+
+#[cfg(any(target_abi = "eabihf", not(target_arch = "arm")))]
+impl Fpscr {
+    /// Read FPSCR (*Floating-Point Status and Control Register*)
+    #[inline]
+    pub fn read() -> Self {
+        let r: u32;
+        // Safety: Reading this register has no side-effects and is atomic
+        #[cfg(target_arch = "arm")]
+        unsafe {
+            core::arch::asm!("vmrs {}, fpscr", out(reg) r, options(nomem, nostack, preserves_flags));
+        }
+        #[cfg(not(target_arch = "arm"))]
+        {
+            r = 0;
+        }
+        Self::new_with_raw_value(r)
+    }
+
+    /// Write FPSCR (*Floating-Point Status and Control Register*)
+    ///
+    /// # Safety
+    ///
+    /// The compiler assumes the floating-point environment is the default one:
+    /// Round to Nearest, no flush-to-zero, no Default NaN mode, no traps and
+    /// no short vectors. It may evaluate floating-point expressions at compile
+    /// time, or move them past this call. You must make sure any code that
+    /// runs with a different rounding mode (or other setting) can cope with
+    /// that, and put the defaults back before handing control back to code
+    /// that can't.
+    #[inline]
+    pub unsafe fn write(_value: Self) {
+        // Safety: up to the caller
+        #[cfg(target_arch = "arm")]
+        unsafe {
+            core::arch::asm!("vmsr fpscr, {}", in(reg) _value.raw_value(), options(nomem, nostack, preserves_flags));
+        }
+    }
+
+    /// Modify FPSCR (*Floating-Point Status and Control Register*)
+    ///
+    /// # Safety
+    ///
+    /// See docs for [Self::write].
+    #[inline]
+    pub unsafe fn modify<F>(f: F)
+    where
+        F: FnOnce(&mut Self),
+    {
+        let mut value = Self::read();
+        f(&mut value);
+        unsafe {
+            Self::write(value);
+        }
+    }
+
+    /// Clear the cumulative exception flags, returning what they were before
+    ///
+    /// This is safe, because the compiler doesn't depend on the flags.
+    #[inline]
+    pub fn take_exceptions() -> Self {
+        let value = Self::read();
+        // Safety: Only the sticky flags change, which nothing relies on
+        unsafe {
+            Self::write(value.without_exceptions());
+        }
+        value
+    }
+}
+
+impl core::fmt::Debug for Fpscr {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "FPSCR {{ N={} Z={} C={} V={} QC={} AHP={} DN={} FZ={} RMODE={:?} IDE={} IXE={} UFE={} OFE={} DZE={} IOE={} IDC={} IXC={} UFC={} OFC={} DZC={} IOC={} }}",
+            self.n() as u8,
+            self.z() as u8,
+            self.c() as u8,
+            self.v() as u8,
+            self.qc() as u8,
+            self.ahp() as u8,
+            self.dn() as u8,
+            self.fz() as u8,
+            self.rmode(),
+            self.ide() as u8,
+            self.ixe() as u8,
+            self.ufe() as u8,
+            self.ofe() as u8,
+            self.dze() as u8,
+            self.ioe() as u8,
+            self.idc() as u8,
+            self.ixc() as u8,
+            self.ufc() as u8,
+            self.ofc() as u8,
+            self.dzc() as u8,
+            self.ioc() as u8,
+        )
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for Fpscr {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(f, "FPSCR {{ N={0=31..32} Z={0=30..31} C={0=29..30} V={0=28..29} QC={0=27..28} AHP={0=26..27} DN={0=25..26} FZ={0=24..25} RMODE={0=22..24} IDE={0=15..16} IXE={0=12..13} UFE={0=11..12} OFE={0=10..11} DZE={0=9..10} IOE={0=8..9} IDC={0=7..8} IXC={0=4..5} UFC={0=3..4} OFC={0=2..3} DZC={0=1..2} IOC={0=0..1} }}", self.raw_value())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn rounding_mode() {
+        let fpscr = Fpscr::new_with_raw_value(0x00C0_0000);
+        assert_eq!(fpscr.rmode(), RoundingMode::Zero);
+        let fpscr = fpscr.with_rmode(RoundingMode::MinusInfinity).with_fz(true);
+        assert_eq!(fpscr.raw_value(), 0x0180_0000);
+    }
+
+    #[test]
+    fn exceptions() {
+        let fpscr = Fpscr::new_with_raw_value(0xF000_0013);
+        assert!(fpscr.any_exception());
+        assert!(fpscr.ixc() && fpscr.dzc() && fpscr.ioc());
+        let fpscr = fpscr.without_exceptions();
+        assert!(!fpscr.any_exception());
+        assert_eq!(fpscr.raw_value(), 0xF000_0000);
+    }
+}
@@ -3,6 +3,9 @@
 use crate::register::{SysReg, SysRegRead, SysRegWrite};
 
 /// HADFSR (*Hyp Auxiliary Data Fault Status Register*)
+///
+/// The contents are IMPLEMENTATION DEFINED.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub struct Hadfsr(pub u32);
 impl SysReg for Hadfsr {
     const CP: u32 = 15;
@@ -3,6 +3,9 @@
 use crate::register::{SysReg, SysRegRead, SysRegWrite};
 
 /// HAIFSR (*Hyp Auxiliary Instruction Fault Status Register*)
+///
+/// The contents are IMPLEMENTATION DEFINED.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub struct Haifsr(pub u32);
 impl SysReg for Haifsr {
     const CP: u32 = 15;
@@ -0,0 +1,223 @@
+//! Code for managing HCR (*Hyp Configuration Register*)
+
+use crate::register::{SysReg, SysRegRead, SysRegWrite};
+
+/// The shareability domain that barriers executed at EL1 and EL0 are upgraded
+/// to
+#[derive(Debug, PartialEq, Eq)]
+#[bitbybit::bitenum(u2, exhaustive = true)]
+pub enum BarrierShareability {
+    /// No effect - barriers keep the domain they were given
+    NoEffect = 0b00,
+    /// At least Inner Shareable
+    InnerShareable = 0b01,
+    /// At least Outer Shareable
+    OuterShareable = 0b10,
+    /// Full system
+    FullSystem = 0b11,
+}
+
+/// HCR (*Hyp Configuration Register*)
+///
+/// Controls what a guest running at EL1 and EL0 can do without the hypervisor
+/// (at EL2) getting involved. Some bits are only on some architectures, and
+/// are RES0 elsewhere - check the Architecture Reference Manual.
+#[bitbybit::bitfield(u32)]
+pub struct Hcr {
+    /// Trap reads of the virtual memory controls (Armv8 only)
+    #[bits(30..=30, rw)]
+    trvm: bool,
+    /// HVC instruction disable (Armv8 only)
+    #[bits(29..=29, rw)]
+    hcd: bool,
+    /// Trap General Exceptions from EL0 to EL2
+    #[bits(27..=27, rw)]
+    tge: bool,
+    /// Trap writes of the virtual memory controls
+    #[bits(26..=26, rw)]
+    tvm: bool,
+    /// Trap TLB maintenance operations
+    #[bits(25..=25, rw)]
+    ttlb: bool,
+    /// Trap cache maintenance to the Point of Unification
+    #[bits(24..=24, rw)]
+    tpu: bool,
+    /// Trap data or unified cache maintenance to the Point of Coherency
+    #[bits(23..=23, rw)]
+    tpc: bool,
+    /// Trap data or unified cache maintenance by set/way
+    #[bits(22..=22, rw)]
+    tsw: bool,
+    /// Trap ACTLR accesses
+    #[bits(21..=21, rw)]
+    tac: bool,
+    /// Trap IMPLEMENTATION DEFINED functionality (the lockdown, DMA and TCM
+    /// registers)
+    #[bits(20..=20, rw)]
+    tidcp: bool,
+    /// Trap SMC instructions
+    #[bits(19..=19, rw)]
+    tsc: bool,
+    /// Trap ID group 3 (the ID_PFRn, ID_DFR0, ID_MMFRn and ID_ISARn registers)
+    #[bits(18..=18, rw)]
+    tid3: bool,
+    /// Trap ID group 2 (CTR, CCSIDR, CLIDR and CSSELR)
+    #[bits(17..=17, rw)]
+    tid2: bool,
+    /// Trap ID group 1 (TCMTR, TLBTR, AIDR and REVIDR)
+    #[bits(16..=16, rw)]
+    tid1: bool,
+    /// Trap ID group 0 (FPSID and JIDR)
+    #[bits(15..=15, rw)]
+    tid0: bool,
+    /// Trap WFE instructions
+    #[bits(14..=14, rw)]
+    twe: bool,
+    /// Trap WFI instructions
+    #[bits(13..=13, rw)]
+    twi: bool,
+    /// Default Cacheable - treat EL1 and EL0 accesses as Normal Write-Back
+    /// memory when their MMU or MPU is off
+    #[bits(12..=12, rw)]
+    dc: bool,
+    /// Barrier Shareability upgrade
+    #[bits(10..=11, rw)]
+    bsu: BarrierShareability,
+    /// Force broadcast of TLB, branch predictor and instruction cache
+    /// maintenance
+    #[bits(9..=9, rw)]
+    fb: bool,
+    /// Virtual Asynchronous Abort (SError) pending
+    #[bits(8..=8, rw)]
+    va: bool,
+    /// Virtual IRQ pending
+    #[bits(7..=7, rw)]
+    vi: bool,
+    /// Virtual FIQ pending
+    #[bits(6..=6, rw)]
+    vf: bool,
+    /// Route Asynchronous Aborts to EL2
+    #[bits(5..=5, rw)]
+    amo: bool,
+    /// Route IRQs to EL2
+    #[bits(4..=4, rw)]
+    imo: bool,
+    /// Route FIQs to EL2
+    #[bits(3..=3, rw)]
+    fmo: bool,
+    /// Protected Table Walk (Armv7 with the Virtualization Extensions only)
+    #[bits(2..=2, rw)]
+    ptw: bool,
+    /// Set/Way Invalidation Override
+    #[bits(1..=1, rw)]
+    swio: bool,
+    /// Turn on the second stage of address translation (or, on Armv8-R, the
+    /// EL2 MPU's checks on EL1 and EL0 accesses)
+    #[bits(0..=0, rw)]
+    vm: bool,
+}
+
+impl SysReg for Hcr {
+    const CP: u32 = 15;
+    const CRN: u32 = 1;
+    const OP1: u32 = 4;
+    const CRM: u32 = 1;
+    const OP2: u32 = 0;
+}
+impl crate::register::SysRegRead for Hcr {}
+impl Hcr {
+    #[inline]
+    /// Reads HCR (*Hyp Configuration Register*)
+    pub fn read() -> Hcr {
+        unsafe { Self::new_with_raw_value(<Self as SysRegRead>::read_raw()) }
+    }
+}
+impl crate::register::SysRegWrite for Hcr {}
+impl Hcr {
+    #[inline]
+    /// Writes HCR (*Hyp Configuration Register*)
+    ///
+    /// # Safety
+    ///
+    /// Ensure that this value is appropriate for this register. You can only
+    /// write it from EL2.
+    pub unsafe fn write(value: Self) {
+        unsafe {
+            <Self as SysRegWrite>::write_raw(value.raw_value());
+        }
+    }
+
+    #[inline]
+    /// Modify HCR (*Hyp Configuration Register*)
+    ///
+    /// # Safety
+    ///
+    /// See docs for [Self::write].
+    pub unsafe fn modify<F>(f: F)
+    where
+        F: FnOnce(&mut Self),
+    {
+        let mut value = Self::read();
+        f(&mut value);
+        unsafe {
+            Self::write(value);
+        }
+    }
+}
+
+impl core::fmt::Debug for Hcr {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Hcr")
+            .field("trvm", &self.trvm())
+            .field("hcd", &self.hcd())
+            .field("tge", &self.tge())
+            .field("tvm", &self.tvm())
+            .field("ttlb", &self.ttlb())
+            .field("tpu", &self.tpu())
+            .field("tpc", &self.tpc())
+            .field("tsw", &self.tsw())
+            .field("tac", &self.tac())
+            .field("tidcp", &self.tidcp())
+            .field("tsc", &self.tsc())
+            .field("tid3", &self.tid3())
+            .field("tid2", &self.tid2())
+            .field("tid1", &self.tid1())
+            .field("tid0", &self.tid0())
+            .field("twe", &self.twe())
+            .field("twi", &self.twi())
+            .field("dc", &self.dc())
+            .field("bsu", &self.bsu())
+            .field("fb", &self.fb())
+            .field("va", &self.va())
+            .field("vi", &self.vi())
+            .field("vf", &self.vf())
+            .field("amo", &self.amo())
+            .field("imo", &self.imo())
+            .field("fmo", &self.fmo())
+            .field("ptw", &self.ptw())
+            .field("swio", &self.swio())
+            .field("vm", &self.vm())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn traps() {
+        let hcr = Hcr::new_with_raw_value(0)
+            .with_twi(true)
+            .with_twe(true)
+            .with_tsc(true)
+            .with_imo(true)
+            .with_bsu(BarrierShareability::InnerShareable)
+            .with_vm(true);
+        assert_eq!(hcr.raw_value(), 0x0008_6411);
+        assert_eq!(
+            Hcr::new_with_raw_value(0x0000_0C00).bsu(),
+            BarrierShareability::FullSystem
+        );
+    }
+}
@@ -3,6 +3,10 @@
 use crate::register::{SysReg, SysRegRead, SysRegWrite};
 
 /// HDFAR (*Hyp Data Fault Address Register*)
+///
+/// Holds the virtual address of the access that caused a Data Abort taken to
+/// EL2.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub struct Hdfar(pub u32);
 impl SysReg for Hdfar {
     const CP: u32 = 15;
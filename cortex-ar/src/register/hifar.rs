@@ -3,6 +3,10 @@
 use crate::register::{SysReg, SysRegRead, SysRegWrite};
 
 /// HIFAR (*Hyp Instruction Fault Address Register*)
+///
+/// Holds the virtual address of the instruction fetch that caused a Prefetch
+/// Abort taken to EL2.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub struct Hifar(pub u32);
 impl SysReg for Hifar {
     const CP: u32 = 15;
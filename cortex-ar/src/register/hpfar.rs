@@ -3,6 +3,7 @@
 use crate::register::{SysReg, SysRegRead, SysRegWrite};
 
 /// HPFAR (*Hyp IPA Fault Address Register*)
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub struct Hpfar(pub u32);
 impl SysReg for Hpfar {
     const CP: u32 = 15;
@@ -33,3 +34,27 @@ impl Hpfar {
         }
     }
 }
+
+impl Hpfar {
+    /// The faulting Intermediate Physical Address, rounded down to a 4 KiB
+    /// page
+    ///
+    /// HPFAR holds bits 39:12 of the address (in its bits 31:4). It is only
+    /// valid for some faults taken to EL2 - see the Architecture Reference
+    /// Manual. Add the bottom twelve bits from [`Hdfar`](super::Hdfar) or
+    /// [`Hifar`](super::Hifar) to get the full address.
+    pub const fn ipa(&self) -> u64 {
+        ((self.0 & 0xFFFF_FFF0) as u64) << 8
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn ipa() {
+        assert_eq!(Hpfar(0x0123_4560).ipa(), 0x01_2345_6000);
+        assert_eq!(Hpfar(0x0000_000F).ipa(), 0);
+    }
+}
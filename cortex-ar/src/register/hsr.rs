@@ -0,0 +1,388 @@
+//! Code for managing HSR (*Hyp Syndrome Register*)
+//!
+//! When an exception is taken to EL2 (Hyp mode), HSR says why. Use
+//! [`Hsr::syndrome`] to decode it.
+
+use crate::register::{SysReg, SysRegRead, SysRegWrite};
+
+/// HSR (*Hyp Syndrome Register*)
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Hsr(pub u32);
+impl SysReg for Hsr {
+    const CP: u32 = 15;
+    const CRN: u32 = 5;
+    const OP1: u32 = 4;
+    const CRM: u32 = 2;
+    const OP2: u32 = 0;
+}
+impl crate::register::SysRegRead for Hsr {}
+impl Hsr {
+    #[inline]
+    /// Reads HSR (*Hyp Syndrome Register*)
+    pub fn read() -> Hsr {
+        unsafe { Self(<Self as SysRegRead>::read_raw()) }
+    }
+}
+impl crate::register::SysRegWrite for Hsr {}
+impl Hsr {
+    #[inline]
+    /// Writes HSR (*Hyp Syndrome Register*)
+    ///
+    /// # Safety
+    ///
+    /// Ensure that this value is appropriate for this register
+    pub unsafe fn write(value: Self) {
+        unsafe {
+            <Self as SysRegWrite>::write_raw(value.0);
+        }
+    }
+}
+
+impl Hsr {
+    /// The raw Exception Class, from bits 31:26
+    pub const fn ec(&self) -> u8 {
+        (self.0 >> 26) as u8
+    }
+
+    /// Was the trapped instruction 32 bits long (the IL bit)?
+    pub const fn il(&self) -> bool {
+        (self.0 & (1 << 25)) != 0
+    }
+
+    /// The raw Instruction Specific Syndrome, from bits 24:0
+    pub const fn iss(&self) -> u32 {
+        self.0 & 0x01FF_FFFF
+    }
+
+    /// Why the exception was taken
+    pub const fn exception_class(&self) -> ExceptionClass {
+        ExceptionClass::from_ec(self.ec())
+    }
+
+    /// Decode the Instruction Specific Syndrome, according to the Exception
+    /// Class
+    pub const fn syndrome(&self) -> Syndrome {
+        let iss = self.iss();
+        match self.exception_class() {
+            ExceptionClass::Unknown => Syndrome::Unknown,
+            ExceptionClass::WfiWfe => Syndrome::WfiWfe { wfe: iss & 1 != 0 },
+            ExceptionClass::Cp15Access => Syndrome::Cp15Access(CoprocessorAccess::from_iss(iss)),
+            ExceptionClass::Cp15Access64 => {
+                Syndrome::Cp15Access64(CoprocessorAccess64::from_iss(iss))
+            }
+            ExceptionClass::Cp14Access => Syndrome::Cp14Access(CoprocessorAccess::from_iss(iss)),
+            ExceptionClass::Cp14Access64 => {
+                Syndrome::Cp14Access64(CoprocessorAccess64::from_iss(iss))
+            }
+            ExceptionClass::Svc => Syndrome::Svc { imm16: iss as u16 },
+            ExceptionClass::Hvc => Syndrome::Hvc { imm16: iss as u16 },
+            ExceptionClass::PrefetchAbortLowerEl => Syndrome::PrefetchAbort {
+                from_lower_el: true,
+                status: (iss & 0x3F) as u8,
+            },
+            ExceptionClass::PrefetchAbortHyp => Syndrome::PrefetchAbort {
+                from_lower_el: false,
+                status: (iss & 0x3F) as u8,
+            },
+            ExceptionClass::DataAbortLowerEl => Syndrome::DataAbort(DataAbort::from_iss(true, iss)),
+            ExceptionClass::DataAbortHyp => Syndrome::DataAbort(DataAbort::from_iss(false, iss)),
+            _ => Syndrome::Other { ec: self.ec(), iss },
+        }
+    }
+}
+
+/// The Exception Class in HSR, which says why an exception was taken to EL2
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ExceptionClass {
+    /// Unknown reason, e.g. an Undefined Instruction
+    Unknown,
+    /// A trapped WFI or WFE
+    WfiWfe,
+    /// A trapped MCR or MRC to CP15
+    Cp15Access,
+    /// A trapped MCRR or MRRC to CP15
+    Cp15Access64,
+    /// A trapped MCR or MRC to CP14
+    Cp14Access,
+    /// A trapped LDC or STC to CP14
+    Cp14LdcStc,
+    /// A trapped access to the FPU or Advanced SIMD, because of HCPTR
+    FpAccess,
+    /// A trapped VMRS access to CP10
+    Cp10Access,
+    /// A trapped MRRC to CP14
+    Cp14Access64,
+    /// An Illegal Execution State exception (Armv8 only)
+    IllegalState,
+    /// An SVC routed to EL2, because HCR.TGE was set
+    Svc,
+    /// An HVC
+    Hvc,
+    /// A trapped SMC
+    Smc,
+    /// A Prefetch Abort from EL1 or EL0
+    PrefetchAbortLowerEl,
+    /// A Prefetch Abort from EL2
+    PrefetchAbortHyp,
+    /// A PC alignment fault (Armv8 only)
+    PcAlignment,
+    /// A Data Abort from EL1 or EL0
+    DataAbortLowerEl,
+    /// A Data Abort from EL2
+    DataAbortHyp,
+    /// A reserved or unrecognised Exception Class
+    Other(u8),
+}
+
+impl ExceptionClass {
+    /// Convert the raw six-bit Exception Class
+    pub const fn from_ec(ec: u8) -> ExceptionClass {
+        match ec {
+            0x00 => ExceptionClass::Unknown,
+            0x01 => ExceptionClass::WfiWfe,
+            0x03 => ExceptionClass::Cp15Access,
+            0x04 => ExceptionClass::Cp15Access64,
+            0x05 => ExceptionClass::Cp14Access,
+            0x06 => ExceptionClass::Cp14LdcStc,
+            0x07 => ExceptionClass::FpAccess,
+            0x08 => ExceptionClass::Cp10Access,
+            0x0C => ExceptionClass::Cp14Access64,
+            0x0E => ExceptionClass::IllegalState,
+            0x11 => ExceptionClass::Svc,
+            0x12 => ExceptionClass::Hvc,
+            0x13 => ExceptionClass::Smc,
+            0x20 => ExceptionClass::PrefetchAbortLowerEl,
+            0x21 => ExceptionClass::PrefetchAbortHyp,
+            0x22 => ExceptionClass::PcAlignment,
+            0x24 => ExceptionClass::DataAbortLowerEl,
+            0x25 => ExceptionClass::DataAbortHyp,
+            _ => ExceptionClass::Other(ec),
+        }
+    }
+}
+
+/// The decoded syndrome for an exception taken to EL2
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Syndrome {
+    /// Unknown reason, e.g. an Undefined Instruction
+    Unknown,
+    /// A trapped WFI or WFE
+    WfiWfe {
+        /// Was it a WFE (rather than a WFI)?
+        wfe: bool,
+    },
+    /// A trapped MCR or MRC to CP15
+    Cp15Access(CoprocessorAccess),
+    /// A trapped MCRR or MRRC to CP15
+    Cp15Access64(CoprocessorAccess64),
+    /// A trapped MCR or MRC to CP14
+    Cp14Access(CoprocessorAccess),
+    /// A trapped MRRC to CP14
+    Cp14Access64(CoprocessorAccess64),
+    /// An SVC routed to EL2
+    Svc {
+        /// The immediate value from the instruction
+        imm16: u16,
+    },
+    /// An HVC
+    Hvc {
+        /// The immediate value from the instruction
+        imm16: u16,
+    },
+    /// A Prefetch Abort
+    PrefetchAbort {
+        /// Was it from EL1 or EL0 (rather than EL2)?
+        from_lower_el: bool,
+        /// The Instruction Fault Status Code
+        status: u8,
+    },
+    /// A Data Abort
+    DataAbort(DataAbort),
+    /// Any other Exception Class - see the Architecture Reference Manual
+    Other {
+        /// The raw Exception Class
+        ec: u8,
+        /// The raw Instruction Specific Syndrome
+        iss: u32,
+    },
+}
+
+/// A trapped 32-bit coprocessor access (MCR or MRC)
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct CoprocessorAccess {
+    /// The opc1 value from the instruction
+    pub opc1: u8,
+    /// The opc2 value from the instruction
+    pub opc2: u8,
+    /// The CRn value from the instruction
+    pub crn: u8,
+    /// The CRm value from the instruction
+    pub crm: u8,
+    /// The general-purpose register being transferred
+    pub rt: u8,
+    /// Was it a read from the coprocessor (an MRC)?
+    pub read: bool,
+}
+
+impl CoprocessorAccess {
+    /// Decode the Instruction Specific Syndrome for a trapped MCR or MRC
+    pub const fn from_iss(iss: u32) -> CoprocessorAccess {
+        CoprocessorAccess {
+            opc2: ((iss >> 17) & 0x7) as u8,
+            opc1: ((iss >> 14) & 0x7) as u8,
+            crn: ((iss >> 10) & 0xF) as u8,
+            rt: ((iss >> 5) & 0xF) as u8,
+            crm: ((iss >> 1) & 0xF) as u8,
+            read: iss & 1 != 0,
+        }
+    }
+}
+
+/// A trapped 64-bit coprocessor access (MCRR or MRRC)
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct CoprocessorAccess64 {
+    /// The opc1 value from the instruction
+    pub opc1: u8,
+    /// The CRm value from the instruction
+    pub crm: u8,
+    /// The general-purpose register for the bottom 32 bits
+    pub rt: u8,
+    /// The general-purpose register for the top 32 bits
+    pub rt2: u8,
+    /// Was it a read from the coprocessor (an MRRC)?
+    pub read: bool,
+}
+
+impl CoprocessorAccess64 {
+    /// Decode the Instruction Specific Syndrome for a trapped MCRR or MRRC
+    pub const fn from_iss(iss: u32) -> CoprocessorAccess64 {
+        CoprocessorAccess64 {
+            opc1: ((iss >> 16) & 0xF) as u8,
+            rt2: ((iss >> 10) & 0xF) as u8,
+            rt: ((iss >> 5) & 0xF) as u8,
+            crm: ((iss >> 1) & 0xF) as u8,
+            read: iss & 1 != 0,
+        }
+    }
+}
+
+/// A Data Abort taken to EL2
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct DataAbort {
+    /// Was it from EL1 or EL0 (rather than EL2)?
+    pub from_lower_el: bool,
+    /// The load or store that faulted, if the hardware could describe it
+    ///
+    /// This is what you need to emulate an access to a device.
+    pub access: Option<DataAccess>,
+    /// Was it an External Abort?
+    pub external: bool,
+    /// Was it caused by cache maintenance?
+    pub cache_maintenance: bool,
+    /// Was it on a stage 1 translation table walk?
+    pub stage1_walk: bool,
+    /// Was it caused by a write (rather than a read)?
+    pub write: bool,
+    /// The Data Fault Status Code
+    pub status: u8,
+}
+
+impl DataAbort {
+    /// Decode the Instruction Specific Syndrome for a Data Abort
+    pub const fn from_iss(from_lower_el: bool, iss: u32) -> DataAbort {
+        let isv = iss & (1 << 24) != 0;
+        DataAbort {
+            from_lower_el,
+            access: if isv {
+                Some(DataAccess {
+                    size: 1 << ((iss >> 22) & 0x3),
+                    sign_extend: iss & (1 << 21) != 0,
+                    rt: ((iss >> 16) & 0xF) as u8,
+                })
+            } else {
+                None
+            },
+            external: iss & (1 << 9) != 0,
+            cache_maintenance: iss & (1 << 8) != 0,
+            stage1_walk: iss & (1 << 7) != 0,
+            write: iss & (1 << 6) != 0,
+            status: (iss & 0x3F) as u8,
+        }
+    }
+}
+
+/// The details of a load or store that caused a Data Abort
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct DataAccess {
+    /// How many bytes were accessed
+    pub size: u8,
+    /// Was the loaded value sign-extended?
+    pub sign_extend: bool,
+    /// The general-purpose register being loaded or stored
+    pub rt: u8,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn hvc() {
+        let hsr = Hsr(0x4A00_0012);
+        assert_eq!(hsr.exception_class(), ExceptionClass::Hvc);
+        assert!(hsr.il());
+        assert_eq!(hsr.syndrome(), Syndrome::Hvc { imm16: 0x12 });
+    }
+
+    #[test]
+    fn cp15_read() {
+        // MRC p15, 0, r2, c0, c0, 0 (reading MIDR)
+        let hsr = Hsr(0x0FE0_0041);
+        assert_eq!(
+            hsr.syndrome(),
+            Syndrome::Cp15Access(CoprocessorAccess {
+                opc1: 0,
+                opc2: 0,
+                crn: 0,
+                crm: 0,
+                rt: 2,
+                read: true,
+            })
+        );
+    }
+
+    #[test]
+    fn data_abort() {
+        // A word store of r3 from EL1, with a Translation fault
+        let hsr = Hsr(0x9383_0047);
+        assert_eq!(
+            hsr.syndrome(),
+            Syndrome::DataAbort(DataAbort {
+                from_lower_el: true,
+                access: Some(DataAccess {
+                    size: 4,
+                    sign_extend: false,
+                    rt: 3,
+                }),
+                external: false,
+                cache_maintenance: false,
+                stage1_walk: false,
+                write: true,
+                status: 0x07,
+            })
+        );
+    }
+
+    #[test]
+    fn other() {
+        let hsr = Hsr(0x3F << 26);
+        assert_eq!(hsr.exception_class(), ExceptionClass::Other(0x3F));
+        assert_eq!(hsr.syndrome(), Syndrome::Other { ec: 0x3F, iss: 0 });
+    }
+}
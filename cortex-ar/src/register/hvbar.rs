@@ -6,10 +6,11 @@ use crate::register::{SysReg, SysRegRead, SysRegWrite};
 ///
 /// There is no `modify` method because this register holds a single 32-bit address.
 ///
-/// This is only available in EL2.
+/// This is only available in EL2 (Hyp mode), on Armv8-R and on Armv7-A with the
+/// Virtualization Extensions.
 #[derive(Clone, Copy, PartialEq, Eq)]
 #[repr(transparent)]
-pub struct Hvbar(*mut u32);
+pub struct Hvbar(pub *mut u32);
 
 impl SysReg for Hvbar {
     const CP: u32 = 15;
@@ -35,8 +36,7 @@ impl Hvbar {
     ///
     /// # Safety
     ///
-    /// You must supply a correctly-aligned address of a valid Arm Cortex-R
-    /// Vector Table.
+    /// You must supply a correctly-aligned address of a valid Vector Table.
     #[inline]
     pub unsafe fn write(value: Self) {
         // Safety: Writing this register is atomic
@@ -0,0 +1,27 @@
+//! Code for managing ICC_EOIR1 (*Interrupt Controller End Of Interrupt Register 1*)
+
+use crate::register::{SysReg, SysRegWrite};
+
+/// ICC_EOIR1 (*Interrupt Controller End Of Interrupt Register 1*)
+pub struct IccEoir1(pub u32);
+impl SysReg for IccEoir1 {
+    const CP: u32 = 15;
+    const CRN: u32 = 12;
+    const OP1: u32 = 0;
+    const CRM: u32 = 12;
+    const OP2: u32 = 1;
+}
+impl crate::register::SysRegWrite for IccEoir1 {}
+impl IccEoir1 {
+    #[inline]
+    /// Writes ICC_EOIR1 (*Interrupt Controller End Of Interrupt Register 1*)
+    ///
+    /// # Safety
+    ///
+    /// Ensure that this value is appropriate for this register
+    pub unsafe fn write(value: Self) {
+        unsafe {
+            <Self as SysRegWrite>::write_raw(value.0);
+        }
+    }
+}
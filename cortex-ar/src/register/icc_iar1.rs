@@ -0,0 +1,24 @@
+//! Code for managing ICC_IAR1 (*Interrupt Controller Interrupt Acknowledge Register 1*)
+
+use crate::register::{SysReg, SysRegRead};
+
+/// ICC_IAR1 (*Interrupt Controller Interrupt Acknowledge Register 1*)
+pub struct IccIar1(pub u32);
+impl SysReg for IccIar1 {
+    const CP: u32 = 15;
+    const CRN: u32 = 12;
+    const OP1: u32 = 0;
+    const CRM: u32 = 12;
+    const OP2: u32 = 0;
+}
+impl crate::register::SysRegRead for IccIar1 {}
+impl IccIar1 {
+    #[inline]
+    /// Reads ICC_IAR1 (*Interrupt Controller Interrupt Acknowledge Register 1*)
+    ///
+    /// Reading this register acknowledges the highest priority pending Group 1
+    /// interrupt.
+    pub fn read() -> IccIar1 {
+        unsafe { Self(<Self as SysRegRead>::read_raw()) }
+    }
+}
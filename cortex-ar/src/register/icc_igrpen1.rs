@@ -0,0 +1,35 @@
+//! Code for managing ICC_IGRPEN1 (*Interrupt Controller Interrupt Group 1 Enable Register*)
+
+use crate::register::{SysReg, SysRegRead, SysRegWrite};
+
+/// ICC_IGRPEN1 (*Interrupt Controller Interrupt Group 1 Enable Register*)
+pub struct IccIgrpen1(pub u32);
+impl SysReg for IccIgrpen1 {
+    const CP: u32 = 15;
+    const CRN: u32 = 12;
+    const OP1: u32 = 0;
+    const CRM: u32 = 12;
+    const OP2: u32 = 7;
+}
+impl crate::register::SysRegRead for IccIgrpen1 {}
+impl IccIgrpen1 {
+    #[inline]
+    /// Reads ICC_IGRPEN1 (*Interrupt Controller Interrupt Group 1 Enable Register*)
+    pub fn read() -> IccIgrpen1 {
+        unsafe { Self(<Self as SysRegRead>::read_raw()) }
+    }
+}
+impl crate::register::SysRegWrite for IccIgrpen1 {}
+impl IccIgrpen1 {
+    #[inline]
+    /// Writes ICC_IGRPEN1 (*Interrupt Controller Interrupt Group 1 Enable Register*)
+    ///
+    /// # Safety
+    ///
+    /// Ensure that this value is appropriate for this register
+    pub unsafe fn write(value: Self) {
+        unsafe {
+            <Self as SysRegWrite>::write_raw(value.0);
+        }
+    }
+}
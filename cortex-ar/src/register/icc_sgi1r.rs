@@ -0,0 +1,49 @@
+//! Code for managing ICC_SGI1R (*Interrupt Controller Software Generated Interrupt Group 1 Register*)
+
+use crate::register::{SysReg64, SysRegWrite64};
+
+/// ICC_SGI1R (*Interrupt Controller Software Generated Interrupt Group 1 Register*)
+pub struct IccSgi1r(pub u64);
+
+impl SysReg64 for IccSgi1r {
+    const CP: u32 = 15;
+    const OP1: u32 = 0;
+    const CRM: u32 = 12;
+}
+
+impl IccSgi1r {
+    /// Send SGI `intid` to the cores in `target_list` with the given affinity
+    ///
+    /// Bit N of `target_list` selects the core with Aff0 = N, within the
+    /// cluster given by `aff3`, `aff2` and `aff1`.
+    pub const fn to_cores(intid: u8, aff3: u8, aff2: u8, aff1: u8, target_list: u16) -> IccSgi1r {
+        IccSgi1r(
+            ((aff3 as u64) << 48)
+                | ((aff2 as u64) << 32)
+                | (((intid & 0xF) as u64) << 24)
+                | ((aff1 as u64) << 16)
+                | target_list as u64,
+        )
+    }
+
+    /// Send SGI `intid` to every core except this one
+    pub const fn to_all_others(intid: u8) -> IccSgi1r {
+        IccSgi1r((1 << 40) | (((intid & 0xF) as u64) << 24))
+    }
+}
+
+impl SysRegWrite64 for IccSgi1r {}
+
+impl IccSgi1r {
+    #[inline]
+    /// Writes ICC_SGI1R (*Interrupt Controller Software Generated Interrupt Group 1 Register*)
+    ///
+    /// # Safety
+    ///
+    /// Ensure that this value is appropriate for this register
+    pub unsafe fn write(value: Self) {
+        unsafe {
+            <Self as SysRegWrite64>::write_raw(value.0);
+        }
+    }
+}
@@ -0,0 +1,27 @@
+//! Code for managing ICIALLU (*Instruction Cache Invalidate All to PoU*)
+
+use crate::register::{SysReg, SysRegWrite};
+
+/// ICIALLU (*Instruction Cache Invalidate All to PoU*)
+pub struct Iciallu(pub u32);
+impl SysReg for Iciallu {
+    const CP: u32 = 15;
+    const CRN: u32 = 7;
+    const OP1: u32 = 0;
+    const CRM: u32 = 5;
+    const OP2: u32 = 0;
+}
+impl crate::register::SysRegWrite for Iciallu {}
+impl Iciallu {
+    #[inline]
+    /// Writes ICIALLU (*Instruction Cache Invalidate All to PoU*)
+    ///
+    /// # Safety
+    ///
+    /// Ensure that this value is appropriate for this register
+    pub unsafe fn write(value: Self) {
+        unsafe {
+            <Self as SysRegWrite>::write_raw(value.0);
+        }
+    }
+}
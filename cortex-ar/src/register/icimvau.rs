@@ -0,0 +1,27 @@
+//! Code for managing ICIMVAU (*Instruction Cache line Invalidate by VA to PoU*)
+
+use crate::register::{SysReg, SysRegWrite};
+
+/// ICIMVAU (*Instruction Cache line Invalidate by VA to PoU*)
+pub struct Icimvau(pub u32);
+impl SysReg for Icimvau {
+    const CP: u32 = 15;
+    const CRN: u32 = 7;
+    const OP1: u32 = 0;
+    const CRM: u32 = 5;
+    const OP2: u32 = 1;
+}
+impl crate::register::SysRegWrite for Icimvau {}
+impl Icimvau {
+    #[inline]
+    /// Writes ICIMVAU (*Instruction Cache line Invalidate by VA to PoU*)
+    ///
+    /// # Safety
+    ///
+    /// Ensure that this value is appropriate for this register
+    pub unsafe fn write(value: Self) {
+        unsafe {
+            <Self as SysRegWrite>::write_raw(value.0);
+        }
+    }
+}
@@ -3,6 +3,9 @@
 use crate::register::{SysReg, SysRegRead};
 
 /// IMP_BUILDOPTR (*Build Options Register*)
+///
+/// Describes the options the Cortex-R52 was built with. See the Cortex-R52
+/// Technical Reference Manual for the full layout.
 pub struct ImpBuildoptr(pub u32);
 impl SysReg for ImpBuildoptr {
     const CP: u32 = 15;
@@ -19,3 +22,29 @@ impl ImpBuildoptr {
         unsafe { Self(<Self as SysRegRead>::read_raw()) }
     }
 }
+
+impl ImpBuildoptr {
+    /// Which sort of core redundancy was this processor built with?
+    #[inline]
+    pub fn redundancy(&self) -> Redundancy {
+        match self.0 & 0b11 {
+            0b01 => Redundancy::Dcls,
+            0b10 | 0b11 => Redundancy::SplitLock,
+            _ => Redundancy::None,
+        }
+    }
+}
+
+/// The core redundancy options a Cortex-R52 can be built with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Redundancy {
+    /// Each core runs on its own
+    None,
+    /// Dual-Core Lock-Step - each core has a redundant copy checking it
+    Dcls,
+    /// Split-Lock - the cores run in lock-step pairs, or independently,
+    /// depending on a pin sampled at reset (see [`ImpPinoptr::split`])
+    ///
+    /// [`ImpPinoptr::split`]: crate::register::ImpPinoptr::split
+    SplitLock,
+}
@@ -19,3 +19,15 @@ impl ImpPinoptr {
         unsafe { Self(<Self as SysRegRead>::read_raw()) }
     }
 }
+
+impl ImpPinoptr {
+    /// Was the processor started in split mode?
+    ///
+    /// Only meaningful on a processor built with
+    /// [`Redundancy::SplitLock`](crate::register::imp::imp_buildoptr::Redundancy::SplitLock).
+    /// When false, the cores are running in lock-step.
+    #[inline]
+    pub fn split(&self) -> bool {
+        (self.0 & 1) != 0
+    }
+}
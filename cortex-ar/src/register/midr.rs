@@ -43,6 +43,94 @@ impl Midr {
     }
 }
 
+impl Midr {
+    /// Which processor this is, from the implementer and part number
+    pub fn cpu_model(&self) -> CpuModel {
+        CpuModel::from_ids(self.implementer(), self.part_no().value())
+    }
+
+    /// The revision, as the N and M in 'rNpM'
+    ///
+    /// These come from the variant and revision fields. Errata notices use
+    /// this form, e.g. r1p3.
+    pub fn revision(&self) -> (u8, u8) {
+        (self.variant().value(), self.rev().value())
+    }
+}
+
+/// The processors we know about, as identified by MIDR
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum CpuModel {
+    /// Arm Cortex-R4
+    CortexR4,
+    /// Arm Cortex-R5
+    CortexR5,
+    /// Arm Cortex-R7
+    CortexR7,
+    /// Arm Cortex-R8
+    CortexR8,
+    /// Arm Cortex-R52
+    CortexR52,
+    /// Arm Cortex-R52+
+    CortexR52Plus,
+    /// Arm Cortex-A5
+    CortexA5,
+    /// Arm Cortex-A7
+    CortexA7,
+    /// Arm Cortex-A8
+    CortexA8,
+    /// Arm Cortex-A9
+    CortexA9,
+    /// Arm Cortex-A12
+    CortexA12,
+    /// Arm Cortex-A15
+    CortexA15,
+    /// Arm Cortex-A17
+    CortexA17,
+    /// Something else
+    Unknown {
+        /// The implementer code, e.g. 0x41 for Arm
+        implementer: u8,
+        /// The primary part number
+        part_no: u16,
+    },
+}
+
+impl CpuModel {
+    /// The implementer code for Arm Limited
+    pub const ARM: u8 = 0x41;
+
+    /// Identify a processor from the MIDR implementer and part number fields
+    pub const fn from_ids(implementer: u8, part_no: u16) -> CpuModel {
+        if implementer != Self::ARM {
+            return CpuModel::Unknown {
+                implementer,
+                part_no,
+            };
+        }
+        match part_no {
+            0xC14 => CpuModel::CortexR4,
+            0xC15 => CpuModel::CortexR5,
+            0xC17 => CpuModel::CortexR7,
+            0xC18 => CpuModel::CortexR8,
+            0xD13 => CpuModel::CortexR52,
+            0xD16 => CpuModel::CortexR52Plus,
+            0xC05 => CpuModel::CortexA5,
+            0xC07 => CpuModel::CortexA7,
+            0xC08 => CpuModel::CortexA8,
+            0xC09 => CpuModel::CortexA9,
+            0xC0D => CpuModel::CortexA12,
+            0xC0F => CpuModel::CortexA15,
+            0xC0E => CpuModel::CortexA17,
+            _ => CpuModel::Unknown {
+                implementer,
+                part_no,
+            },
+        }
+    }
+}
+
 impl core::fmt::Debug for Midr {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         write!(f, "MIDR {{ implementer=0x{:02x} variant=0x{:x} arch=0x{:x} part_no=0x{:03x} rev=0x{:x} }}",
@@ -56,3 +144,35 @@ impl defmt::Format for Midr {
         defmt::write!(f, "MIDR {{ implementer=0x{0=24..32:02x} variant=0x{0=20..24:x} arch=0x{0=16..20:x} part_no=0x{0=4..16:03x} rev=0x{0=0..4:x} }}", self.raw_value())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn cortex_r5() {
+        // Cortex-R5 r1p3
+        let midr = Midr::new_with_raw_value(0x411F_C153);
+        assert_eq!(midr.cpu_model(), CpuModel::CortexR5);
+        assert_eq!(midr.revision(), (1, 3));
+    }
+
+    #[test]
+    fn cortex_a9() {
+        // Cortex-A9 r3p0
+        let midr = Midr::new_with_raw_value(0x413F_C090);
+        assert_eq!(midr.cpu_model(), CpuModel::CortexA9);
+        assert_eq!(midr.revision(), (3, 0));
+    }
+
+    #[test]
+    fn unknown() {
+        assert_eq!(
+            CpuModel::from_ids(0x51, 0x00F),
+            CpuModel::Unknown {
+                implementer: 0x51,
+                part_no: 0x00F
+            }
+        );
+    }
+}
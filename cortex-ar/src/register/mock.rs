@@ -0,0 +1,98 @@
+//! A fake register file, for testing register code on the host
+//!
+//! With the `mock` feature enabled, when not compiling for Arm, every
+//! [`SysRegRead`](super::SysRegRead) and [`SysRegWrite`](super::SysRegWrite)
+//! access (and the 64-bit equivalents) goes to a thread-local table instead of
+//! a real register. Registers you haven't set read as zero.
+//!
+//! ```rust ignore
+//! use cortex_ar::register::{mock, Mpidr};
+//!
+//! mock::set::<Mpidr>(0x8000_0001);
+//! assert_eq!(Mpidr::read().0, 0x8000_0001);
+//! ```
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use super::{SysReg, SysReg64};
+
+/// Identifies a 32-bit register by its encoding (CP, CRn, OP1, CRm, OP2)
+type Key32 = (u32, u32, u32, u32, u32);
+
+/// Identifies a 64-bit register by its encoding (CP, OP1, CRm)
+type Key64 = (u32, u32, u32);
+
+std::thread_local! {
+    static REGS_32: RefCell<HashMap<Key32, u32>> = RefCell::new(HashMap::new());
+    static REGS_64: RefCell<HashMap<Key64, u64>> = RefCell::new(HashMap::new());
+}
+
+fn key32<R: SysReg + ?Sized>() -> Key32 {
+    (R::CP, R::CRN, R::OP1, R::CRM, R::OP2)
+}
+
+fn key64<R: SysReg64 + ?Sized>() -> Key64 {
+    (R::CP, R::OP1, R::CRM)
+}
+
+/// Set the value of a fake 32-bit register
+pub fn set<R: SysReg>(value: u32) {
+    REGS_32.with(|regs| regs.borrow_mut().insert(key32::<R>(), value));
+}
+
+/// Get the value of a fake 32-bit register
+pub fn get<R: SysReg>() -> u32 {
+    read::<R>()
+}
+
+/// Set the value of a fake 64-bit register
+pub fn set64<R: SysReg64>(value: u64) {
+    REGS_64.with(|regs| regs.borrow_mut().insert(key64::<R>(), value));
+}
+
+/// Get the value of a fake 64-bit register
+pub fn get64<R: SysReg64>() -> u64 {
+    read64::<R>()
+}
+
+/// Set every fake register back to zero
+pub fn reset() {
+    REGS_32.with(|regs| regs.borrow_mut().clear());
+    REGS_64.with(|regs| regs.borrow_mut().clear());
+}
+
+pub(crate) fn read<R: SysReg + ?Sized>() -> u32 {
+    REGS_32.with(|regs| regs.borrow().get(&key32::<R>()).copied().unwrap_or(0))
+}
+
+pub(crate) fn write<R: SysReg + ?Sized>(value: u32) {
+    REGS_32.with(|regs| regs.borrow_mut().insert(key32::<R>(), value));
+}
+
+pub(crate) fn read64<R: SysReg64 + ?Sized>() -> u64 {
+    REGS_64.with(|regs| regs.borrow().get(&key64::<R>()).copied().unwrap_or(0))
+}
+
+pub(crate) fn write64<R: SysReg64 + ?Sized>(value: u64) {
+    REGS_64.with(|regs| regs.borrow_mut().insert(key64::<R>(), value));
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::register::{CntpCval, Mpidr, SysRegRead};
+
+    #[test]
+    fn read_write() {
+        reset();
+        assert_eq!(Mpidr::read().0, 0);
+        set::<Mpidr>(0x8000_0001);
+        assert_eq!(Mpidr::read().0, 0x8000_0001);
+        assert_eq!(unsafe { <Mpidr as SysRegRead>::read_raw() }, 0x8000_0001);
+        CntpCval::write(CntpCval(0x1234_5678_9ABC));
+        assert_eq!(get64::<CntpCval>(), 0x1234_5678_9ABC);
+        reset();
+        assert_eq!(get::<Mpidr>(), 0);
+    }
+}
@@ -7,13 +7,61 @@ pub mod aidr;
 pub mod aifsr;
 pub mod amair0;
 pub mod amair1;
+pub mod atcmrr;
+pub mod ats1cpr;
+pub mod ats1cpw;
+pub mod ats1cur;
+pub mod ats1cuw;
+pub mod bpiall;
+pub mod btcmrr;
+pub mod cbar;
 pub mod ccsidr;
 pub mod clidr;
+#[cfg(any(test, arm_architecture = "v8-r", arm_profile = "a"))]
+pub mod cntfrq;
+#[cfg(any(test, arm_architecture = "v8-r", arm_profile = "a"))]
+pub mod cnthctl;
+#[cfg(any(test, arm_architecture = "v8-r", arm_profile = "a"))]
+pub mod cnthp_ctl;
+#[cfg(any(test, arm_architecture = "v8-r", arm_profile = "a"))]
+pub mod cnthp_cval;
+#[cfg(any(test, arm_architecture = "v8-r", arm_profile = "a"))]
+pub mod cnthp_tval;
+#[cfg(any(test, arm_architecture = "v8-r", arm_profile = "a"))]
+pub mod cntkctl;
+#[cfg(any(test, arm_architecture = "v8-r", arm_profile = "a"))]
+pub mod cntp_ctl;
+#[cfg(any(test, arm_architecture = "v8-r", arm_profile = "a"))]
+pub mod cntp_cval;
+#[cfg(any(test, arm_architecture = "v8-r", arm_profile = "a"))]
+pub mod cntp_tval;
+#[cfg(any(test, arm_architecture = "v8-r", arm_profile = "a"))]
+pub mod cntpct;
+#[cfg(any(test, arm_architecture = "v8-r", arm_profile = "a"))]
+pub mod cntv_ctl;
+#[cfg(any(test, arm_architecture = "v8-r", arm_profile = "a"))]
+pub mod cntv_cval;
+#[cfg(any(test, arm_architecture = "v8-r", arm_profile = "a"))]
+pub mod cntv_tval;
+#[cfg(any(test, arm_architecture = "v8-r", arm_profile = "a"))]
+pub mod cntvct;
+#[cfg(any(test, arm_architecture = "v8-r", arm_profile = "a"))]
+pub mod cntvoff;
 pub mod contextidr;
 pub mod cpacr;
 pub mod cpsr;
 pub mod csselr;
 pub mod ctr;
+pub mod dacr;
+pub mod dbgdscrint;
+pub mod dbgdtrrxint;
+pub mod dbgdtrtxint;
+pub mod dccimvac;
+pub mod dccisw;
+pub mod dccmvac;
+pub mod dccmvau;
+pub mod dcimvac;
+pub mod dcisw;
 pub mod dfar;
 pub mod dfsr;
 pub mod dlr;
@@ -22,7 +70,31 @@ pub mod drbar;
 pub mod drsr;
 pub mod dspsr;
 pub mod fcseidr;
+pub mod fpexc;
+pub mod fpscr;
+#[cfg(any(test, arm_architecture = "v8-r", arm_profile = "a"))]
+pub mod hadfsr;
+#[cfg(any(test, arm_architecture = "v8-r", arm_profile = "a"))]
+pub mod haifsr;
+#[cfg(any(test, arm_architecture = "v8-r", arm_profile = "a"))]
+pub mod hcr;
+#[cfg(any(test, arm_architecture = "v8-r", arm_profile = "a"))]
+pub mod hdfar;
+#[cfg(any(test, arm_architecture = "v8-r", arm_profile = "a"))]
+pub mod hifar;
+#[cfg(any(test, arm_architecture = "v8-r", arm_profile = "a"))]
+pub mod hpfar;
+#[cfg(any(test, arm_architecture = "v8-r", arm_profile = "a"))]
+pub mod hsr;
+#[cfg(any(test, arm_architecture = "v8-r", arm_profile = "a"))]
+pub mod hvbar;
+pub mod icc_eoir1;
+pub mod icc_iar1;
+pub mod icc_igrpen1;
 pub mod icc_pmr;
+pub mod icc_sgi1r;
+pub mod iciallu;
+pub mod icimvau;
 pub mod id_afr0;
 pub mod id_dfr0;
 pub mod id_isar0;
@@ -49,6 +121,8 @@ pub mod mair1;
 pub mod midr;
 pub mod mpidr;
 pub mod mpuir;
+#[cfg(any(test, arm_profile = "a"))]
+pub mod mvbar;
 pub mod nsacr;
 pub mod par;
 pub mod pmccfiltr;
@@ -78,12 +152,18 @@ pub mod pmxevtyper;
 pub mod revidr;
 pub mod rgnr;
 pub mod rvbar;
+pub mod scr;
 pub mod sctlr;
 pub mod tcmtr;
+pub mod tlbiall;
 pub mod tlbtr;
 pub mod tpidrprw;
 pub mod tpidruro;
 pub mod tpidrurw;
+pub mod ttbcr;
+pub mod ttbr0;
+#[cfg(any(test, arm_architecture = "v8-r", arm_profile = "a"))]
+pub mod vbar;
 pub mod vmpidr;
 pub mod vpidr;
 pub mod vsctlr;
@@ -95,13 +175,61 @@ pub use aidr::Aidr;
 pub use aifsr::Aifsr;
 pub use amair0::Amair0;
 pub use amair1::Amair1;
+pub use atcmrr::Atcmrr;
+pub use ats1cpr::Ats1cpr;
+pub use ats1cpw::Ats1cpw;
+pub use ats1cur::Ats1cur;
+pub use ats1cuw::Ats1cuw;
+pub use bpiall::Bpiall;
+pub use btcmrr::Btcmrr;
+pub use cbar::Cbar;
 pub use ccsidr::Ccsidr;
 pub use clidr::Clidr;
+#[cfg(any(test, arm_architecture = "v8-r", arm_profile = "a"))]
+pub use cntfrq::Cntfrq;
+#[cfg(any(test, arm_architecture = "v8-r", arm_profile = "a"))]
+pub use cnthctl::Cnthctl;
+#[cfg(any(test, arm_architecture = "v8-r", arm_profile = "a"))]
+pub use cnthp_ctl::CnthpCtl;
+#[cfg(any(test, arm_architecture = "v8-r", arm_profile = "a"))]
+pub use cnthp_cval::CnthpCval;
+#[cfg(any(test, arm_architecture = "v8-r", arm_profile = "a"))]
+pub use cnthp_tval::CnthpTval;
+#[cfg(any(test, arm_architecture = "v8-r", arm_profile = "a"))]
+pub use cntkctl::Cntkctl;
+#[cfg(any(test, arm_architecture = "v8-r", arm_profile = "a"))]
+pub use cntp_ctl::CntpCtl;
+#[cfg(any(test, arm_architecture = "v8-r", arm_profile = "a"))]
+pub use cntp_cval::CntpCval;
+#[cfg(any(test, arm_architecture = "v8-r", arm_profile = "a"))]
+pub use cntp_tval::CntpTval;
+#[cfg(any(test, arm_architecture = "v8-r", arm_profile = "a"))]
+pub use cntpct::CntPct;
+#[cfg(any(test, arm_architecture = "v8-r", arm_profile = "a"))]
+pub use cntv_ctl::CntvCtl;
+#[cfg(any(test, arm_architecture = "v8-r", arm_profile = "a"))]
+pub use cntv_cval::CntvCval;
+#[cfg(any(test, arm_architecture = "v8-r", arm_profile = "a"))]
+pub use cntv_tval::CntvTval;
+#[cfg(any(test, arm_architecture = "v8-r", arm_profile = "a"))]
+pub use cntvct::CntVct;
+#[cfg(any(test, arm_architecture = "v8-r", arm_profile = "a"))]
+pub use cntvoff::CntVoff;
 pub use contextidr::Contextidr;
 pub use cpacr::Cpacr;
 pub use cpsr::Cpsr;
 pub use csselr::Csselr;
 pub use ctr::Ctr;
+pub use dacr::Dacr;
+pub use dbgdscrint::Dbgdscrint;
+pub use dbgdtrrxint::Dbgdtrrxint;
+pub use dbgdtrtxint::Dbgdtrtxint;
+pub use dccimvac::Dccimvac;
+pub use dccisw::Dccisw;
+pub use dccmvac::Dccmvac;
+pub use dccmvau::Dccmvau;
+pub use dcimvac::Dcimvac;
+pub use dcisw::Dcisw;
 pub use dfar::Dfar;
 pub use dfsr::Dfsr;
 pub use dlr::Dlr;
@@ -110,7 +238,31 @@ pub use drbar::Drbar;
 pub use drsr::Drsr;
 pub use dspsr::Dspsr;
 pub use fcseidr::Fcseidr;
+pub use fpexc::Fpexc;
+pub use fpscr::Fpscr;
+#[cfg(any(test, arm_architecture = "v8-r", arm_profile = "a"))]
+pub use hadfsr::Hadfsr;
+#[cfg(any(test, arm_architecture = "v8-r", arm_profile = "a"))]
+pub use haifsr::Haifsr;
+#[cfg(any(test, arm_architecture = "v8-r", arm_profile = "a"))]
+pub use hcr::Hcr;
+#[cfg(any(test, arm_architecture = "v8-r", arm_profile = "a"))]
+pub use hdfar::Hdfar;
+#[cfg(any(test, arm_architecture = "v8-r", arm_profile = "a"))]
+pub use hifar::Hifar;
+#[cfg(any(test, arm_architecture = "v8-r", arm_profile = "a"))]
+pub use hpfar::Hpfar;
+#[cfg(any(test, arm_architecture = "v8-r", arm_profile = "a"))]
+pub use hsr::Hsr;
+#[cfg(any(test, arm_architecture = "v8-r", arm_profile = "a"))]
+pub use hvbar::Hvbar;
+pub use icc_eoir1::IccEoir1;
+pub use icc_iar1::IccIar1;
+pub use icc_igrpen1::IccIgrpen1;
 pub use icc_pmr::IccPmr;
+pub use icc_sgi1r::IccSgi1r;
+pub use iciallu::Iciallu;
+pub use icimvau::Icimvau;
 pub use id_afr0::IdAfr0;
 pub use id_dfr0::IdDfr0;
 pub use id_isar0::IdIsar0;
@@ -136,6 +288,8 @@ pub use mair1::Mair1;
 pub use midr::Midr;
 pub use mpidr::Mpidr;
 pub use mpuir::Mpuir;
+#[cfg(any(test, arm_profile = "a"))]
+pub use mvbar::Mvbar;
 pub use nsacr::Nsacr;
 pub use par::Par;
 pub use pmccfiltr::Pmccfiltr;
@@ -165,12 +319,18 @@ pub use pmxevtyper::Pmxevtyper;
 pub use revidr::Revidr;
 pub use rgnr::Rgnr;
 pub use rvbar::Rvbar;
+pub use scr::Scr;
 pub use sctlr::Sctlr;
 pub use tcmtr::Tcmtr;
+pub use tlbiall::Tlbiall;
 pub use tlbtr::Tlbtr;
 pub use tpidrprw::Tpidrprw;
 pub use tpidruro::Tpidruro;
 pub use tpidrurw::Tpidrurw;
+pub use ttbcr::Ttbcr;
+pub use ttbr0::Ttbr0;
+#[cfg(any(test, arm_architecture = "v8-r", arm_profile = "a"))]
+pub use vbar::Vbar;
 pub use vmpidr::Vmpidr;
 pub use vpidr::Vpidr;
 pub use vsctlr::Vsctlr;
@@ -182,6 +342,9 @@ pub use armv8r::*;
 
 pub use imp::*;
 
+#[cfg(all(feature = "mock", not(target_arch = "arm")))]
+pub mod mock;
+
 /// Describes a 32-bit System Register
 pub trait SysReg {
     /// Which Co-Processor (e.g. 15 for CP15) is this register in?
@@ -220,7 +383,11 @@ pub trait SysRegRead: SysReg {
                 options(nomem, nostack, preserves_flags)
             );
         }
-        #[cfg(not(target_arch = "arm"))]
+        #[cfg(all(not(target_arch = "arm"), feature = "mock"))]
+        {
+            r = mock::read::<Self>();
+        }
+        #[cfg(all(not(target_arch = "arm"), not(feature = "mock")))]
         {
             r = 0;
         }
@@ -251,6 +418,8 @@ pub trait SysRegWrite: SysReg {
                 options(nomem, nostack, preserves_flags)
             );
         }
+        #[cfg(all(not(target_arch = "arm"), feature = "mock"))]
+        mock::write::<Self>(_value);
     }
 }
 
@@ -288,7 +457,13 @@ pub trait SysRegRead64: SysReg64 {
                 options(nomem, nostack, preserves_flags)
             );
         }
-        #[cfg(not(target_arch = "arm"))]
+        #[cfg(all(not(target_arch = "arm"), feature = "mock"))]
+        {
+            let value = mock::read64::<Self>();
+            r_lo = value as u32;
+            r_hi = (value >> 32) as u32;
+        }
+        #[cfg(all(not(target_arch = "arm"), not(feature = "mock")))]
         {
             r_lo = 0;
             r_hi = 0;
@@ -321,5 +496,7 @@ pub trait SysRegWrite64: SysReg64 {
                 options(nomem, nostack, preserves_flags)
             );
         }
+        #[cfg(all(not(target_arch = "arm"), feature = "mock"))]
+        mock::write64::<Self>(_value);
     }
 }
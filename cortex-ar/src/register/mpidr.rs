@@ -3,7 +3,7 @@
 use crate::register::{SysReg, SysRegRead};
 
 /// MPIDR (*Multiprocessor Affinity Register*)
-#[derive(Debug)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub struct Mpidr(pub u32);
 impl SysReg for Mpidr {
     const CP: u32 = 15;
@@ -19,4 +19,91 @@ impl Mpidr {
     pub fn read() -> Mpidr {
         unsafe { Self(<Self as SysRegRead>::read_raw()) }
     }
+
+    /// Affinity level 0 - normally the core number within its cluster
+    pub const fn aff0(&self) -> u8 {
+        self.0 as u8
+    }
+
+    /// Affinity level 1 - normally the cluster number
+    pub const fn aff1(&self) -> u8 {
+        (self.0 >> 8) as u8
+    }
+
+    /// Affinity level 2
+    pub const fn aff2(&self) -> u8 {
+        (self.0 >> 16) as u8
+    }
+
+    /// All three affinity levels, as the bottom 24 bits of MPIDR
+    pub const fn affinity(&self) -> u32 {
+        self.0 & 0x00FF_FFFF
+    }
+
+    /// Is this a uniprocessor system (the U bit)?
+    pub const fn uniprocessor(&self) -> bool {
+        (self.0 & (1 << 30)) != 0
+    }
+
+    /// Are the cores multi-threaded (the MT bit)?
+    ///
+    /// If so, Aff0 is the thread number, and the core number moves up to Aff1.
+    pub const fn mt(&self) -> bool {
+        (self.0 & (1 << 24)) != 0
+    }
+
+    /// The number of this core within its cluster
+    ///
+    /// This is Aff0, or Aff1 if the [`MT`](Mpidr::mt) bit is set.
+    pub const fn core_index(&self) -> u8 {
+        if self.mt() {
+            self.aff1()
+        } else {
+            self.aff0()
+        }
+    }
+
+    /// The number of the cluster this core is in
+    ///
+    /// This is Aff1, or Aff2 if the [`MT`](Mpidr::mt) bit is set.
+    pub const fn cluster_index(&self) -> u8 {
+        if self.mt() {
+            self.aff2()
+        } else {
+            self.aff1()
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn cortex_a7_cluster() {
+        // Core 2 of cluster 1, in a big.LITTLE system
+        let mpidr = Mpidr(0x8000_0102);
+        assert_eq!(mpidr.aff0(), 2);
+        assert_eq!(mpidr.aff1(), 1);
+        assert_eq!(mpidr.aff2(), 0);
+        assert_eq!(mpidr.affinity(), 0x102);
+        assert!(!mpidr.uniprocessor());
+        assert!(!mpidr.mt());
+        assert_eq!(mpidr.core_index(), 2);
+        assert_eq!(mpidr.cluster_index(), 1);
+    }
+
+    #[test]
+    fn multi_threaded() {
+        let mpidr = Mpidr(0x8103_0201);
+        assert!(mpidr.mt());
+        assert_eq!(mpidr.affinity(), 0x03_0201);
+        assert_eq!(mpidr.core_index(), 2);
+        assert_eq!(mpidr.cluster_index(), 3);
+    }
+
+    #[test]
+    fn uniprocessor() {
+        assert!(Mpidr(0xC000_0000).uniprocessor());
+    }
 }
@@ -0,0 +1,61 @@
+//! Code for managing MVBAR (*Monitor Vector Base Address Register*)
+
+use crate::register::{SysReg, SysRegRead, SysRegWrite};
+
+/// MVBAR (*Monitor Vector Base Address Register*)
+///
+/// There is no `modify` method because this register holds a single 32-bit address.
+///
+/// This is only available on Armv7-A with the Security Extensions, and can only
+/// be accessed from Secure PL1 modes.
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[repr(transparent)]
+pub struct Mvbar(pub *mut u32);
+
+impl SysReg for Mvbar {
+    const CP: u32 = 15;
+    const CRN: u32 = 12;
+    const OP1: u32 = 0;
+    const CRM: u32 = 0;
+    const OP2: u32 = 1;
+}
+
+impl SysRegRead for Mvbar {}
+
+impl SysRegWrite for Mvbar {}
+
+impl Mvbar {
+    /// Read MVBAR (*Monitor Vector Base Address Register*)
+    #[inline]
+    pub fn read() -> Mvbar {
+        // Safety: Reading this register has no side-effects and is atomic
+        unsafe { Self(<Self as SysRegRead>::read_raw() as *mut u32) }
+    }
+
+    /// Write MVBAR (*Monitor Vector Base Address Register*)
+    ///
+    /// # Safety
+    ///
+    /// You must supply a correctly-aligned address of a valid Monitor
+    /// Vector Table.
+    #[inline]
+    pub unsafe fn write(value: Self) {
+        // Safety: Writing this register is atomic
+        unsafe {
+            <Self as SysRegWrite>::write_raw(value.0 as u32);
+        }
+    }
+}
+
+impl core::fmt::Debug for Mvbar {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "MVBAR {{ {:010p} }}", self.0)
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for Mvbar {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(f, "MVBAR {{ 0x{=usize:08x} }}", self.0 as usize)
+    }
+}
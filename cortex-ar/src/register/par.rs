@@ -1,8 +1,16 @@
 //! Code for managing PAR (*Physical Address Register*)
 
-use crate::register::{SysReg, SysRegRead, SysRegWrite};
+use crate::{
+    addr::PhysAddr,
+    register::{SysReg, SysRegRead, SysRegWrite},
+};
 
 /// PAR (*Physical Address Register*)
+///
+/// Holds the result of an address translation operation, like
+/// [`Ats1cpr`](crate::register::Ats1cpr). This is the 32-bit format, used with
+/// the Short-descriptor translation table format.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub struct Par(pub u32);
 impl SysReg for Par {
     const CP: u32 = 15;
@@ -33,3 +41,109 @@ impl Par {
         }
     }
 }
+
+impl Par {
+    /// Did the translation fail?
+    pub const fn fault(&self) -> bool {
+        self.0 & 1 != 0
+    }
+
+    /// The fault status, if the translation failed
+    ///
+    /// This is `{ExT, FS[4], FS[3:0]}` from the DFSR (or IFSR) the fault
+    /// would have reported.
+    pub const fn fault_status(&self) -> Option<u8> {
+        if self.fault() {
+            Some(((self.0 >> 1) & 0x3F) as u8)
+        } else {
+            None
+        }
+    }
+
+    /// Was the address mapped by a supersection?
+    pub const fn supersection(&self) -> bool {
+        !self.fault() && (self.0 & (1 << 1)) != 0
+    }
+
+    /// The physical address of the page (or section, or supersection) the
+    /// virtual address maps to, if the translation succeeded
+    ///
+    /// Add the offset of the virtual address within the page to get the full
+    /// physical address.
+    pub const fn page_addr(&self) -> Option<PhysAddr> {
+        if self.fault() {
+            None
+        } else if self.supersection() {
+            // The bits below 24 hold the top of a 40-bit address, which we
+            // can't use
+            Some(PhysAddr::new((self.0 & 0xFF00_0000) as usize))
+        } else {
+            Some(PhysAddr::new((self.0 & 0xFFFF_F000) as usize))
+        }
+    }
+
+    /// Is the memory Non-secure?
+    pub const fn non_secure(&self) -> bool {
+        !self.fault() && (self.0 & (1 << 9)) != 0
+    }
+
+    /// Is the memory Shareable?
+    pub const fn shareable(&self) -> bool {
+        !self.fault() && (self.0 & (1 << 7)) != 0
+    }
+
+    /// Is the memory Outer Shareable (as well as Shareable)?
+    pub const fn outer_shareable(&self) -> bool {
+        self.shareable() && (self.0 & (1 << 10)) == 0
+    }
+
+    /// The Inner memory attributes
+    ///
+    /// * `0b000`: Non-cacheable
+    /// * `0b001`: Strongly-ordered
+    /// * `0b011`: Device
+    /// * `0b101`: Write-Back, Write-Allocate
+    /// * `0b110`: Write-Through
+    /// * `0b111`: Write-Back, no Write-Allocate
+    pub const fn inner_attrs(&self) -> u8 {
+        ((self.0 >> 4) & 0b111) as u8
+    }
+
+    /// The Outer memory attributes
+    ///
+    /// * `0b00`: Non-cacheable
+    /// * `0b01`: Write-Back, Write-Allocate
+    /// * `0b10`: Write-Through
+    /// * `0b11`: Write-Back, no Write-Allocate
+    pub const fn outer_attrs(&self) -> u8 {
+        ((self.0 >> 2) & 0b11) as u8
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn decode_section() {
+        // Write-Back cacheable, shareable, Secure
+        let par = Par(0x8010_0000 | (1 << 7) | (0b101 << 4) | (0b01 << 2));
+        assert!(!par.fault());
+        assert_eq!(par.fault_status(), None);
+        assert_eq!(par.page_addr(), Some(PhysAddr::new(0x8010_0000)));
+        assert!(par.shareable());
+        assert!(par.outer_shareable());
+        assert!(!par.non_secure());
+        assert_eq!(par.inner_attrs(), 0b101);
+        assert_eq!(par.outer_attrs(), 0b01);
+    }
+
+    #[test]
+    fn decode_fault() {
+        // Translation fault, section
+        let par = Par((0b00101 << 1) | 1);
+        assert!(par.fault());
+        assert_eq!(par.fault_status(), Some(0b00101));
+        assert_eq!(par.page_addr(), None);
+    }
+}
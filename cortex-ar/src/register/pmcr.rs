@@ -1,9 +1,41 @@
 //! Code for managing PMCR (*Performance Monitors Control Register*)
 
+use arbitrary_int::u5;
+
 use crate::register::{SysReg, SysRegRead, SysRegWrite};
 
 /// PMCR (*Performance Monitors Control Register*)
-pub struct Pmcr(pub u32);
+#[bitbybit::bitfield(u32)]
+pub struct Pmcr {
+    /// Implementer code
+    #[bits(24..=31, r)]
+    imp: u8,
+    /// Identification code
+    #[bits(16..=23, r)]
+    idcode: u8,
+    /// The number of event counters
+    #[bits(11..=15, r)]
+    n: u5,
+    /// Disable the cycle counter when event counting is prohibited
+    #[bits(5..=5, rw)]
+    dp: bool,
+    /// Export events to an external monitoring block
+    #[bits(4..=4, rw)]
+    x: bool,
+    /// Make the cycle counter count once every 64 cycles
+    #[bits(3..=3, rw)]
+    d: bool,
+    /// Write one to reset the cycle counter to zero
+    #[bits(2..=2, rw)]
+    c: bool,
+    /// Write one to reset all the event counters to zero
+    #[bits(1..=1, rw)]
+    p: bool,
+    /// Enable all the counters
+    #[bits(0..=0, rw)]
+    e: bool,
+}
+
 impl SysReg for Pmcr {
     const CP: u32 = 15;
     const CRN: u32 = 9;
@@ -16,7 +48,7 @@ impl Pmcr {
     #[inline]
     /// Reads PMCR (*Performance Monitors Control Register*)
     pub fn read() -> Pmcr {
-        unsafe { Self(<Self as SysRegRead>::read_raw()) }
+        unsafe { Self::new_with_raw_value(<Self as SysRegRead>::read_raw()) }
     }
 }
 impl crate::register::SysRegWrite for Pmcr {}
@@ -29,7 +61,59 @@ impl Pmcr {
     /// Ensure that this value is appropriate for this register
     pub unsafe fn write(value: Self) {
         unsafe {
-            <Self as SysRegWrite>::write_raw(value.0);
+            <Self as SysRegWrite>::write_raw(value.raw_value());
         }
     }
+
+    #[inline]
+    /// Modify PMCR (*Performance Monitors Control Register*)
+    ///
+    /// # Safety
+    ///
+    /// See docs for [Self::write].
+    pub unsafe fn modify<F>(f: F)
+    where
+        F: FnOnce(&mut Self),
+    {
+        let mut value = Self::read();
+        f(&mut value);
+        unsafe {
+            Self::write(value);
+        }
+    }
+}
+
+impl core::fmt::Debug for Pmcr {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Pmcr")
+            .field("imp", &self.imp())
+            .field("idcode", &self.idcode())
+            .field("n", &self.n())
+            .field("dp", &self.dp())
+            .field("x", &self.x())
+            .field("d", &self.d())
+            .field("c", &self.c())
+            .field("p", &self.p())
+            .field("e", &self.e())
+            .finish()
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for Pmcr {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "PMCR {{ imp={=u8:#04x}, idcode={=u8:#04x}, n={=u8}, dp={=bool}, x={=bool}, d={=bool}, c={=bool}, p={=bool}, e={=bool} }}",
+            self.imp(),
+            self.idcode(),
+            self.n().value(),
+            self.dp(),
+            self.x(),
+            self.d(),
+            self.c(),
+            self.p(),
+            self.e()
+        )
+    }
 }
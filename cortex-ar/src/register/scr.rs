@@ -1,28 +1,28 @@
-//! Code for managing HCR (*Hyp Configuration Register*)
+//! Code for managing SCR (*Secure Configuration Register*)
 
 use crate::register::{SysReg, SysRegRead, SysRegWrite};
 
-/// HCR (*Hyp Configuration Register*)
-pub struct Hcr(pub u32);
-impl SysReg for Hcr {
+/// SCR (*Secure Configuration Register*)
+pub struct Scr(pub u32);
+impl SysReg for Scr {
     const CP: u32 = 15;
     const CRN: u32 = 1;
-    const OP1: u32 = 4;
+    const OP1: u32 = 0;
     const CRM: u32 = 1;
     const OP2: u32 = 0;
 }
-impl crate::register::SysRegRead for Hcr {}
-impl Hcr {
+impl crate::register::SysRegRead for Scr {}
+impl Scr {
     #[inline]
-    /// Reads HCR (*Hyp Configuration Register*)
-    pub fn read() -> Hcr {
+    /// Reads SCR (*Secure Configuration Register*)
+    pub fn read() -> Scr {
         unsafe { Self(<Self as SysRegRead>::read_raw()) }
     }
 }
-impl crate::register::SysRegWrite for Hcr {}
-impl Hcr {
+impl crate::register::SysRegWrite for Scr {}
+impl Scr {
     #[inline]
-    /// Writes HCR (*Hyp Configuration Register*)
+    /// Writes SCR (*Secure Configuration Register*)
     ///
     /// # Safety
     ///
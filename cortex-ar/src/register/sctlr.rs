@@ -3,6 +3,10 @@
 use super::{SysReg, SysRegRead, SysRegWrite};
 
 /// SCTLR (*System Control Register*)
+///
+/// Some bits only exist on some architectures, or mean different things on
+/// different architectures, so their accessors are only there when you build
+/// for an architecture which has them.
 #[bitbybit::bitfield(u32)]
 pub struct Sctlr {
     /// The bitmask for the Instruction Endianness bit
@@ -23,10 +27,7 @@ pub struct Sctlr {
     /// The bitmask for the Fast Interrupt bit
     #[bits(21..=21, rw)]
     fi: bool,
-    /// The bitmask for the Divide by Zero Fault bit
-    #[bits(18..=18, rw)]
-    dz: bool,
-    /// The bitmask for the Background Region bit
+    /// The bitmask for the Background Region bit (PMSA only)
     #[bits(17..=17, rw)]
     br: bool,
     /// The bitmask for the Round Robin bit
@@ -95,18 +96,99 @@ impl Sctlr {
     }
 }
 
+/// Generates a getter, a `with_` and a `set_` method for a single bit
+#[cfg(any(
+    test,
+    arm_profile = "a",
+    arm_architecture = "v7-r",
+    arm_architecture = "v8-r"
+))]
+macro_rules! sctlr_bit {
+    ($(#[$attr:meta])* $bit:literal, $get:ident, $with:ident, $set:ident) => {
+        $(#[$attr])*
+        pub const fn $get(&self) -> bool {
+            (self.raw_value() & (1 << $bit)) != 0
+        }
+
+        $(#[$attr])*
+        pub const fn $with(self, value: bool) -> Self {
+            Self::new_with_raw_value((self.raw_value() & !(1 << $bit)) | ((value as u32) << $bit))
+        }
+
+        $(#[$attr])*
+        pub fn $set(&mut self, value: bool) {
+            *self = self.$with(value);
+        }
+    };
+}
+
+/// The VMSA-only bits
+#[cfg(any(test, arm_profile = "a"))]
+impl Sctlr {
+    sctlr_bit!(
+        /// The Access Flag Enable bit
+        29, afe, with_afe, set_afe
+    );
+    sctlr_bit!(
+        /// The TEX Remap Enable bit
+        28, tre, with_tre, set_tre
+    );
+}
+
+/// The Armv7-only bits
+#[cfg(any(test, arm_architecture = "v7-r", arm_architecture = "v7-a"))]
+impl Sctlr {
+    sctlr_bit!(
+        /// The Interrupt Vectors Enable bit
+        ///
+        /// If set, the FIQ and IRQ vectors are implementation defined.
+        24, ve, with_ve, set_ve
+    );
+}
+
+/// The execute-never bits, which Armv7-R doesn't have
+///
+/// On Armv7-A they only exist with the Virtualization Extensions.
+#[cfg(any(test, arm_architecture = "v8-r", arm_profile = "a"))]
+impl Sctlr {
+    sctlr_bit!(
+        /// The Unprivileged Write Execute Never bit
+        20, uwxn, with_uwxn, set_uwxn
+    );
+    sctlr_bit!(
+        /// The Write Execute Never bit
+        19, wxn, with_wxn, set_wxn
+    );
+}
+
+/// The Armv7-R-only bits
+#[cfg(any(test, arm_architecture = "v7-r"))]
+impl Sctlr {
+    sctlr_bit!(
+        /// The Divide by Zero Fault bit
+        ///
+        /// If set, an `SDIV` or `UDIV` by zero raises an Undefined Instruction
+        /// exception, instead of returning zero.
+        19, dz, with_dz, set_dz
+    );
+}
+
 impl core::fmt::Debug for Sctlr {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "SCTLR {{ IE={} TE={} ", self.ie() as u8, self.te() as u8)?;
+        #[cfg(any(test, arm_profile = "a"))]
+        write!(f, "AFE={} TRE={} ", self.afe() as u8, self.tre() as u8)?;
+        write!(f, "NMFI={} EE={} ", self.nmfi() as u8, self.ee() as u8)?;
+        #[cfg(any(test, arm_architecture = "v7-r", arm_architecture = "v7-a"))]
+        write!(f, "VE={} ", self.ve() as u8)?;
+        write!(f, "U={} FI={} ", self.u() as u8, self.fi() as u8)?;
+        #[cfg(any(test, arm_architecture = "v8-r", arm_profile = "a"))]
+        write!(f, "UWXN={} WXN={} ", self.uwxn() as u8, self.wxn() as u8)?;
+        #[cfg(any(test, arm_architecture = "v7-r"))]
+        write!(f, "DZ={} ", self.dz() as u8)?;
         write!(
             f,
-            "SCTLR {{ IE={} TE={} NMFI={} EE={} U={} FI={} DZ={} BR={} RR={} V={} I={} Z={} SW={} C={} A={} M={} }}",
-            self.ie() as u8,
-            self.te() as u8,
-            self.nmfi() as u8,
-            self.ee() as u8,
-            self.u() as u8,
-            self.fi() as u8,
-            self.dz() as u8,
+            "BR={} RR={} V={} I={} Z={} SW={} C={} A={} M={} }}",
             self.br() as u8,
             self.rr() as u8,
             self.v() as u8,
@@ -123,6 +205,36 @@ impl core::fmt::Debug for Sctlr {
 #[cfg(feature = "defmt")]
 impl defmt::Format for Sctlr {
     fn format(&self, f: defmt::Formatter) {
-        defmt::write!(f, "SCTLR {{ IE={0=31..32} TE={0=30..31} NMFI={0=27..28} EE={0=25..26} U={0=22..23} FI={0=21..22} DZ={0=18..19} BR={0=17..18} RR={0=14..15} V={0=13..14} I={0=12..13} Z={0=11..12} SW={0=10..11} C={0=2..3} A={0=1..2} M={0=0..1} }}", self.raw_value())
+        let raw = self.raw_value();
+        defmt::write!(f, "SCTLR {{ IE={0=31..32} TE={0=30..31} ", raw);
+        #[cfg(any(test, arm_profile = "a"))]
+        defmt::write!(f, "AFE={0=29..30} TRE={0=28..29} ", raw);
+        defmt::write!(f, "NMFI={0=27..28} EE={0=25..26} ", raw);
+        #[cfg(any(test, arm_architecture = "v7-r", arm_architecture = "v7-a"))]
+        defmt::write!(f, "VE={0=24..25} ", raw);
+        defmt::write!(f, "U={0=22..23} FI={0=21..22} ", raw);
+        #[cfg(any(test, arm_architecture = "v8-r", arm_profile = "a"))]
+        defmt::write!(f, "UWXN={0=20..21} WXN={0=19..20} ", raw);
+        #[cfg(any(test, arm_architecture = "v7-r"))]
+        defmt::write!(f, "DZ={0=19..20} ", raw);
+        defmt::write!(f, "BR={0=17..18} RR={0=14..15} V={0=13..14} I={0=12..13} Z={0=11..12} SW={0=10..11} C={0=2..3} A={0=1..2} M={0=0..1} }}", raw)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn arch_specific_bits() {
+        let mut sctlr = Sctlr::new_with_raw_value(0);
+        sctlr.set_dz(true);
+        assert_eq!(sctlr.raw_value(), 1 << 19);
+        assert!(sctlr.wxn());
+        sctlr.set_wxn(false);
+        assert!(!sctlr.dz());
+        let sctlr = sctlr.with_afe(true).with_ve(true).with_uwxn(true);
+        assert_eq!(sctlr.raw_value(), (1 << 29) | (1 << 24) | (1 << 20));
+        assert!(sctlr.afe() && !sctlr.tre());
     }
 }
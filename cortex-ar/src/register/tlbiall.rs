@@ -0,0 +1,27 @@
+//! Code for managing TLBIALL (*TLB Invalidate All*)
+
+use crate::register::{SysReg, SysRegWrite};
+
+/// TLBIALL (*TLB Invalidate All*)
+pub struct Tlbiall(pub u32);
+impl SysReg for Tlbiall {
+    const CP: u32 = 15;
+    const CRN: u32 = 8;
+    const OP1: u32 = 0;
+    const CRM: u32 = 7;
+    const OP2: u32 = 0;
+}
+impl crate::register::SysRegWrite for Tlbiall {}
+impl Tlbiall {
+    #[inline]
+    /// Writes TLBIALL (*TLB Invalidate All*)
+    ///
+    /// # Safety
+    ///
+    /// Ensure that this value is appropriate for this register
+    pub unsafe fn write(value: Self) {
+        unsafe {
+            <Self as SysRegWrite>::write_raw(value.0);
+        }
+    }
+}
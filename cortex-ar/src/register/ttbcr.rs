@@ -0,0 +1,35 @@
+//! Code for managing TTBCR (*Translation Table Base Control Register*)
+
+use crate::register::{SysReg, SysRegRead, SysRegWrite};
+
+/// TTBCR (*Translation Table Base Control Register*)
+pub struct Ttbcr(pub u32);
+impl SysReg for Ttbcr {
+    const CP: u32 = 15;
+    const CRN: u32 = 2;
+    const OP1: u32 = 0;
+    const CRM: u32 = 0;
+    const OP2: u32 = 2;
+}
+impl crate::register::SysRegRead for Ttbcr {}
+impl Ttbcr {
+    #[inline]
+    /// Reads TTBCR (*Translation Table Base Control Register*)
+    pub fn read() -> Ttbcr {
+        unsafe { Self(<Self as SysRegRead>::read_raw()) }
+    }
+}
+impl crate::register::SysRegWrite for Ttbcr {}
+impl Ttbcr {
+    #[inline]
+    /// Writes TTBCR (*Translation Table Base Control Register*)
+    ///
+    /// # Safety
+    ///
+    /// Ensure that this value is appropriate for this register
+    pub unsafe fn write(value: Self) {
+        unsafe {
+            <Self as SysRegWrite>::write_raw(value.0);
+        }
+    }
+}
@@ -0,0 +1,35 @@
+//! Code for managing TTBR0 (*Translation Table Base Register 0*)
+
+use crate::register::{SysReg, SysRegRead, SysRegWrite};
+
+/// TTBR0 (*Translation Table Base Register 0*)
+pub struct Ttbr0(pub u32);
+impl SysReg for Ttbr0 {
+    const CP: u32 = 15;
+    const CRN: u32 = 2;
+    const OP1: u32 = 0;
+    const CRM: u32 = 0;
+    const OP2: u32 = 0;
+}
+impl crate::register::SysRegRead for Ttbr0 {}
+impl Ttbr0 {
+    #[inline]
+    /// Reads TTBR0 (*Translation Table Base Register 0*)
+    pub fn read() -> Ttbr0 {
+        unsafe { Self(<Self as SysRegRead>::read_raw()) }
+    }
+}
+impl crate::register::SysRegWrite for Ttbr0 {}
+impl Ttbr0 {
+    #[inline]
+    /// Writes TTBR0 (*Translation Table Base Register 0*)
+    ///
+    /// # Safety
+    ///
+    /// Ensure that this value is appropriate for this register
+    pub unsafe fn write(value: Self) {
+        unsafe {
+            <Self as SysRegWrite>::write_raw(value.0);
+        }
+    }
+}
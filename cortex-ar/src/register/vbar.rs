@@ -5,6 +5,8 @@ use crate::register::{SysReg, SysRegRead, SysRegWrite};
 /// VBAR (*Vector Base Address Register*)
 ///
 /// There is no `modify` method because this register holds a single 32-bit address.
+///
+/// This is available on Armv8-R, and on Armv7-A with the Security Extensions.
 #[derive(Clone, Copy, PartialEq, Eq)]
 #[repr(transparent)]
 pub struct Vbar(pub *mut u32);
@@ -33,8 +35,7 @@ impl Vbar {
     ///
     /// # Safety
     ///
-    /// You must supply a correctly-aligned address of a valid Arm Cortex-R
-    /// Vector Table.
+    /// You must supply a correctly-aligned address of a valid Vector Table.
     #[inline]
     pub unsafe fn write(value: Self) {
         // Safety: Writing this register is atomic
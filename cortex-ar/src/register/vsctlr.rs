@@ -3,6 +3,9 @@
 use crate::register::{SysReg, SysRegRead, SysRegWrite};
 
 /// VSCTLR (*Virtualization System Control Register*)
+///
+/// Holds the VMID of the virtual machine running at EL1 and EL0.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub struct Vsctlr(pub u32);
 impl SysReg for Vsctlr {
     const CP: u32 = 15;
@@ -33,3 +36,29 @@ impl Vsctlr {
         }
     }
 }
+
+impl Vsctlr {
+    /// The VMID (*Virtual Machine Identifier*), in bits 23:16
+    pub const fn vmid(&self) -> u8 {
+        (self.0 >> 16) as u8
+    }
+
+    /// A copy of this value with a different VMID
+    pub const fn with_vmid(self, vmid: u8) -> Vsctlr {
+        Vsctlr((self.0 & !0x00FF_0000) | ((vmid as u32) << 16))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn vmid() {
+        let vsctlr = Vsctlr(0x0012_0000);
+        assert_eq!(vsctlr.vmid(), 0x12);
+        let vsctlr = vsctlr.with_vmid(0xAB);
+        assert_eq!(vsctlr.0, 0x00AB_0000);
+        assert_eq!(vsctlr.vmid(), 0xAB);
+    }
+}
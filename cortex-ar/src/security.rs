@@ -0,0 +1,73 @@
+//! Access to the Non-secure copies of banked registers
+//!
+//! On a processor with the Security Extensions, many CP15 registers (like
+//! SCTLR, VBAR, TTBR0 and TTBR1) have a Secure copy and a Non-secure copy.
+//! Which one you get depends on the current security state - except in Monitor
+//! mode, which is always Secure, but where `SCR.NS` picks the copy.
+//!
+//! A secure monitor written in Rust has to set up the Non-secure copies before
+//! it starts the normal world, which is what [`with_non_secure`] is for.
+//!
+//! ```rust ignore
+//! use cortex_ar::register::{Sctlr, Ttbr0};
+//!
+//! let ns_sctlr = cortex_ar::security::with_non_secure(|| {
+//!     // Safety: the normal world hasn't started yet
+//!     unsafe { Ttbr0::write(Ttbr0(table_addr)) };
+//!     Sctlr::read()
+//! })?;
+//! ```
+
+use crate::register::{cpsr::ProcessorMode, Cpsr, Scr};
+
+/// The NS bit in SCR
+const SCR_NS: u32 = 1 << 0;
+
+/// We weren't in Monitor mode, so we couldn't select the Non-secure registers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NotMonitorMode;
+
+/// Run `f` with the Non-secure copies of the banked CP15 registers selected
+///
+/// Sets `SCR.NS` before calling `f`, and puts SCR back as it was afterwards.
+/// IRQ and FIQ are masked whilst `f` runs, because any exception taken with
+/// `SCR.NS` set would go to the Non-secure world. Memory accesses made by `f`
+/// are still Secure.
+///
+/// Returns `Err` (without calling `f`) if we're not in Monitor mode.
+pub fn with_non_secure<F, R>(f: F) -> Result<R, NotMonitorMode>
+where
+    F: FnOnce() -> R,
+{
+    let cpsr = Cpsr::read();
+    if !matches!(cpsr.mode(), Ok(ProcessorMode::Mon)) {
+        return Err(NotMonitorMode);
+    }
+    let _guard = crate::interrupt::mask();
+    crate::interrupt::disable_fiq();
+
+    let scr = Scr::read();
+    // Safety: We're in Monitor mode with interrupts masked, so only the
+    // registers `f` touches are affected
+    unsafe {
+        Scr::write(Scr(scr.0 | SCR_NS));
+    }
+    crate::asm::isb();
+
+    let result = f();
+
+    // Safety: This is the value SCR had before
+    unsafe {
+        Scr::write(scr);
+    }
+    crate::asm::isb();
+
+    // the f bit means "masked"
+    if !cpsr.f() {
+        // Safety: We're only turning FIQ back on if it was on previously
+        unsafe {
+            crate::interrupt::enable_fiq();
+        }
+    }
+    Ok(result)
+}
@@ -0,0 +1,198 @@
+//! A client for the Arm SMC Calling Convention (SMCCC)
+//!
+//! This is the low-level way to ask firmware for something: put a function ID
+//! and up to six arguments in R0-R6, execute an `smc` (or `hvc`), and read up
+//! to four results back from R0-R3. Services like [PSCI](crate::psci), the
+//! TRNG interface and chip vendors' SiP services are all built on it.
+//!
+//! Only the SMC32 calling convention is supported, because we're AArch32.
+//!
+//! See the [Arm SMC Calling Convention][smccc] specification.
+//!
+//! [smccc]: https://developer.arm.com/documentation/den0028/latest/
+
+/// Set in a function ID for a Fast Call, which is atomic from the caller's
+/// point of view. Clear for a Yielding Call, which the firmware may pre-empt.
+pub const FAST_CALL: u32 = 1 << 31;
+
+/// The value returned in R0 for a function that isn't implemented
+pub const NOT_SUPPORTED: i32 = -1;
+
+/// Function ID for SMCCC_VERSION
+const SMCCC_VERSION: u32 = 0x8000_0000;
+
+/// Function ID for SMCCC_ARCH_FEATURES
+const SMCCC_ARCH_FEATURES: u32 = 0x8000_0001;
+
+/// Which service a function belongs to, in bits 29:24 of the function ID
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Service {
+    /// Arm Architecture Calls, like SMCCC_VERSION
+    Arch,
+    /// CPU Service Calls
+    Cpu,
+    /// SiP (Silicon Provider) Service Calls, defined by your chip vendor
+    Sip,
+    /// OEM Service Calls
+    Oem,
+    /// Standard Secure Service Calls, like PSCI and TRNG
+    StandardSecure,
+    /// Standard Hypervisor Service Calls
+    StandardHypervisor,
+    /// Vendor Specific Hypervisor Service Calls
+    VendorHypervisor,
+    /// Trusted Application Calls (owning entity numbers 48 and 49)
+    TrustedApp(u8),
+    /// Trusted OS Calls (owning entity numbers 50 to 63)
+    TrustedOs(u8),
+}
+
+impl Service {
+    /// The owning entity number, for bits 29:24 of the function ID
+    pub const fn number(self) -> u8 {
+        match self {
+            Service::Arch => 0,
+            Service::Cpu => 1,
+            Service::Sip => 2,
+            Service::Oem => 3,
+            Service::StandardSecure => 4,
+            Service::StandardHypervisor => 5,
+            Service::VendorHypervisor => 6,
+            Service::TrustedApp(n) | Service::TrustedOs(n) => n,
+        }
+    }
+}
+
+/// Build an SMC32 function ID
+///
+/// `fast` selects a Fast Call rather than a Yielding Call. Only the bottom six
+/// bits of the service number are used.
+pub const fn function_id(fast: bool, service: Service, function: u16) -> u32 {
+    let fast_bit = if fast { FAST_CALL } else { 0 };
+    fast_bit | ((service.number() as u32 & 0x3F) << 24) | function as u32
+}
+
+/// The instruction used to call the firmware
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Conduit {
+    /// `smc`, to call the Secure Monitor
+    Smc,
+    /// `hvc`, to call the hypervisor
+    Hvc,
+}
+
+/// Make an SMC32 call with `smc`
+///
+/// `args` go in R1 to R6, and the results come back from R0 to R3. Functions
+/// that take fewer arguments ignore the rest, so pass zeros.
+///
+/// A Yielding Call may return early, if the firmware was interrupted - check
+/// the service's specification for what it returns when that happens.
+pub fn smccc_call(function_id: u32, args: [u32; 6]) -> [u32; 4] {
+    call(Conduit::Smc, function_id, args)
+}
+
+/// Make an SMC32 call with `hvc`
+///
+/// Like [`smccc_call`], but for firmware (or a hypervisor) that wants calls
+/// made with `hvc` rather than `smc`.
+pub fn hvc_call(function_id: u32, args: [u32; 6]) -> [u32; 4] {
+    call(Conduit::Hvc, function_id, args)
+}
+
+/// Make an SMC32 call with the given conduit
+pub fn call(conduit: Conduit, function_id: u32, args: [u32; 6]) -> [u32; 4] {
+    #[cfg(target_arch = "arm")]
+    {
+        let r0: u32;
+        let r1: u32;
+        let r2: u32;
+        let r3: u32;
+        // Safety: The firmware only changes R0-R3, and preserves R4-R7 (which
+        // is why we save R6 ourselves - LLVM won't let us mark it as used)
+        unsafe {
+            match conduit {
+                Conduit::Smc => core::arch::asm!(
+                    ".arch_extension sec",
+                    "mov {tmp}, r6",
+                    "mov r6, {arg6}",
+                    "smc #0",
+                    "mov r6, {tmp}",
+                    arg6 = in(reg) args[5],
+                    tmp = out(reg) _,
+                    inout("r0") function_id => r0,
+                    inout("r1") args[0] => r1,
+                    inout("r2") args[1] => r2,
+                    inout("r3") args[2] => r3,
+                    in("r4") args[3],
+                    in("r5") args[4],
+                    options(nostack, preserves_flags)
+                ),
+                Conduit::Hvc => core::arch::asm!(
+                    ".arch_extension virt",
+                    "mov {tmp}, r6",
+                    "mov r6, {arg6}",
+                    "hvc #0",
+                    "mov r6, {tmp}",
+                    arg6 = in(reg) args[5],
+                    tmp = out(reg) _,
+                    inout("r0") function_id => r0,
+                    inout("r1") args[0] => r1,
+                    inout("r2") args[1] => r2,
+                    inout("r3") args[2] => r3,
+                    in("r4") args[3],
+                    in("r5") args[4],
+                    options(nostack, preserves_flags)
+                ),
+            }
+        }
+        [r0, r1, r2, r3]
+    }
+    #[cfg(not(target_arch = "arm"))]
+    {
+        let _ = (conduit, function_id, args);
+        [NOT_SUPPORTED as u32, 0, 0, 0]
+    }
+}
+
+/// Ask the firmware which version of the SMC Calling Convention it implements
+///
+/// Returns the major and minor version, or `None` if the firmware only
+/// implements SMCCC 1.0 (which doesn't have this call).
+pub fn version(conduit: Conduit) -> Option<(u16, u16)> {
+    let result = call(conduit, SMCCC_VERSION, [0; 6])[0] as i32;
+    if result < 0 {
+        None
+    } else {
+        Some(((result >> 16) as u16, result as u16))
+    }
+}
+
+/// Ask the firmware whether it implements an Arm Architecture Call
+///
+/// Returns `false` if it doesn't, or if it doesn't implement SMCCC 1.1 or
+/// later. Only ask about a function in [`Service::Arch`].
+pub fn arch_features(conduit: Conduit, function_id: u32) -> bool {
+    call(conduit, SMCCC_ARCH_FEATURES, [function_id, 0, 0, 0, 0, 0])[0] as i32 >= 0
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn psci_ids() {
+        // CPU_ON and SYSTEM_RESET, from the PSCI specification
+        assert_eq!(function_id(true, Service::StandardSecure, 3), 0x8400_0003);
+        assert_eq!(function_id(true, Service::StandardSecure, 9), 0x8400_0009);
+    }
+
+    #[test]
+    fn other_ids() {
+        assert_eq!(function_id(true, Service::Arch, 0), SMCCC_VERSION);
+        assert_eq!(function_id(false, Service::Sip, 0x10), 0x0200_0010);
+        assert_eq!(function_id(true, Service::TrustedOs(63), 1), 0xBF00_0001);
+    }
+}
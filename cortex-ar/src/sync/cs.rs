@@ -0,0 +1,136 @@
+//! Synchronisation primitives built on `critical-section`
+
+use core::cell::{Cell, RefCell, UnsafeCell};
+use core::mem::MaybeUninit;
+use core::sync::atomic::{AtomicBool, Ordering};
+
+/// A value that can only be accessed inside a critical section
+///
+/// This is a `critical_section::Mutex<RefCell<T>>`, but you don't have to
+/// borrow the `RefCell` yourself.
+///
+/// ```rust ignore
+/// static COUNTER: cortex_ar::sync::Mutex<u32> = cortex_ar::sync::Mutex::new(0);
+///
+/// COUNTER.lock(|counter| *counter += 1);
+/// ```
+pub struct Mutex<T> {
+    inner: critical_section::Mutex<RefCell<T>>,
+}
+
+impl<T> Mutex<T> {
+    /// Create a new mutex, holding the given value
+    pub const fn new(value: T) -> Mutex<T> {
+        Mutex {
+            inner: critical_section::Mutex::new(RefCell::new(value)),
+        }
+    }
+
+    /// Enter a critical section and call `f` with the value
+    ///
+    /// # Panics
+    ///
+    /// Panics if `f` tries to lock this mutex again.
+    pub fn lock<F, R>(&self, f: F) -> R
+    where
+        F: FnOnce(&mut T) -> R,
+    {
+        critical_section::with(|cs| f(&mut self.inner.borrow_ref_mut(cs)))
+    }
+
+    /// Get the value back out of the mutex
+    pub fn into_inner(self) -> T {
+        self.inner.into_inner().into_inner()
+    }
+}
+
+/// A value that is initialised the first time it is accessed
+///
+/// The initialisation function runs inside a critical section, so only one
+/// caller ever runs it.
+///
+/// ```rust ignore
+/// static TABLE: cortex_ar::sync::LazyLock<[u32; 256]> =
+///     cortex_ar::sync::LazyLock::new(make_table);
+///
+/// let x = TABLE[5];
+/// ```
+pub struct LazyLock<T, F = fn() -> T> {
+    init: critical_section::Mutex<Cell<Option<F>>>,
+    value: UnsafeCell<MaybeUninit<T>>,
+    ready: AtomicBool,
+}
+
+// Safety: The value is only written once, inside a critical section, before
+// `ready` is set. After that, it is only ever shared.
+unsafe impl<T: Send + Sync, F: Send> Sync for LazyLock<T, F> {}
+
+impl<T, F> LazyLock<T, F>
+where
+    F: FnOnce() -> T,
+{
+    /// Create a new lazy value, which calls `f` to initialise itself
+    pub const fn new(f: F) -> LazyLock<T, F> {
+        LazyLock {
+            init: critical_section::Mutex::new(Cell::new(Some(f))),
+            value: UnsafeCell::new(MaybeUninit::uninit()),
+            ready: AtomicBool::new(false),
+        }
+    }
+
+    /// Get the value, initialising it if required
+    ///
+    /// # Panics
+    ///
+    /// Panics if the initialisation function tries to access this value, or if
+    /// it panicked on a previous call. With the `panic-free` feature, it spins
+    /// forever instead.
+    pub fn force(this: &LazyLock<T, F>) -> &T {
+        if !this.ready.load(Ordering::Acquire) {
+            critical_section::with(|cs| {
+                // If someone else initialised the value while we were waiting
+                // for the critical section, the function will be gone.
+                if let Some(f) = this.init.borrow(cs).take() {
+                    let value = f();
+                    // Safety: Nobody can read the value until `ready` is set
+                    unsafe {
+                        (*this.value.get()).write(value);
+                    }
+                    this.ready.store(true, Ordering::Release);
+                }
+            });
+        }
+        if !this.ready.load(Ordering::Acquire) {
+            #[cfg(not(feature = "panic-free"))]
+            panic!("LazyLock was not initialised");
+            #[cfg(feature = "panic-free")]
+            loop {
+                core::hint::spin_loop();
+            }
+        }
+        // Safety: `ready` is only set once the value has been written
+        unsafe { (*this.value.get()).assume_init_ref() }
+    }
+}
+
+impl<T, F> core::ops::Deref for LazyLock<T, F>
+where
+    F: FnOnce() -> T,
+{
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        LazyLock::force(self)
+    }
+}
+
+impl<T, F> Drop for LazyLock<T, F> {
+    fn drop(&mut self) {
+        if *self.ready.get_mut() {
+            // Safety: `ready` is only set once the value has been written
+            unsafe {
+                self.value.get_mut().assume_init_drop();
+            }
+        }
+    }
+}
@@ -0,0 +1,20 @@
+//! Blocking synchronisation primitives
+//!
+//! `Mutex` and `LazyLock` use whichever `critical-section` implementation
+//! you have selected, so they are safe to use from interrupt handlers and,
+//! with a multi-core implementation, from other cores. They need the
+//! `critical-section` feature.
+//!
+//! [`SpinMutex`] and [`RwSpinLock`] don't disable interrupts. They are for
+//! sharing data between cores, where a waiting core sleeps with `WFE` until
+//! the lock is released.
+
+#[cfg(feature = "critical-section")]
+mod cs;
+mod rwlock;
+mod spin;
+
+#[cfg(feature = "critical-section")]
+pub use cs::{LazyLock, Mutex};
+pub use rwlock::{RwSpinLock, RwSpinLockReadGuard, RwSpinLockWriteGuard};
+pub use spin::{SpinMutex, SpinMutexGuard};
@@ -0,0 +1,225 @@
+//! A reader-writer spin-lock which sleeps with `WFE` while it waits
+
+use core::cell::UnsafeCell;
+use core::sync::atomic::{AtomicU32, Ordering};
+
+use super::spin::{wait, wake};
+
+/// Set while a writer holds the lock
+const WRITER: u32 = 1 << 31;
+
+/// Set while a writer is waiting, to stop new readers starving it
+const WRITER_WAITING: u32 = 1 << 30;
+
+/// The bits that count the readers
+const READERS: u32 = WRITER_WAITING - 1;
+
+/// A reader-writer lock, for sharing data between cores
+///
+/// Any number of readers can hold the lock at once, or one writer. Once a
+/// writer is waiting, new readers wait too, so a steady stream of readers
+/// can't keep a writer out forever.
+///
+/// Like [`SpinMutex`](super::SpinMutex), waiting cores sleep with `WFE`, and
+/// interrupts are left alone - don't take the lock in an interrupt handler if
+/// the code it interrupted might be holding it.
+///
+/// ```rust ignore
+/// static ROUTES: cortex_ar::sync::RwSpinLock<[Route; 64]> =
+///     cortex_ar::sync::RwSpinLock::new([Route::EMPTY; 64]);
+///
+/// let next_hop = ROUTES.read()[dest].next_hop;
+/// ROUTES.write()[dest] = new_route;
+/// ```
+pub struct RwSpinLock<T: ?Sized> {
+    state: AtomicU32,
+    value: UnsafeCell<T>,
+}
+
+// Safety: Readers share `&T` across cores, and writers have exclusive access
+unsafe impl<T: ?Sized + Send + Sync> Sync for RwSpinLock<T> {}
+
+impl<T> RwSpinLock<T> {
+    /// Create a new, unlocked, lock holding the given value
+    pub const fn new(value: T) -> RwSpinLock<T> {
+        RwSpinLock {
+            state: AtomicU32::new(0),
+            value: UnsafeCell::new(value),
+        }
+    }
+
+    /// Get the value back out of the lock
+    pub fn into_inner(self) -> T {
+        self.value.into_inner()
+    }
+}
+
+impl<T: ?Sized> RwSpinLock<T> {
+    /// Lock for reading, waiting until no writer holds (or is waiting for)
+    /// the lock
+    pub fn read(&self) -> RwSpinLockReadGuard<'_, T> {
+        loop {
+            if let Some(guard) = self.try_read() {
+                return guard;
+            }
+            wait();
+        }
+    }
+
+    /// Lock for reading, if no writer holds (or is waiting for) the lock
+    pub fn try_read(&self) -> Option<RwSpinLockReadGuard<'_, T>> {
+        let mut state = self.state.load(Ordering::Relaxed);
+        loop {
+            if state & (WRITER | WRITER_WAITING) != 0 || state & READERS == READERS {
+                return None;
+            }
+            // If this fails, another reader got in first, so try again
+            // straight away - nobody will send us an event
+            match self.state.compare_exchange_weak(
+                state,
+                state + 1,
+                Ordering::Acquire,
+                Ordering::Relaxed,
+            ) {
+                Ok(_) => return Some(RwSpinLockReadGuard { lock: self }),
+                Err(actual) => state = actual,
+            }
+        }
+    }
+
+    /// Lock for writing, waiting until nobody else holds the lock
+    pub fn write(&self) -> RwSpinLockWriteGuard<'_, T> {
+        loop {
+            let state = self.state.load(Ordering::Relaxed);
+            if state & !WRITER_WAITING == 0 {
+                // Taking the lock clears WRITER_WAITING - any other waiting
+                // writers will set it again
+                if self
+                    .state
+                    .compare_exchange_weak(state, WRITER, Ordering::Acquire, Ordering::Relaxed)
+                    .is_ok()
+                {
+                    return RwSpinLockWriteGuard { lock: self };
+                }
+            } else {
+                self.state.fetch_or(WRITER_WAITING, Ordering::Relaxed);
+                wait();
+            }
+        }
+    }
+
+    /// Lock for writing, if nobody else holds the lock
+    pub fn try_write(&self) -> Option<RwSpinLockWriteGuard<'_, T>> {
+        let state = self.state.load(Ordering::Relaxed);
+        if state & !WRITER_WAITING != 0 {
+            return None;
+        }
+        self.state
+            .compare_exchange(state, WRITER, Ordering::Acquire, Ordering::Relaxed)
+            .ok()
+            .map(|_| RwSpinLockWriteGuard { lock: self })
+    }
+
+    /// Get the value, without locking
+    ///
+    /// This is fine because having `&mut self` means nobody else can be
+    /// holding the lock.
+    pub fn get_mut(&mut self) -> &mut T {
+        self.value.get_mut()
+    }
+}
+
+/// Gives shared access to the value in a [`RwSpinLock`], and unlocks it when
+/// dropped
+#[must_use = "the lock is released as soon as the guard is dropped"]
+pub struct RwSpinLockReadGuard<'a, T: ?Sized> {
+    lock: &'a RwSpinLock<T>,
+}
+
+impl<T: ?Sized> core::ops::Deref for RwSpinLockReadGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        // Safety: We hold a read lock, so nobody can be writing
+        unsafe { &*self.lock.value.get() }
+    }
+}
+
+impl<T: ?Sized> Drop for RwSpinLockReadGuard<'_, T> {
+    fn drop(&mut self) {
+        self.lock.state.fetch_sub(1, Ordering::Release);
+        wake();
+    }
+}
+
+/// Gives exclusive access to the value in a [`RwSpinLock`], and unlocks it
+/// when dropped
+#[must_use = "the lock is released as soon as the guard is dropped"]
+pub struct RwSpinLockWriteGuard<'a, T: ?Sized> {
+    lock: &'a RwSpinLock<T>,
+}
+
+impl<T: ?Sized> core::ops::Deref for RwSpinLockWriteGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        // Safety: We hold the write lock
+        unsafe { &*self.lock.value.get() }
+    }
+}
+
+impl<T: ?Sized> core::ops::DerefMut for RwSpinLockWriteGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        // Safety: We hold the write lock
+        unsafe { &mut *self.lock.value.get() }
+    }
+}
+
+impl<T: ?Sized> Drop for RwSpinLockWriteGuard<'_, T> {
+    fn drop(&mut self) {
+        // Leave WRITER_WAITING alone, so another writer goes next
+        self.lock.state.fetch_and(!WRITER, Ordering::Release);
+        wake();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn readers_share() {
+        let lock = RwSpinLock::new(7u32);
+        let a = lock.read();
+        let b = lock.read();
+        assert_eq!(*a + *b, 14);
+        assert!(lock.try_write().is_none());
+        drop(a);
+        assert!(lock.try_write().is_none());
+        drop(b);
+        assert!(lock.try_write().is_some());
+    }
+
+    #[test]
+    fn writer_excludes() {
+        let lock = RwSpinLock::new(7u32);
+        {
+            let mut w = lock.write();
+            *w = 8;
+            assert!(lock.try_read().is_none());
+            assert!(lock.try_write().is_none());
+        }
+        assert_eq!(*lock.read(), 8);
+        assert_eq!(lock.into_inner(), 8);
+    }
+
+    #[test]
+    fn waiting_writer_blocks_readers() {
+        let lock = RwSpinLock::new(0u32);
+        lock.state.fetch_or(WRITER_WAITING, Ordering::Relaxed);
+        assert!(lock.try_read().is_none());
+        let w = lock.write();
+        drop(w);
+        assert!(lock.try_read().is_some());
+    }
+}
@@ -0,0 +1,170 @@
+//! Spin-locks which sleep with `WFE` while they wait
+
+use core::cell::UnsafeCell;
+use core::sync::atomic::{AtomicBool, Ordering};
+
+/// Wait for another core to send an event
+///
+/// Returns early if an event has been sent since the last time we waited, so
+/// check the lock, then call this, then check again.
+#[inline]
+pub(super) fn wait() {
+    #[cfg(target_arch = "arm")]
+    crate::asm::wfe();
+    #[cfg(not(target_arch = "arm"))]
+    core::hint::spin_loop();
+}
+
+/// Wake up any cores waiting in [`wait`]
+#[inline]
+pub(super) fn wake() {
+    #[cfg(target_arch = "arm")]
+    {
+        // The other cores must be able to see our store before they wake up
+        crate::asm::dsb();
+        crate::asm::sev();
+    }
+}
+
+/// A mutual exclusion lock, for sharing data between cores
+///
+/// Waiting cores sleep with `WFE`, and unlocking sends an event with `SEV` to
+/// wake them up, so this uses less power than spinning on an atomic.
+///
+/// Interrupts are left alone, so if an interrupt handler on this core tries to
+/// take a lock that the code it interrupted is holding, it will wait forever.
+/// Use a `sync::Mutex` for data shared with interrupt handlers, or
+/// mask interrupts whilst you hold the lock.
+///
+/// ```rust ignore
+/// static SHARED: cortex_ar::sync::SpinMutex<[u32; 4]> =
+///     cortex_ar::sync::SpinMutex::new([0; 4]);
+///
+/// SHARED.lock()[1] += 1;
+/// ```
+pub struct SpinMutex<T: ?Sized> {
+    locked: AtomicBool,
+    value: UnsafeCell<T>,
+}
+
+// Safety: The value is only accessed by whoever holds the lock
+unsafe impl<T: ?Sized + Send> Sync for SpinMutex<T> {}
+
+impl<T> SpinMutex<T> {
+    /// Create a new, unlocked, mutex holding the given value
+    pub const fn new(value: T) -> SpinMutex<T> {
+        SpinMutex {
+            locked: AtomicBool::new(false),
+            value: UnsafeCell::new(value),
+        }
+    }
+
+    /// Get the value back out of the mutex
+    pub fn into_inner(self) -> T {
+        self.value.into_inner()
+    }
+}
+
+impl<T: ?Sized> SpinMutex<T> {
+    /// Lock the mutex, waiting until it is free
+    ///
+    /// The lock is released when the returned guard is dropped.
+    pub fn lock(&self) -> SpinMutexGuard<'_, T> {
+        loop {
+            if let Some(guard) = self.try_lock() {
+                return guard;
+            }
+            wait();
+        }
+    }
+
+    /// Lock the mutex, if it is free
+    pub fn try_lock(&self) -> Option<SpinMutexGuard<'_, T>> {
+        if self
+            .locked
+            .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
+            .is_ok()
+        {
+            Some(SpinMutexGuard { mutex: self })
+        } else {
+            None
+        }
+    }
+
+    /// Is someone holding the lock right now?
+    ///
+    /// Only useful as a hint, because it could change as soon as you look.
+    pub fn is_locked(&self) -> bool {
+        self.locked.load(Ordering::Relaxed)
+    }
+
+    /// Get the value, without locking
+    ///
+    /// This is fine because having `&mut self` means nobody else can be
+    /// holding the lock.
+    pub fn get_mut(&mut self) -> &mut T {
+        self.value.get_mut()
+    }
+}
+
+/// Gives access to the value in a [`SpinMutex`], and unlocks it when dropped
+///
+/// Sharing a guard gives out `&T`, so a guard is only `Sync` if `T` is:
+///
+/// ```rust compile_fail
+/// fn is_sync<T: Sync>(_: &T) {}
+///
+/// let mutex = cortex_ar::sync::SpinMutex::new(core::cell::Cell::new(0u32));
+/// let guard = mutex.lock();
+/// is_sync(&guard);
+/// ```
+#[must_use = "the mutex is unlocked as soon as the guard is dropped"]
+pub struct SpinMutexGuard<'a, T: ?Sized> {
+    mutex: &'a SpinMutex<T>,
+}
+
+// Safety: Sharing the guard only gives out `&T`. Without this, the guard would
+// be `Sync` whenever `T: Send`, because `SpinMutex<T>` is.
+unsafe impl<T: ?Sized + Sync> Sync for SpinMutexGuard<'_, T> {}
+
+impl<T: ?Sized> core::ops::Deref for SpinMutexGuard<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        // Safety: We hold the lock
+        unsafe { &*self.mutex.value.get() }
+    }
+}
+
+impl<T: ?Sized> core::ops::DerefMut for SpinMutexGuard<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        // Safety: We hold the lock
+        unsafe { &mut *self.mutex.value.get() }
+    }
+}
+
+impl<T: ?Sized> Drop for SpinMutexGuard<'_, T> {
+    fn drop(&mut self) {
+        self.mutex.locked.store(false, Ordering::Release);
+        wake();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn lock_and_unlock() {
+        let mutex = SpinMutex::new(5u32);
+        {
+            let mut guard = mutex.lock();
+            *guard += 1;
+            assert!(mutex.is_locked());
+            assert!(mutex.try_lock().is_none());
+        }
+        assert!(!mutex.is_locked());
+        assert_eq!(*mutex.try_lock().unwrap(), 6);
+        assert_eq!(mutex.into_inner(), 6);
+    }
+}
@@ -0,0 +1,238 @@
+//! Support for switching between tasks, for RTOS authors
+//!
+//! A [`TaskContext`] holds the registers a function call must preserve, plus
+//! the task's interrupt masks. [`switch_context`] saves the current task's
+//! registers and loads another task's, and [`init_stack`] sets up a context
+//! for a task that hasn't started yet.
+//!
+//! The `cortex-r-rt` and `cortex-a-rt` IRQ trampolines push the interrupted
+//! task's state onto the System mode stack and call `_irq_handler` in System
+//! mode. If you call [`switch_context`] from there, the trampoline returns
+//! into whichever task you switched to. Make sure you have finished with the
+//! interrupt controller before you switch.
+//!
+//! All tasks must run in the same processor mode.
+//!
+//! The FPU registers a function call must preserve (D8 to D15, and FPSCR) are
+//! saved too, on hard-float (`eabihf`) targets, or if the `vfp2` target
+//! feature is enabled, or with the `eabi-fpu` feature (which `cortex-r-rt`
+//! and `cortex-a-rt` turn on with their own `eabi-fpu` feature). Stable Rust
+//! doesn't let us see the `vfp*` target features, so if you turn on the FPU
+//! on a soft-float target (e.g. with `-C target-feature=+vfp3`), enable
+//! `eabi-fpu` too, or your tasks' FPU registers will be corrupted.
+//!
+//! An interrupt handler usually shouldn't switch tasks itself, because another
+//! interrupt handler may be nested inside it. Instead, call
+//! [`request_reschedule`]. With the `reschedule` feature enabled, the
+//! `cortex-r-rt` and `cortex-a-rt` IRQ trampolines check for a request just
+//! before the outermost IRQ returns, and if there is one they call
+//! `extern "C" fn _reschedule_handler()` in System mode with interrupts
+//! masked. That's where you pick the next task and call [`switch_context`] -
+//! much like the PendSV handler on an M-Profile processor.
+
+use core::sync::atomic::{AtomicBool, Ordering};
+
+/// How many cores can request a reschedule
+///
+/// Cores are identified by the bottom three bits of
+/// [`Mpidr::core_index`](crate::register::Mpidr::core_index), which the IRQ
+/// trampolines work out the same way.
+pub const MAX_CORES: usize = 8;
+
+/// Whether each core has a reschedule pending
+///
+/// The IRQ trampolines find this by name, and index it by core, so don't
+/// change its layout.
+#[export_name = "_reschedule_pending"]
+static RESCHEDULE_PENDING: [AtomicBool; MAX_CORES] = [const { AtomicBool::new(false) }; MAX_CORES];
+
+/// Ask for `_reschedule_handler` to be called, once this core has finished
+/// handling all of its interrupts
+///
+/// Calling this more than once before then only results in one call. It does
+/// nothing unless the run-time crate's `reschedule` feature is enabled.
+pub fn request_reschedule() {
+    let core = usize::from(crate::register::Mpidr::read().core_index()) % MAX_CORES;
+    RESCHEDULE_PENDING[core].store(true, Ordering::Release);
+}
+
+/// The saved state of a task that isn't running
+///
+/// This layout is used by the assembly code in [`switch_context`] - don't
+/// change it without changing that too.
+#[repr(C)]
+#[derive(Debug, Clone, Default)]
+pub struct TaskContext {
+    /// R4 to R11
+    pub regs: [u32; 8],
+    /// The stack pointer
+    pub sp: u32,
+    /// The address we return to when we switch back to this task
+    pub lr: u32,
+    /// The task's program status
+    ///
+    /// Only the I and F bits are restored by [`switch_context`].
+    pub spsr: u32,
+    /// The Floating-point Status and Control Register
+    #[cfg(any(target_abi = "eabihf", target_feature = "vfp2", feature = "eabi-fpu"))]
+    pub fpscr: u32,
+    /// D8 to D15
+    #[cfg(any(target_abi = "eabihf", target_feature = "vfp2", feature = "eabi-fpu"))]
+    pub fpu_regs: [u64; 8],
+}
+
+impl TaskContext {
+    /// Create an empty context, for a task that hasn't been saved yet
+    ///
+    /// Useful for initialising a `static`. Pass it as the `from` argument to
+    /// [`switch_context`], or fill it in with [`init_stack`].
+    pub const fn new() -> TaskContext {
+        TaskContext {
+            regs: [0; 8],
+            sp: 0,
+            lr: 0,
+            spsr: 0,
+            #[cfg(any(target_abi = "eabihf", target_feature = "vfp2", feature = "eabi-fpu"))]
+            fpscr: 0,
+            #[cfg(any(target_abi = "eabihf", target_feature = "vfp2", feature = "eabi-fpu"))]
+            fpu_regs: [0; 8],
+        }
+    }
+}
+
+/// Create the context for a task that will call `entry(arg)`, using the stack
+/// that ends at `stack_top`
+///
+/// The task starts with IRQ and FIQ enabled. The `entry` function must never
+/// return.
+pub fn init_stack(entry: extern "C" fn(usize) -> !, arg: usize, stack_top: *mut u8) -> TaskContext {
+    extern "C" {
+        fn __cortex_ar_task_start();
+    }
+    let mut context = TaskContext::default();
+    // `__cortex_ar_task_start` moves these into place
+    context.regs[0] = arg as u32;
+    context.regs[1] = entry as *const () as usize as u32;
+    // The AAPCS requires an 8-byte aligned stack
+    context.sp = (stack_top as usize & !7) as u32;
+    context.lr = __cortex_ar_task_start as *const () as usize as u32;
+    context
+}
+
+/// Save the current task's state into `from`, and switch to the task in `to`
+///
+/// Returns when some other task switches back to `from`.
+///
+/// # Safety
+///
+/// `to` must have come from [`init_stack`] or an earlier call to this
+/// function, and the stack it refers to must still be valid.
+#[inline]
+pub unsafe fn switch_context(from: &mut TaskContext, to: &TaskContext) {
+    extern "C" {
+        fn __cortex_ar_switch_context(from: *mut TaskContext, to: *const TaskContext);
+    }
+    // Safety: The caller has checked `to` is a valid task
+    unsafe {
+        __cortex_ar_switch_context(from, to);
+    }
+}
+
+#[cfg(all(
+    target_arch = "arm",
+    any(target_abi = "eabihf", target_feature = "vfp2", feature = "eabi-fpu")
+))]
+macro_rules! save_fpu {
+    () => {
+        r#"
+        vmrs    r2, fpscr
+        str     r2, [r0], #4
+        vstmia  r0, {{d8-d15}}
+        "#
+    };
+}
+
+#[cfg(all(
+    target_arch = "arm",
+    not(any(target_abi = "eabihf", target_feature = "vfp2", feature = "eabi-fpu"))
+))]
+macro_rules! save_fpu {
+    () => {
+        ""
+    };
+}
+
+#[cfg(all(
+    target_arch = "arm",
+    any(target_abi = "eabihf", target_feature = "vfp2", feature = "eabi-fpu")
+))]
+macro_rules! restore_fpu {
+    () => {
+        r#"
+        ldr     r3, [r1], #4
+        vmsr    fpscr, r3
+        vldmia  r1, {{d8-d15}}
+        "#
+    };
+}
+
+#[cfg(all(
+    target_arch = "arm",
+    not(any(target_abi = "eabihf", target_feature = "vfp2", feature = "eabi-fpu"))
+))]
+macro_rules! restore_fpu {
+    () => {
+        ""
+    };
+}
+
+#[cfg(target_arch = "arm")]
+core::arch::global_asm!(
+    r#"
+    // Work around https://github.com/rust-lang/rust/issues/127269
+    .fpu vfp3-d16
+
+    // `extern "C" fn __cortex_ar_switch_context(from: *mut TaskContext, to: *const TaskContext);`
+    .section .text.__cortex_ar_switch_context
+    .global __cortex_ar_switch_context
+    .type __cortex_ar_switch_context, %function
+    __cortex_ar_switch_context:
+        // save our state to `from`
+        stmia   r0!, {{r4-r11}}
+        str     sp, [r0], #4
+        str     lr, [r0], #4
+        mrs     r2, cpsr
+        str     r2, [r0], #4
+    "#,
+    save_fpu!(),
+    r#"
+        // load the new state from `to`
+        ldmia   r1!, {{r4-r11}}
+        ldr     sp, [r1], #4
+        ldr     lr, [r1], #4
+        ldr     r2, [r1], #4
+    "#,
+    restore_fpu!(),
+    r#"
+        // restore the I and F bits, leaving the mode alone
+        and     r2, r2, #0xC0
+        mrs     r3, cpsr
+        bic     r3, r3, #0xC0
+        orr     r3, r3, r2
+        msr     cpsr_c, r3
+        bx      lr
+    .size __cortex_ar_switch_context, . - __cortex_ar_switch_context
+
+    // The first thing a new task runs. Calls `entry(arg)`, where `arg` is in
+    // R4 and `entry` is in R5.
+    .section .text.__cortex_ar_task_start
+    .global __cortex_ar_task_start
+    .type __cortex_ar_task_start, %function
+    __cortex_ar_task_start:
+        mov     r0, r4
+        blx     r5
+        // `entry` must not return
+        b       .
+    .size __cortex_ar_task_start, . - __cortex_ar_task_start
+    "#
+);
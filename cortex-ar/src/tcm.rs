@@ -0,0 +1,205 @@
+//! Code for configuring the Tightly-Coupled Memories on Cortex-R processors
+//!
+//! The Cortex-R4 and Cortex-R5 have an ATCM and a BTCM, and the Cortex-R52
+//! adds a CTCM. How big each one is was fixed when the chip was built, but you
+//! can choose where it appears in the address space, and whether it is enabled.
+//!
+//! Moving or disabling a TCM makes whatever is in it disappear from its old
+//! address, so do this early in boot, before you've put any code or data in it
+//! (or from code which runs somewhere else).
+
+use crate::{addr::PhysAddr, register};
+
+/// Mask for the base address in a TCM region register
+const BASE_MASK: u32 = 0xFFFF_F000;
+
+/// Bit 0 enables the TCM (at EL1 and EL0, on Armv8-R)
+const ENABLE_BIT: u32 = 1 << 0;
+
+/// Bit 1 enables the TCM at EL2 (Armv8-R only)
+const ENABLE_EL2_BIT: u32 = 1 << 1;
+
+/// Ways this API can fail
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Error {
+    /// This processor doesn't have the given TCM
+    NotPresent,
+    /// The base address is not a multiple of the size of the TCM
+    Unaligned,
+}
+
+/// One of the Tightly-Coupled Memories
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Tcm {
+    /// The ATCM
+    A,
+    /// The BTCM
+    B,
+    /// The CTCM
+    #[cfg(arm_architecture = "v8-r")]
+    C,
+}
+
+/// How a TCM is currently set up
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct TcmRegion {
+    /// Where the TCM appears in the address space
+    pub base: PhysAddr,
+    /// The size of the TCM, in bytes, or zero if it isn't there
+    pub size: usize,
+    /// Is the TCM enabled (at EL1 and EL0, on Armv8-R)?
+    pub enabled: bool,
+    /// Is the TCM enabled at EL2?
+    ///
+    /// Always `false` on Armv7-R, which has no EL2.
+    pub el2_enabled: bool,
+}
+
+impl TcmRegion {
+    /// Decode the value of a TCM region register
+    pub const fn from_bits(bits: u32) -> TcmRegion {
+        // The size is log2(size in bytes) - 9, with zero meaning no TCM
+        let size_bits = (bits >> 2) & 0b11111;
+        TcmRegion {
+            base: PhysAddr::new((bits & BASE_MASK) as usize),
+            size: if size_bits == 0 { 0 } else { 512 << size_bits },
+            enabled: bits & ENABLE_BIT != 0,
+            el2_enabled: bits & ENABLE_EL2_BIT != 0,
+        }
+    }
+}
+
+impl Tcm {
+    /// Read how this TCM is set up
+    pub fn region(self) -> TcmRegion {
+        TcmRegion::from_bits(self.read_raw())
+    }
+
+    /// The size of this TCM, in bytes, or zero if it isn't there
+    pub fn size(self) -> usize {
+        self.region().size
+    }
+
+    /// Move this TCM to `base`, and enable or disable it
+    ///
+    /// The base address must be a multiple of the size of the TCM. On
+    /// Armv8-R, this only controls access at EL1 and EL0 - EL2 access is left
+    /// as it was.
+    ///
+    /// # Safety
+    ///
+    /// Whatever was at the old address (or is at the new one) disappears, so
+    /// nothing can be using it. The new address must also not clash with
+    /// anything in the memory map that you still need.
+    #[cfg(target_arch = "arm")]
+    pub unsafe fn configure(self, base: PhysAddr, enabled: bool) -> Result<(), Error> {
+        let old = self.read_raw();
+        let size = TcmRegion::from_bits(old).size;
+        if size == 0 {
+            return Err(Error::NotPresent);
+        }
+        if !base.is_aligned(size) {
+            return Err(Error::Unaligned);
+        }
+        let mut new = base.as_u32() | (old & ENABLE_EL2_BIT);
+        if enabled {
+            new |= ENABLE_BIT;
+        }
+        crate::asm::dsb();
+        // Safety: Up to the caller
+        unsafe {
+            self.write_raw(new);
+        }
+        crate::asm::dsb();
+        crate::asm::isb();
+        Ok(())
+    }
+
+    /// Enable or disable this TCM at EL2, leaving its address as it is
+    ///
+    /// # Safety
+    ///
+    /// If you disable it, nothing at EL2 can be using it.
+    #[cfg(all(target_arch = "arm", arm_architecture = "v8-r"))]
+    pub unsafe fn set_el2_enabled(self, enabled: bool) -> Result<(), Error> {
+        let old = self.read_raw();
+        if TcmRegion::from_bits(old).size == 0 {
+            return Err(Error::NotPresent);
+        }
+        let new = if enabled {
+            old | ENABLE_EL2_BIT
+        } else {
+            old & !ENABLE_EL2_BIT
+        };
+        // Safety: Up to the caller
+        unsafe {
+            self.write_raw(new);
+        }
+        crate::asm::dsb();
+        crate::asm::isb();
+        Ok(())
+    }
+
+    #[cfg(arm_architecture = "v8-r")]
+    fn read_raw(self) -> u32 {
+        match self {
+            Tcm::A => register::ImpAtcmregionr::read().0,
+            Tcm::B => register::ImpBtcmregionr::read().0,
+            Tcm::C => register::ImpCtcmregionr::read().0,
+        }
+    }
+
+    #[cfg(all(target_arch = "arm", arm_architecture = "v8-r"))]
+    unsafe fn write_raw(self, value: u32) {
+        unsafe {
+            match self {
+                Tcm::A => register::ImpAtcmregionr::write(register::ImpAtcmregionr(value)),
+                Tcm::B => register::ImpBtcmregionr::write(register::ImpBtcmregionr(value)),
+                Tcm::C => register::ImpCtcmregionr::write(register::ImpCtcmregionr(value)),
+            }
+        }
+    }
+
+    #[cfg(not(arm_architecture = "v8-r"))]
+    fn read_raw(self) -> u32 {
+        match self {
+            Tcm::A => register::Atcmrr::read().0,
+            Tcm::B => register::Btcmrr::read().0,
+        }
+    }
+
+    #[cfg(all(target_arch = "arm", not(arm_architecture = "v8-r")))]
+    unsafe fn write_raw(self, value: u32) {
+        unsafe {
+            match self {
+                Tcm::A => register::Atcmrr::write(register::Atcmrr(value)),
+                Tcm::B => register::Btcmrr::write(register::Btcmrr(value)),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn decode_region() {
+        // 64 KB at 0x0080_0000, enabled
+        let region = TcmRegion::from_bits(0x0080_0000 | (0b00111 << 2) | 1);
+        assert_eq!(region.base, PhysAddr::new(0x0080_0000));
+        assert_eq!(region.size, 64 * 1024);
+        assert!(region.enabled);
+        assert!(!region.el2_enabled);
+    }
+
+    #[test]
+    fn decode_missing() {
+        let region = TcmRegion::from_bits(0);
+        assert_eq!(region.size, 0);
+        assert!(!region.enabled);
+    }
+}
@@ -0,0 +1,80 @@
+//! Code for moving the exception vector table at run-time
+//!
+//! The start-up code in `cortex-r-rt` and `cortex-a-rt` points the vector base
+//! address registers at their `_vector_table`. If you want to use a different
+//! table (e.g. one you've copied into faster memory), use these functions
+//! rather than writing the registers yourself.
+//!
+//! Each table must be aligned to 32 bytes.
+
+use crate::{addr::VirtAddr, register};
+
+/// The alignment the vector base address registers need
+pub const VECTOR_TABLE_ALIGN: usize = 32;
+
+/// Point VBAR at a new vector table
+///
+/// This is the table used for exceptions taken to EL1 (or, on Armv7-A, to
+/// PL1 modes other than Monitor mode, in the current Security state).
+///
+/// # Panics
+///
+/// Panics if `addr` is not aligned to [`VECTOR_TABLE_ALIGN`] bytes.
+///
+/// # Safety
+///
+/// `addr` must point to a valid vector table, which stays there for as long
+/// as it is in use.
+pub unsafe fn set_vector_table(addr: VirtAddr) {
+    assert!(addr.is_aligned(VECTOR_TABLE_ALIGN));
+    // Safety: up to the caller
+    unsafe {
+        register::Vbar::write(register::Vbar(addr.as_mut_ptr()));
+    }
+    crate::asm::isb();
+}
+
+/// Point HVBAR at a new vector table
+///
+/// This is the table used for exceptions taken to EL2 (Hyp mode). You can only
+/// call this from EL2.
+///
+/// # Panics
+///
+/// Panics if `addr` is not aligned to [`VECTOR_TABLE_ALIGN`] bytes.
+///
+/// # Safety
+///
+/// `addr` must point to a valid vector table, which stays there for as long
+/// as it is in use.
+pub unsafe fn set_hyp_vector_table(addr: VirtAddr) {
+    assert!(addr.is_aligned(VECTOR_TABLE_ALIGN));
+    // Safety: up to the caller
+    unsafe {
+        register::Hvbar::write(register::Hvbar(addr.as_mut_ptr()));
+    }
+    crate::asm::isb();
+}
+
+/// Point MVBAR at a new Monitor vector table
+///
+/// This is the table used for exceptions taken to Monitor mode, such as an
+/// `SMC`. You can only call this from a Secure PL1 mode.
+///
+/// # Panics
+///
+/// Panics if `addr` is not aligned to [`VECTOR_TABLE_ALIGN`] bytes.
+///
+/// # Safety
+///
+/// `addr` must point to a valid Monitor vector table, which stays there for
+/// as long as it is in use.
+#[cfg(arm_profile = "a")]
+pub unsafe fn set_monitor_vector_table(addr: VirtAddr) {
+    assert!(addr.is_aligned(VECTOR_TABLE_ALIGN));
+    // Safety: up to the caller
+    unsafe {
+        register::Mvbar::write(register::Mvbar(addr.as_mut_ptr()));
+    }
+    crate::asm::isb();
+}
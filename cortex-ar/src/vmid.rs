@@ -0,0 +1,41 @@
+//! Code for switching between virtual machines on Armv8-R
+//!
+//! A hypervisor running at EL2 gives each of its virtual machines a VMID
+//! (*Virtual Machine Identifier*), and puts the one that's about to run into
+//! VSCTLR. There are no translation tables in the PMSA, so there's no TLB to
+//! maintain when the VMID changes, but the branch predictor must be
+//! invalidated so one virtual machine can't steer another's speculation.
+//!
+//! All of these functions must be called from EL2.
+
+use crate::register::{Bpiall, Vsctlr};
+
+/// Get the VMID of the virtual machine that runs at EL1 and EL0
+pub fn current() -> u8 {
+    Vsctlr::read().vmid()
+}
+
+/// Set the VMID of the virtual machine that runs at EL1 and EL0
+///
+/// This invalidates the branch predictor, so nothing predicted for the old
+/// virtual machine is used by the new one. Call it before you load the new
+/// virtual machine's EL1 state and return to it.
+///
+/// # Safety
+///
+/// Only call this from EL2, and not while any EL1 or EL0 code could be
+/// running on this core.
+pub unsafe fn set(vmid: u8) {
+    let vsctlr = Vsctlr::read().with_vmid(vmid);
+    // Safety: up to the caller
+    unsafe {
+        Vsctlr::write(vsctlr);
+    }
+    crate::asm::isb();
+    // Safety: this only throws away predictions
+    unsafe {
+        Bpiall::write(Bpiall(0));
+    }
+    crate::asm::dsb();
+    crate::asm::isb();
+}
@@ -8,7 +8,58 @@ use std::io::Write;
 
 fn main() {
     arm_targets::process();
-    write("link.x", include_bytes!("link.x"));
+    let mut link_x = include_str!("link.x").to_string();
+    if std::env::var_os("CARGO_FEATURE_RAM_IMAGE").is_some() {
+        // Load .data where it runs, so there's nothing to copy
+        link_x = link_x.replace("} > DATA AT>CODE", "} > DATA");
+    }
+    if std::env::var_os("CARGO_FEATURE_HIVECS").is_some() {
+        // Put the vector table where SCTLR.V=1 expects it
+        link_x = link_x.replace(
+            ".vector_table ORIGIN(VECTORS) :",
+            ".vector_table 0xFFFF0000 :",
+        );
+    }
+    if std::env::var_os("CARGO_FEATURE_STACKS_REGION").is_some() {
+        // Put all the stacks in the STACKS region, instead of the top of DATA
+        link_x = link_x
+            .replace(
+                "PROVIDE(_stack_top = ORIGIN(DATA) + LENGTH(DATA));",
+                "PROVIDE(_stack_top = ORIGIN(STACKS) + LENGTH(STACKS));",
+            )
+            .replace(
+                "PROVIDE(_stack_bottom = __sheap);",
+                "PROVIDE(_stack_bottom = ORIGIN(STACKS));",
+            )
+            .replace(
+                "PROVIDE(_heap_end = _stack_top - _hyp_stack_size - _und_stack_size - _svc_stack_size - _abt_stack_size - _irq_stack_size - _fiq_stack_size);",
+                "PROVIDE(_heap_end = ORIGIN(DATA) + LENGTH(DATA));",
+            );
+    }
+    if std::env::var_os("CARGO_FEATURE_STACKS_REGION").is_some()
+        || std::env::var_os("CARGO_FEATURE_ZERO_STACKS").is_some()
+    {
+        // Only check the stacks fit when _stack_bottom means something -
+        // otherwise _stack_top may have been moved below the heap
+        link_x.push_str(
+            "ASSERT(_stack_top - _hyp_stack_size - _und_stack_size - _svc_stack_size - _abt_stack_size - _irq_stack_size - _fiq_stack_size > _stack_bottom, \"ERROR(cortex-r-rt): the exception stacks don't fit between _stack_bottom and _stack_top\");\n",
+        );
+    }
+    if std::env::var_os("CARGO_FEATURE_NULL_TRAP").is_some() {
+        // Nothing can live in the range the MPU leaves uncovered
+        link_x.push_str(
+            "ASSERT(_null_trap_size % 64 == 0, \"ERROR(cortex-r-rt): _null_trap_size is not a multiple of 64 bytes\");\n",
+        );
+        link_x.push_str(
+            "ASSERT(ORIGIN(VECTORS) >= _null_trap_size && ORIGIN(CODE) >= _null_trap_size && ORIGIN(DATA) >= _null_trap_size, \"ERROR(cortex-r-rt): a memory region starts below _null_trap_size\");\n",
+        );
+        if std::env::var_os("CARGO_FEATURE_STACKS_REGION").is_some() {
+            link_x.push_str(
+                "ASSERT(ORIGIN(STACKS) >= _null_trap_size, \"ERROR(cortex-r-rt): the STACKS region starts below _null_trap_size\");\n",
+            );
+        }
+    }
+    write("link.x", link_x.as_bytes());
 }
 
 fn write(file: &str, contents: &[u8]) {
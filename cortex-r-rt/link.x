@@ -4,7 +4,15 @@ Basic Cortex-R linker script.
 You must supply a file called `memory.x` which defines the memory regions
 'VECTORS', 'CODE' and 'DATA'.
 
-The stack pointer(s) will be (near) the top of the DATA region by default.
+The stack pointer(s) will be (near) the top of the DATA region by default. With
+the `stacks-region` feature, they go at the top of the 'STACKS' region instead,
+which `memory.x` must then also define (e.g. as an alias for your DTCM).
+
+You can add your own output sections from `memory.x` (or any other script you
+pass to the linker) with `INSERT AFTER` or `INSERT BEFORE`, instead of replacing
+this whole script. These output sections are stable anchors for that:
+
+  .vector_table, .text, .rodata, .irq_dispatch, .data, .bss and .uninit
 
 Based upon the linker script from https://github.com/rust-embedded/cortex-m
 */
@@ -19,6 +27,7 @@ SECTIONS {
     .vector_table ORIGIN(VECTORS) : {
         /* The vector table must come first */
         *(.vector_table)
+        *(.vector_table.default)
     } > VECTORS
 
     .text : {
@@ -30,6 +39,13 @@ SECTIONS {
         *(.rodata .rodata*)
     } > CODE
 
+    .irq_dispatch : ALIGN(4) {
+        /* The table of `#[interrupt]` handlers, searched by `dispatch_interrupts` */
+        __sirq_dispatch = .;
+        KEEP(*(.irq_dispatch .irq_dispatch.*));
+        __eirq_dispatch = .;
+    } > CODE
+
     .data : ALIGN(4) {
         . = ALIGN(4);
         __sdata = .;
@@ -69,6 +85,13 @@ SECTIONS {
         __euninit = .;
     } > DATA
 
+    /*
+     * The heap (if you have one) starts after all the other RAM we use. We
+     * don't use `.` here, because a section from `INSERT AFTER .uninit` might
+     * be in some other region.
+     */
+    __sheap = ALIGN(__euninit, 8);
+
     /DISCARD/ : {
         *(.note .note*)
     }
@@ -88,26 +111,63 @@ PROVIDE(_abt_stack_size = 0x400);
 PROVIDE(_irq_stack_size = 0x400);
 PROVIDE(_fiq_stack_size = 0x400);
 
+/*
+With the `zero-stacks` feature, we fill everything from _stack_bottom up to
+_stack_top with _stack_fill_value at start-up. By default that's all the RAM
+above the heap start.
+*/
+PROVIDE(_stack_bottom = __sheap);
+PROVIDE(_stack_fill_value = 0);
+
+/*
+The heap can grow from __sheap up to _heap_end. By default that's the top of
+the SYS stack, which shares the space with it (and `_sbrk` also stops at the
+stack pointer). With the `stacks-region` feature it's the end of DATA.
+*/
+PROVIDE(_heap_end = _stack_top - _hyp_stack_size - _und_stack_size - _svc_stack_size - _abt_stack_size - _irq_stack_size - _fiq_stack_size);
+
+/*
+With the `null-trap` feature, the MPU leaves everything below _null_trap_size
+uncovered. It must be a multiple of 64 bytes.
+*/
+PROVIDE(_null_trap_size = 0x1000);
+
 ASSERT(_stack_top % 8 == 0, "ERROR(cortex-r-rt): top of stack is not 8-byte aligned");
 ASSERT(_und_stack_size % 8 == 0, "ERROR(cortex-r-rt): size of UND stack is not 8-byte aligned");
 ASSERT(_svc_stack_size % 8 == 0, "ERROR(cortex-r-rt): size of SVC stack is not 8-byte aligned");
 ASSERT(_abt_stack_size % 8 == 0, "ERROR(cortex-r-rt): size of ABT stack is not 8-byte aligned");
 ASSERT(_irq_stack_size % 8 == 0, "ERROR(cortex-r-rt): size of IRQ stack is not 8-byte aligned");
 ASSERT(_fiq_stack_size % 8 == 0, "ERROR(cortex-r-rt): size of FIQ stack is not 8-byte aligned");
+ASSERT(_stack_bottom % 4 == 0, "ERROR(cortex-r-rt): bottom of stack is not 4-byte aligned");
+ASSERT(_heap_end >= __sheap, "ERROR(cortex-r-rt): _heap_end is below the start of the heap");
 
 /* Weak aliases for ASM default handlers */
+PROVIDE(_vector_table               = _default_vector_table);
 PROVIDE(_start                      = _default_start);
 PROVIDE(_asm_undefined_handler      = _asm_default_undefined_handler);
 PROVIDE(_asm_svc_handler            = _asm_default_svc_handler);
 PROVIDE(_asm_prefetch_abort_handler = _asm_default_prefetch_abort_handler);
 PROVIDE(_asm_data_abort_handler     = _asm_default_data_abort_handler);
+PROVIDE(_asm_hvc_handler            = _asm_default_hvc_handler);
 PROVIDE(_asm_irq_handler            = _asm_default_irq_handler);
 PROVIDE(_asm_fiq_handler            = _asm_default_fiq_handler);
 
+/* Weak aliases for start-up hooks */
+PROVIDE(_post_stack_setup = _default_hook);
+PROVIDE(_post_bss_init    = _default_hook);
+PROVIDE(_post_data_init   = _default_hook);
+PROVIDE(_post_fpu_enable  = _default_hook);
+PROVIDE(_pre_kmain        = _default_hook);
+
+/* Weak alias for the reschedule handler */
+PROVIDE(_reschedule_handler = _default_hook);
+
 /* Weak aliases for C default handlers */
-PROVIDE(_undefined_handler      = _default_handler);
-PROVIDE(_svc_handler            = _default_handler);
-PROVIDE(_prefetch_abort_handler = _default_handler);
-PROVIDE(_data_abort_handler     = _default_handler);
-PROVIDE(_irq_handler            = _default_handler);
+PROVIDE(_undefined_handler      = _default_undefined_handler);
+PROVIDE(_svc_handler            = _default_svc_handler);
+PROVIDE(_prefetch_abort_handler = _default_prefetch_abort_handler);
+PROVIDE(_data_abort_handler     = _default_data_abort_handler);
+PROVIDE(_hvc_handler            = _default_hvc_handler);
+PROVIDE(_irq_handler            = _default_irq_handler);
+PROVIDE(_lockstep_error         = _default_lockstep_error);
 /* There is no default C-language FIQ handler */
@@ -0,0 +1,56 @@
+//! Information about how we booted
+//!
+//! Enable the `boot-info` feature to get this. `_default_start` fills in a
+//! [`BootInfo`] just before it calls `kmain`, and passes a reference to it as
+//! the first argument. So your `#[entry]` function can take a `&BootInfo`:
+//!
+//! ```rust ignore
+//! #[entry]
+//! fn main(info: &BootInfo) -> ! {
+//!     println!("Booted on core {} with r0 = {:#x}", info.core_id, info.args[0]);
+//!     loop {}
+//! }
+//! ```
+//!
+//! You can also get it at any time with [`boot_info`].
+//!
+//! If you supply your own `_start` instead of using `_default_start`, every
+//! field is zero.
+
+/// What `_default_start` found out as it booted
+#[repr(C)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BootInfo {
+    /// The values of r0, r1 and r2 when `_start` was called, which is how a
+    /// bootloader usually passes arguments
+    pub args: [u32; 3],
+    /// The value of CPSR when `_start` was called, which tells you which mode
+    /// we booted in
+    pub entry_cpsr: u32,
+    /// The core we booted on (`MPIDR.Aff0`)
+    pub core_id: u32,
+    /// The top of the stacks (`_stack_top`)
+    pub stack_top: u32,
+    /// The top of the SYS stack, below the stacks for all the other modes
+    pub sys_stack_top: u32,
+}
+
+/// The `BootInfo`
+///
+/// `_default_start` finds this by name, and writes it once `.bss` has been
+/// zeroed, so don't re-order the fields of [`BootInfo`].
+#[export_name = "_boot_info"]
+static mut BOOT_INFO: BootInfo = BootInfo {
+    args: [0; 3],
+    entry_cpsr: 0,
+    core_id: 0,
+    stack_top: 0,
+    sys_stack_top: 0,
+};
+
+/// Get the information `_default_start` collected as it booted
+pub fn boot_info() -> &'static BootInfo {
+    // Safety: This is only written by `_default_start`, before any Rust code
+    // runs
+    unsafe { &*core::ptr::addr_of!(BOOT_INFO) }
+}
@@ -0,0 +1,51 @@
+//! How long the start-up code took
+//!
+//! Enable the `boot-ticks` feature to get this. `_default_start` reads a
+//! counter as soon as it starts, and again just before it calls `kmain`, so
+//! you can report (and keep an eye on) how long your system takes to boot.
+//!
+//! On Armv8-R the counter is the Physical Counter (`CNTPCT`), which ticks at
+//! the frequency in `CNTFRQ`. On Armv7-R it is the PMU cycle counter
+//! (`PMCCNTR`), which counts processor cycles. We start the cycle counter
+//! for you, and leave it running. It's only 32 bits wide, so it wraps after
+//! 2^32 cycles and [`BootTicks::elapsed`] will be wrong if your boot takes
+//! longer than that.
+//!
+//! If you supply your own `_start` instead of using `_default_start`, both
+//! readings are zero.
+
+use core::sync::atomic::{AtomicU32, Ordering};
+
+/// The two readings, as low word then high word
+///
+/// `_default_start` finds this by name, and writes it once `.bss` has been
+/// zeroed, so don't re-order it.
+#[export_name = "_boot_ticks"]
+static BOOT_TICKS: [AtomicU32; 4] = [const { AtomicU32::new(0) }; 4];
+
+/// The counter readings taken by `_default_start`
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct BootTicks {
+    /// The counter when `_default_start` began
+    pub start: u64,
+    /// The counter just before `kmain` was called
+    pub end: u64,
+}
+
+impl BootTicks {
+    /// How many ticks it took to get from reset to `kmain`
+    pub fn elapsed(&self) -> u64 {
+        self.end.wrapping_sub(self.start)
+    }
+}
+
+/// Get the counter readings taken during start-up
+pub fn boot_ticks() -> BootTicks {
+    let [start_lo, start_hi, end_lo, end_hi] = BOOT_TICKS
+        .each_ref()
+        .map(|c| u64::from(c.load(Ordering::Relaxed)));
+    BootTicks {
+        start: (start_hi << 32) | start_lo,
+        end: (end_hi << 32) | end_lo,
+    }
+}
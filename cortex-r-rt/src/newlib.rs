@@ -0,0 +1,140 @@
+//! System call stubs for newlib (or newlib-nano)
+//!
+//! Enable the `newlib` feature to get these. They let you link C libraries
+//! that use newlib into your application without undefined references.
+//!
+//! * `_sbrk` hands out memory starting at `__sheap` (the end of RAM used by
+//!   `.data`, `.bss` and `.uninit`) and fails if the heap would grow past
+//!   `_heap_end`, or past the current stack pointer if the stack is in the
+//!   way.
+//! * `_write` sends `stdout` and `stderr` to the debugger using semihosting.
+//! * `_exit` exits using semihosting.
+//! * Everything else fails, or reports that there is no data.
+
+use core::ffi::{c_char, c_int, c_void};
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+/// The current end of the heap, or zero if `_sbrk` hasn't been called yet
+static HEAP_END: AtomicUsize = AtomicUsize::new(0);
+
+/// Grow (or shrink) the heap by `incr` bytes, returning the old end of the heap
+#[no_mangle]
+pub extern "C" fn _sbrk(incr: isize) -> *mut c_void {
+    extern "C" {
+        static __sheap: u8;
+        static _heap_end: u8;
+    }
+    let heap_start = core::ptr::addr_of!(__sheap) as usize;
+    let heap_end = core::ptr::addr_of!(_heap_end) as usize;
+    // By default the SYS stack grows down towards the heap, so don't let the
+    // heap run into it. With the `stacks-region` feature it's somewhere else.
+    let sp = stack_pointer();
+    let limit = if (heap_start..heap_end).contains(&sp) {
+        sp
+    } else {
+        heap_end
+    };
+    let result = HEAP_END.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |end| {
+        let end = if end == 0 { heap_start } else { end };
+        let new_end = end.checked_add_signed(incr)?;
+        if new_end > limit {
+            None
+        } else {
+            Some(new_end)
+        }
+    });
+    match result {
+        // first call - the old end was the start of the heap
+        Ok(0) => heap_start as *mut c_void,
+        Ok(old_end) => old_end as *mut c_void,
+        // newlib uses `(void*) -1` to mean 'out of memory'
+        Err(_) => usize::MAX as *mut c_void,
+    }
+}
+
+/// Write to a file descriptor. Only `stdout` (1) and `stderr` (2) work.
+///
+/// # Safety
+///
+/// `buf` must point to `len` readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn _write(fd: c_int, buf: *const c_char, len: c_int) -> c_int {
+    use semihosting::io::Write;
+
+    if len < 0 {
+        return -1;
+    }
+    // Safety: the caller promises this is OK
+    let data = unsafe { core::slice::from_raw_parts(buf as *const u8, len as usize) };
+    let result = match fd {
+        1 => semihosting::io::stdout().and_then(|mut out| out.write(data)),
+        2 => semihosting::io::stderr().and_then(|mut out| out.write(data)),
+        _ => return -1,
+    };
+    match result {
+        Ok(n) => n as c_int,
+        Err(_) => -1,
+    }
+}
+
+/// Read from a file descriptor. There is never any data.
+#[no_mangle]
+pub extern "C" fn _read(_fd: c_int, _buf: *mut c_char, _len: c_int) -> c_int {
+    0
+}
+
+/// Close a file descriptor. Always fails.
+#[no_mangle]
+pub extern "C" fn _close(_fd: c_int) -> c_int {
+    -1
+}
+
+/// Seek within a file. Does nothing.
+#[no_mangle]
+pub extern "C" fn _lseek(_fd: c_int, _offset: c_int, _whence: c_int) -> c_int {
+    0
+}
+
+/// Get information about a file. Always fails.
+#[no_mangle]
+pub extern "C" fn _fstat(_fd: c_int, _stat: *mut c_void) -> c_int {
+    -1
+}
+
+/// Is this file descriptor a terminal? They all are.
+#[no_mangle]
+pub extern "C" fn _isatty(_fd: c_int) -> c_int {
+    1
+}
+
+/// Send a signal to a process. Always fails.
+#[no_mangle]
+pub extern "C" fn _kill(_pid: c_int, _sig: c_int) -> c_int {
+    -1
+}
+
+/// Get our process ID. There's only one process.
+#[no_mangle]
+pub extern "C" fn _getpid() -> c_int {
+    1
+}
+
+/// Exit the program using semihosting
+#[no_mangle]
+pub extern "C" fn _exit(code: c_int) -> ! {
+    semihosting::process::exit(code)
+}
+
+/// Get the current stack pointer
+fn stack_pointer() -> usize {
+    let sp: usize;
+    #[cfg(target_arch = "arm")]
+    unsafe {
+        core::arch::asm!("mov {}, sp", out(reg) sp, options(nomem, nostack, preserves_flags));
+    }
+    #[cfg(not(target_arch = "arm"))]
+    {
+        sp = usize::MAX;
+    }
+    sp
+}
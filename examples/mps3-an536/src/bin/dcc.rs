@@ -0,0 +1,43 @@
+//! Debug Communications Channel example for Arm Cortex-R52
+//!
+//! Prints using the DCC instead of semihosting. Semihosting halts the core on
+//! every call, which is slow and upsets real-time code, but the DCC only
+//! needs a debugger polling it - for example with OpenOCD (`arm dcc`) or
+//! probe-rs.
+//!
+//! QEMU doesn't emulate a debugger on the other end of the DCC, so this is
+//! for real hardware only. CI builds it, but `tests.sh` doesn't run it.
+
+#![no_std]
+#![no_main]
+
+use core::fmt::Write;
+
+// pull in our start-up code
+use cortex_r_rt::entry;
+
+// pull in our library
+use mps3_an536 as _;
+
+use cortex_ar::dcc;
+
+/// The entry-point to the Rust application.
+///
+/// It is called by the start-up code in `cortex-r-rt`.
+#[entry]
+fn main() -> ! {
+    let mut out = dcc::Writer;
+    writeln!(out, "Hello, this is the Debug Communications Channel!").unwrap();
+
+    let mut count = 0u32;
+    loop {
+        writeln!(out, "count = {}", count).unwrap();
+        count = count.wrapping_add(1);
+        if let Some(word) = dcc::read_word() {
+            writeln!(out, "Debugger sent us {:#010x}", word).unwrap();
+        }
+        for _ in 0..1_000_000 {
+            cortex_ar::asm::nop();
+        }
+    }
+}
@@ -0,0 +1,209 @@
+//! Example configuring the PMSAv8-32 MPU and the caches, and catching a
+//! permission fault.
+
+#![no_std]
+#![no_main]
+
+use core::sync::atomic::{AtomicU32, Ordering};
+
+use cortex_ar::{
+    addr::PhysAddr,
+    cache::{self, DmaBuffer},
+    fault::FaultInfo,
+    pmsav8::{
+        AccessPerms, Cacheable, Config, El1Mpu, MemAttr, Region, RwAllocPolicy, Shareability,
+    },
+    register::Sctlr,
+};
+
+// pull in our start-up code
+use cortex_r_rt::{entry, exception};
+
+// pull in our library
+use mps3_an536 as _;
+
+use semihosting::println;
+
+/// Code lives here
+const QSPI: core::ops::RangeInclusive<usize> = 0x0800_0000..=0x087F_FFFF;
+
+/// Data lives here
+const DDR: core::ops::RangeInclusive<usize> = 0x2000_0000..=0x27FF_FFFF;
+
+/// The UART, timers, GIC, etc live here
+const PERIPHERALS: core::ops::RangeInclusive<usize> = 0xE000_0000..=0xFFFF_FFFF;
+
+/// The MPU makes this part of DDR read-only, so writing to it causes a fault.
+///
+/// Our data is at the bottom of DDR and our stacks are at the top, so nothing
+/// else is using it.
+const TRAP: core::ops::RangeInclusive<usize> = 0x2400_0000..=0x2400_003F;
+
+/// A buffer we do some cache maintenance on
+static mut BUFFER: DmaBuffer<128> = DmaBuffer::new([0; 128]);
+
+static FAULT_COUNT: AtomicU32 = AtomicU32::new(0);
+
+/// The entry-point to the Rust application.
+///
+/// It is called by the start-up.
+#[entry]
+fn main() -> ! {
+    println!("Hello, this is an MPU example");
+
+    let mut mpu = unsafe { El1Mpu::new() };
+    mpu.configure(&Config {
+        background_config: true,
+        regions: &[
+            // Code - read-only, cacheable
+            Region {
+                range: PhysAddr::new(*QSPI.start())..=PhysAddr::new(*QSPI.end()),
+                shareability: Shareability::NonShareable,
+                access: AccessPerms::ReadOnlyNoEL0,
+                no_exec: false,
+                mair: 0,
+                enable: true,
+            },
+            // Data, below the trap - read-write, cacheable
+            Region {
+                range: PhysAddr::new(*DDR.start())..=PhysAddr::new(*TRAP.start() - 1),
+                shareability: Shareability::NonShareable,
+                access: AccessPerms::ReadWriteNoEL0,
+                no_exec: true,
+                mair: 0,
+                enable: true,
+            },
+            // The trap - read-only
+            Region {
+                range: PhysAddr::new(*TRAP.start())..=PhysAddr::new(*TRAP.end()),
+                shareability: Shareability::NonShareable,
+                access: AccessPerms::ReadOnlyNoEL0,
+                no_exec: true,
+                mair: 0,
+                enable: true,
+            },
+            // Data, above the trap - read-write, cacheable
+            Region {
+                range: PhysAddr::new(*TRAP.end() + 1)..=PhysAddr::new(*DDR.end()),
+                shareability: Shareability::NonShareable,
+                access: AccessPerms::ReadWriteNoEL0,
+                no_exec: true,
+                mair: 0,
+                enable: true,
+            },
+            // Peripherals - device memory
+            Region {
+                range: PhysAddr::new(*PERIPHERALS.start())..=PhysAddr::new(*PERIPHERALS.end()),
+                shareability: Shareability::OuterShareable,
+                access: AccessPerms::ReadWriteNoEL0,
+                no_exec: true,
+                mair: 1,
+                enable: true,
+            },
+        ],
+        memory_attributes: &[
+            MemAttr::NormalMemory {
+                outer: Cacheable::WriteBackNonTransient(RwAllocPolicy::RW),
+                inner: Cacheable::WriteBackNonTransient(RwAllocPolicy::RW),
+            },
+            MemAttr::DeviceMemory,
+        ],
+    })
+    .unwrap();
+
+    for idx in 0..5 {
+        if let Some(region) = mpu.get_region(idx) {
+            println!(
+                "Region {}: access={:?} no_exec={} mair={} enable={}",
+                idx, region.access, region.no_exec, region.mair, region.enable
+            );
+        }
+    }
+
+    mpu.enable();
+    cortex_ar::asm::isb();
+    println!("MPU enabled");
+
+    Sctlr::modify(|w| {
+        w.set_c(true);
+        w.set_i(true);
+    });
+    cortex_ar::asm::isb();
+    println!("Caches enabled");
+    println!("Data cache line size: {}", cache::dcache_line_size());
+
+    // Safety: We're the only thing using the buffer
+    let buffer = unsafe { &mut *core::ptr::addr_of_mut!(BUFFER) };
+    buffer.fill(0xAA);
+    buffer.clean();
+    // pretend a DMA engine wrote to it
+    buffer.invalidate();
+    println!("Buffer maintenance done, first byte is {:#x}", buffer[0]);
+
+    let trap = *TRAP.start() as *mut u32;
+    println!("Reading from the trap: {}", unsafe { trap.read_volatile() });
+
+    println!("Writing to the trap...");
+    unsafe {
+        write_word(trap, 0x1234_5678);
+    }
+
+    if FAULT_COUNT.load(Ordering::Relaxed) == 1 {
+        println!("Write was blocked OK, value is {}", unsafe {
+            trap.read_volatile()
+        });
+    } else {
+        println!("Write was not blocked!");
+    }
+
+    semihosting::process::exit(0);
+}
+
+// This function is written in assembly, so we know how big the faulting
+// instruction is
+extern "C" {
+    fn write_word(addr: *mut u32, value: u32);
+}
+
+core::arch::global_asm!(
+    r#"
+    // fn write_word(addr: *mut u32, value: u32);
+    .arm
+    .global write_word
+    .type write_word, %function
+    write_word:
+        str     r1, [r0]
+        bx      lr
+    .size write_word, . - write_word
+"#
+);
+
+#[exception(Undefined)]
+fn undefined_handler(_addr: usize) -> ! {
+    panic!("unexpected undefined exception");
+}
+
+#[exception(PrefetchAbort)]
+fn prefetch_abort_handler(_addr: usize) -> ! {
+    panic!("unexpected prefetch abort");
+}
+
+#[exception(DataAbort)]
+unsafe fn data_abort_handler(info: FaultInfo) -> usize {
+    println!("data abort occurred");
+    println!("DFSR WnR: {}", info.dfsr.wnr());
+
+    if info.dfar as usize == *TRAP.start() {
+        println!("caught fault on TRAP");
+    } else {
+        println!("Bad DFAR address {:08x}", info.dfar);
+    }
+
+    if FAULT_COUNT.fetch_add(1, Ordering::Relaxed) != 0 {
+        println!("We double faulted");
+        semihosting::process::abort();
+    }
+
+    // skip the faulting instruction
+    info.addr + 4
+}
@@ -40,7 +40,8 @@ fn chip_info() {
 #[cfg(arm_architecture = "v7-r")]
 fn mpu_pmsa_v7() {
     use cortex_ar::{
-        pmsav7::{CacheablePolicy, Config, MemAttr, Mpu, Region, RegionSize},
+        addr::PhysAddr,
+        pmsav7::{AccessPerms, CacheablePolicy, Config, MemAttr, Mpu, Region, RegionSize},
         register::Mpuir,
     };
 
@@ -67,10 +68,11 @@ fn mpu_pmsa_v7() {
     mpu.configure(&Config {
         background_config: true,
         dregions: &[Region {
-            base: 0x2000_0000 as *mut u8,
+            base: PhysAddr::new(0x2000_0000),
             size: RegionSize::_16M,
             subregion_mask: 0x00,
             enabled: true,
+            access: AccessPerms::ReadWrite,
             no_exec: false,
             mem_attr: MemAttr::Cacheable {
                 inner: CacheablePolicy::WriteThroughNoWriteAllocate,
@@ -93,6 +95,7 @@ fn mpu_pmsa_v7() {
 #[cfg(arm_architecture = "v8-r")]
 fn mpu_pmsa_v8() {
     use cortex_ar::{
+        addr::PhysAddr,
         pmsav8::{
             AccessPerms, Cacheable, Config, El1Mpu, MemAttr, Region, RwAllocPolicy, Shareability,
         },
@@ -117,7 +120,7 @@ fn mpu_pmsa_v8() {
     mpu.configure(&Config {
         background_config: true,
         regions: &[Region {
-            range: 0x0000_0000 as *mut u8..=0x3FFF_FFFF as *mut u8,
+            range: PhysAddr::new(0x0000_0000)..=PhysAddr::new(0x3FFF_FFFF),
             shareability: Shareability::OuterShareable,
             access: AccessPerms::ReadWrite,
             no_exec: true,
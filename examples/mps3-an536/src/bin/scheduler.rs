@@ -0,0 +1,172 @@
+//! Pre-emptive scheduler example for Arm Cortex-R52 on an MPS3-AN536
+//!
+//! Runs two tasks in User mode, and switches between them every time the EL1
+//! Physical Timer fires. The tasks can't use semihosting from User mode, so
+//! they ask the kernel to print for them using an SVC call.
+
+#![no_std]
+#![no_main]
+
+use core::sync::atomic::{AtomicU32, AtomicUsize, Ordering};
+
+// pull in our start-up code
+use cortex_r_rt::{entry, exception, irq, ExceptionFrame};
+
+// pull in our library
+use mps3_an536 as _;
+
+use arm_gic::{gicv3::Group, IntId};
+use cortex_ar::{
+    generic_timer::{El1PhysicalTimer, GenericTimer},
+    gic::GicV3CpuInterface,
+    interrupt::InterruptController,
+    task::{self, TaskContext},
+};
+use semihosting::println;
+
+type SingleCoreGic = arm_gic::gicv3::GicV3<1>;
+
+/// Offset from PERIPHBASE for GIC Distributor
+const GICD_BASE_OFFSET: usize = 0x0000_0000usize;
+
+/// Offset from PERIPHBASE for the first GIC Redistributor
+const GICR_BASE_OFFSET: usize = 0x0010_0000usize;
+
+/// The EL1 Physical Timer is wired to PPI 14 (INTID 30)
+const TIMER_INTID: IntId = IntId::ppi(14);
+
+/// How many times should we switch tasks before we stop?
+const NUM_TICKS: u32 = 10;
+
+/// The SVC call a task makes to say it has started
+const SVC_STARTED: u32 = 1;
+
+/// How big is each task's stack, in 64-bit words
+const STACK_WORDS: usize = 1024;
+
+const NUM_TASKS: usize = 2;
+
+static mut STACKS: [[u64; STACK_WORDS]; NUM_TASKS] = [[0; STACK_WORDS]; NUM_TASKS];
+
+static mut TASKS: [TaskContext; NUM_TASKS] = [const { TaskContext::new() }; NUM_TASKS];
+
+static mut MAIN_CONTEXT: TaskContext = TaskContext::new();
+
+/// Which task is running
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+
+/// How many times has the timer fired
+static TICKS: AtomicU32 = AtomicU32::new(0);
+
+/// How much work has each task done
+static COUNTERS: [AtomicU32; NUM_TASKS] = [const { AtomicU32::new(0) }; NUM_TASKS];
+
+/// The entry-point to the Rust application.
+///
+/// It is called by the start-up code in `cortex-r-rt`.
+#[entry]
+fn main() -> ! {
+    // Get the GIC address by reading CBAR
+    let periphbase = cortex_ar::register::ImpCbar::read().periphbase();
+    let gicd_base = periphbase.wrapping_byte_add(GICD_BASE_OFFSET);
+    let gicr_base = periphbase.wrapping_byte_add(GICR_BASE_OFFSET);
+
+    // Initialise the GIC.
+    let mut gic: SingleCoreGic =
+        unsafe { SingleCoreGic::new(gicd_base.cast(), [gicr_base.cast()]) };
+    gic.setup(0);
+    SingleCoreGic::set_priority_mask(0x80);
+    gic.set_interrupt_priority(TIMER_INTID, Some(0), 0x10);
+    gic.set_group(TIMER_INTID, Some(0), Group::Group1NS);
+    gic.enable_interrupt(TIMER_INTID, Some(0), true);
+
+    // Safety: Interrupts are off, so nothing else is using the tasks
+    let tasks = unsafe { &mut *core::ptr::addr_of_mut!(TASKS) };
+    for (idx, task) in tasks.iter_mut().enumerate() {
+        let stack_top = unsafe {
+            core::ptr::addr_of_mut!(STACKS[idx])
+                .cast::<u8>()
+                .add(STACK_WORDS * 8)
+        };
+        *task = task::init_stack(task_main, idx, stack_top);
+    }
+
+    println!("Starting tasks...");
+    let mut timer = unsafe { El1PhysicalTimer::new() };
+    timer.countdown_set(timer.frequency_hz() / 100);
+    timer.interrupt_mask(false);
+    timer.enable(true);
+
+    // Start the first task. This turns interrupts on, and never comes back.
+    unsafe {
+        task::switch_context(&mut *core::ptr::addr_of_mut!(MAIN_CONTEXT), &tasks[0]);
+    }
+
+    unreachable!();
+}
+
+/// The code for each task
+extern "C" fn task_main(idx: usize) -> ! {
+    // Drop to User mode. We're in System mode, which uses the same stack.
+    unsafe {
+        core::arch::asm!("cps #0x10");
+    }
+    unsafe {
+        core::arch::asm!("svc {arg}", arg = const SVC_STARTED, in("r0") idx);
+    }
+    loop {
+        COUNTERS[idx].fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// Our SVC handler, which prints messages on behalf of the tasks
+#[exception(SupervisorCall)]
+fn svc_handler(arg: u32, frame: &mut ExceptionFrame) {
+    if arg == SVC_STARTED {
+        println!("Task {} started", frame.r0);
+    } else {
+        println!("Unknown SVC call {:#x}", arg);
+    }
+}
+
+/// Our IRQ handler, which switches tasks on every timer tick
+#[irq]
+fn irq_handler() {
+    let mut gic = GicV3CpuInterface;
+    let mut tick = false;
+    while let Some(id) = gic.acknowledge() {
+        if id == u32::from(TIMER_INTID) {
+            // Go again. This also de-asserts the interrupt.
+            let mut timer = unsafe { El1PhysicalTimer::new() };
+            timer.countdown_set(timer.frequency_hz() / 100);
+            tick = true;
+        }
+        gic.end_of_interrupt(id);
+    }
+    if !tick {
+        return;
+    }
+
+    if TICKS.fetch_add(1, Ordering::Relaxed) + 1 == NUM_TICKS {
+        println!("{} ticks done", NUM_TICKS);
+        for (idx, counter) in COUNTERS.iter().enumerate() {
+            if counter.load(Ordering::Relaxed) > 0 {
+                println!("Task {} made progress", idx);
+            } else {
+                println!("Task {} did not run!", idx);
+            }
+        }
+        semihosting::process::exit(0);
+    }
+
+    // We've finished with the interrupt controller, so we can switch
+    let from = CURRENT.load(Ordering::Relaxed);
+    let to = (from + 1) % NUM_TASKS;
+    CURRENT.store(to, Ordering::Relaxed);
+    // Safety: Only this handler touches the tasks once they have started, and
+    // it can't be interrupted
+    unsafe {
+        let tasks = core::ptr::addr_of_mut!(TASKS).cast::<TaskContext>();
+        task::switch_context(&mut *tasks.add(from), &*tasks.add(to));
+    }
+}
@@ -10,9 +10,11 @@
 #![no_std]
 #![no_main]
 
-use core::cell::{RefCell, UnsafeCell};
+use core::cell::UnsafeCell;
 use core::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 
+use cortex_ar::sync::Mutex;
+
 // pull in our start-up code
 use cortex_r_rt::entry;
 
@@ -47,8 +49,7 @@ static CORE1_BOOTED: AtomicBool = AtomicBool::new(false);
 
 static SHARED_VARIABLE: AtomicU32 = AtomicU32::new(0);
 
-static SHARED_VARIABLE_2: critical_section::Mutex<RefCell<u32>> =
-    critical_section::Mutex::new(RefCell::new(0));
+static SHARED_VARIABLE_2: Mutex<u32> = Mutex::new(0);
 
 /// How long core 0 waits for core 1
 const CORE0_WILL_WAIT: usize = 1_000_000;
@@ -95,10 +96,7 @@ fn main() -> ! {
     }
 
     for _ in 0..CS_MUTEX_LOOPS {
-        critical_section::with(|cs| {
-            let mut value_ref = SHARED_VARIABLE_2.borrow_ref_mut(cs);
-            *value_ref += 1;
-        })
+        SHARED_VARIABLE_2.lock(|value| *value += 1);
     }
 
     // let the other core finish
@@ -113,10 +111,7 @@ fn main() -> ! {
         println!("CAS test failed, got {} not 2000", total_a);
     }
 
-    let total_b = critical_section::with(|cs| {
-        let value_ref = SHARED_VARIABLE_2.borrow_ref(cs);
-        *value_ref
-    });
+    let total_b = SHARED_VARIABLE_2.lock(|value| *value);
 
     if total_b == CS_MUTEX_LOOPS * 2 {
         println!("CS Mutex test passed");
@@ -130,8 +125,8 @@ fn main() -> ! {
 /// The entry-point to the Rust application.
 ///
 /// It is called by the start-up code below, on Core 1.
-#[no_mangle]
-pub extern "C" fn kmain2() {
+#[entry(core = 1)]
+fn main_core1() {
     CORE1_BOOTED.store(true, Ordering::SeqCst);
 
     for _ in 0..CAS_LOOPS {
@@ -139,14 +134,7 @@ pub extern "C" fn kmain2() {
     }
 
     for _ in 0..CS_MUTEX_LOOPS {
-        critical_section::with(|cs| {
-            let mut value_ref = SHARED_VARIABLE_2.borrow_ref_mut(cs);
-            *value_ref += 1;
-        })
-    }
-
-    loop {
-        core::hint::spin_loop();
+        SHARED_VARIABLE_2.lock(|value| *value += 1);
     }
 }
 
@@ -0,0 +1,94 @@
+//! Peripheral interrupt example for Arm Cortex-R52 on an MPS3-AN536
+//!
+//! Routes the EL1 Physical Timer interrupt through the GIC, and handles it
+//! with an `#[interrupt]` function called by the default IRQ handler.
+
+#![no_std]
+#![no_main]
+
+use core::sync::atomic::{AtomicU32, Ordering};
+
+// pull in our start-up code
+use cortex_r_rt::{entry, interrupt};
+
+// pull in our library
+use mps3_an536 as _;
+
+use arm_gic::{gicv3::Group, IntId};
+use cortex_ar::generic_timer::{El1PhysicalTimer, GenericTimer};
+use semihosting::println;
+
+type SingleCoreGic = arm_gic::gicv3::GicV3<1>;
+
+/// Offset from PERIPHBASE for GIC Distributor
+const GICD_BASE_OFFSET: usize = 0x0000_0000usize;
+
+/// Offset from PERIPHBASE for the first GIC Redistributor
+const GICR_BASE_OFFSET: usize = 0x0010_0000usize;
+
+/// The EL1 Physical Timer is wired to PPI 14 (INTID 30)
+const TIMER_INTID: IntId = IntId::ppi(14);
+
+/// How many times should the timer fire?
+const NUM_TICKS: u32 = 5;
+
+static TICKS: AtomicU32 = AtomicU32::new(0);
+
+/// The entry-point to the Rust application.
+///
+/// It is called by the start-up code in `cortex-r-rt`.
+#[entry]
+fn main() -> ! {
+    // Get the GIC address by reading CBAR
+    let periphbase = cortex_ar::register::ImpCbar::read().periphbase();
+    let gicd_base = periphbase.wrapping_byte_add(GICD_BASE_OFFSET);
+    let gicr_base = periphbase.wrapping_byte_add(GICR_BASE_OFFSET);
+
+    // Initialise the GIC.
+    let mut gic: SingleCoreGic =
+        unsafe { SingleCoreGic::new(gicd_base.cast(), [gicr_base.cast()]) };
+    gic.setup(0);
+    SingleCoreGic::set_priority_mask(0x80);
+
+    println!("Configure timer interrupt {:?}...", TIMER_INTID);
+    gic.set_interrupt_priority(TIMER_INTID, Some(0), 0x10);
+    gic.set_group(TIMER_INTID, Some(0), Group::Group1NS);
+    gic.enable_interrupt(TIMER_INTID, Some(0), true);
+
+    println!("Starting timer...");
+    let mut timer = unsafe { El1PhysicalTimer::new() };
+    timer.countdown_set(timer.frequency_hz() / 10);
+    timer.interrupt_mask(false);
+    timer.enable(true);
+
+    unsafe {
+        cortex_ar::interrupt::enable();
+    }
+
+    while TICKS.load(Ordering::Relaxed) < NUM_TICKS {
+        cortex_ar::asm::wfi();
+    }
+
+    cortex_ar::interrupt::disable();
+    timer.enable(false);
+
+    println!("Timer interrupt test completed OK");
+
+    semihosting::process::exit(0);
+}
+
+/// Called by `dispatch_interrupts`, from the default IRQ handler
+#[interrupt(PPI = 14)]
+fn timer_tick() {
+    let tick = TICKS.fetch_add(1, Ordering::Relaxed) + 1;
+    println!("- Timer tick {}", tick);
+    // Safety: main isn't using the timer while interrupts are enabled
+    let mut timer = unsafe { El1PhysicalTimer::new() };
+    if tick < NUM_TICKS {
+        // Go again. This also de-asserts the interrupt.
+        timer.countdown_set(timer.frequency_hz() / 10);
+    } else {
+        // The interrupt is level-sensitive, so stop it firing
+        timer.interrupt_mask(true);
+    }
+}
@@ -5,7 +5,7 @@
 
 use core::sync::atomic::{AtomicU32, Ordering};
 
-use cortex_ar::register::{Dfar, Dfsr, Sctlr};
+use cortex_ar::{fault::FaultInfo, register::Sctlr};
 // pull in our start-up code
 use versatileab::rt::{entry, exception};
 
@@ -77,16 +77,15 @@ fn prefetch_abort_handler(_addr: usize) -> ! {
 }
 
 #[exception(DataAbort)]
-unsafe fn data_abort_handler(addr: usize) -> usize {
+unsafe fn data_abort_handler(info: FaultInfo) -> usize {
     println!("data abort occurred");
-    // If this is not disabled, reading DFAR will trigger an alignment fault on Armv8-R, leading
+    // If this is not disabled, printing can trigger an alignment fault on Armv8-R, leading
     // to a loop.
     disable_alignment_check();
-    let dfsr = Dfsr::read();
-    println!("DFSR (Fault Status Register): {:?}", dfsr);
-    println!("DFSR Status: {:?}", dfsr.status());
-    let dfar = Dfar::read();
+    println!("DFSR (Fault Status Register): {:?}", info.dfsr);
+    println!("DFSR Status: {:?}", info.dfsr.status());
     enable_alignment_check();
+    let addr = info.addr;
 
     // note the fault isn't at the start of the function
     let expect_fault_at = unaligned_from_t32 as usize + 5;
@@ -102,12 +101,12 @@ unsafe fn data_abort_handler(addr: usize) -> usize {
 
     let expect_fault_from = core::ptr::addr_of!(COUNTER) as usize + 1;
 
-    if dfar.0 as usize == expect_fault_from {
+    if info.dfar as usize == expect_fault_from {
         println!("caught fault on COUNTER");
     } else {
         println!(
             "Bad DFAR address {:08x} is not {:08x}",
-            dfar.0, expect_fault_from
+            info.dfar, expect_fault_from
         );
     }
 
@@ -0,0 +1,204 @@
+//! Example setting up the MMU and caches on Armv7-A
+//!
+//! We build a short-descriptor L1 translation table, with 1 MB sections that:
+//!
+//! * map SDRAM as Normal, cacheable memory at its physical address
+//! * map the peripherals as Device memory
+//! * map SDRAM a second time, at a different virtual address
+//!
+//! Then we check we can see the same data through both virtual addresses, and
+//! that touching an unmapped address gives a translation fault.
+//!
+//! QEMU doesn't model caches, so we can't see the difference between Device
+//! and Normal memory by timing accesses. Instead we read the section entries
+//! back out of the table.
+//!
+//! Armv7-R has no MMU, so on those targets this example does nothing.
+
+#![no_std]
+#![no_main]
+
+// pull in our start-up code
+use versatileab::rt::entry;
+
+/// The entry-point to the Rust application.
+///
+/// It is called by the start-up.
+#[entry]
+fn main() -> ! {
+    #[cfg(arm_profile = "a")]
+    mmu::run();
+    #[cfg(not(arm_profile = "a"))]
+    semihosting::println!("This target has no MMU");
+    semihosting::process::exit(0);
+}
+
+#[cfg(arm_profile = "a")]
+mod mmu {
+    use core::sync::atomic::{AtomicU32, Ordering};
+
+    use cortex_ar::{
+        addr::PhysAddr,
+        fault::FaultInfo,
+        mmu::{AccessPermissions, L1Section, MemoryRegionAttributes, SectionAttributes},
+        register::{Dacr, Sctlr, Tlbiall, Ttbcr, Ttbr0},
+    };
+    use versatileab::rt::exception;
+
+    use semihosting::println;
+
+    /// Each section is 1 MB
+    const SECTION_SIZE: usize = 1024 * 1024;
+
+    /// SDRAM is at the bottom of the address space
+    const SDRAM: core::ops::Range<usize> = 0x0000_0000..0x0800_0000;
+
+    /// The peripherals (UARTs, timers, interrupt controllers, etc) are here
+    const PERIPHERALS: core::ops::Range<usize> = 0x1000_0000..0x1020_0000;
+
+    /// We map SDRAM a second time, here
+    const ALIAS_BASE: usize = 0x4000_0000;
+
+    /// Nothing is mapped here
+    const UNMAPPED: usize = 0x9000_0000;
+
+    /// Normal memory, write-back cacheable
+    const NORMAL: SectionAttributes = SectionAttributes {
+        non_global: false,
+        p_bit: false,
+        shareable: false,
+        access: AccessPermissions::FullAccess,
+        memory_attrs: MemoryRegionAttributes::OuterAndInnerWriteBackWriteAlloc.as_raw(),
+        domain: 0,
+        execute_never: false,
+    };
+
+    /// Device memory, which you can't execute from
+    const DEVICE: SectionAttributes = SectionAttributes {
+        non_global: false,
+        p_bit: false,
+        shareable: true,
+        access: AccessPermissions::FullAccess,
+        memory_attrs: MemoryRegionAttributes::ShareableDevice.as_raw(),
+        domain: 0,
+        execute_never: true,
+    };
+
+    /// An L1 translation table, covering all 4 GB of address space
+    ///
+    /// Must be aligned to 16 KB.
+    #[repr(C, align(16384))]
+    struct L1Table([u32; 4096]);
+
+    static mut L1_TABLE: L1Table = L1Table([0; 4096]);
+
+    /// Some data we look at through both mappings
+    static MAGIC: AtomicU32 = AtomicU32::new(0xC0FF_EE00);
+
+    static FAULT_COUNT: AtomicU32 = AtomicU32::new(0);
+
+    pub fn run() {
+        println!("Hello, this is an MMU example");
+
+        // Safety: The MMU is off, so nothing else is looking at the table
+        let table = unsafe { &mut *core::ptr::addr_of_mut!(L1_TABLE) };
+        for addr in SDRAM.step_by(SECTION_SIZE) {
+            table.0[addr / SECTION_SIZE] = L1Section::new(PhysAddr::new(addr), NORMAL).raw_value();
+            table.0[(ALIAS_BASE + addr) / SECTION_SIZE] =
+                L1Section::new(PhysAddr::new(addr), NORMAL).raw_value();
+        }
+        for addr in PERIPHERALS.step_by(SECTION_SIZE) {
+            table.0[addr / SECTION_SIZE] = L1Section::new(PhysAddr::new(addr), DEVICE).raw_value();
+        }
+
+        println!(
+            "SDRAM: {:?}",
+            L1Section::new_with_raw_value(table.0[SDRAM.start / SECTION_SIZE])
+        );
+        println!(
+            "Peripherals: {:?}",
+            L1Section::new_with_raw_value(table.0[PERIPHERALS.start / SECTION_SIZE])
+        );
+
+        // Safety: The table maps all the code and data we use at its current
+        // address
+        unsafe {
+            // Domain 0 is a client, so the access permissions are checked
+            Dacr::write(Dacr(0b01));
+            // Only use TTBR0
+            Ttbcr::write(Ttbcr(0));
+            Ttbr0::write(Ttbr0(table.0.as_ptr() as u32));
+            Tlbiall::write(Tlbiall(0));
+        }
+        cortex_ar::asm::dsb();
+        cortex_ar::asm::isb();
+
+        Sctlr::modify(|w| {
+            w.set_m(true);
+            w.set_c(true);
+            w.set_i(true);
+        });
+        cortex_ar::asm::isb();
+        println!("MMU and caches enabled");
+
+        let magic_addr = core::ptr::addr_of!(MAGIC) as usize;
+        let alias = (ALIAS_BASE + magic_addr) as *const u32;
+        MAGIC.store(0x1234_5678, Ordering::Relaxed);
+        // Safety: This address is mapped to the same place as MAGIC
+        let value = unsafe { alias.read_volatile() };
+        if value == MAGIC.load(Ordering::Relaxed) {
+            println!("Read {:#x} through the alias OK", value);
+        } else {
+            println!("Read {:#x} through the alias, which is wrong!", value);
+        }
+
+        println!("Reading an unmapped address...");
+        unsafe {
+            read_word(UNMAPPED as *const u32);
+        }
+        if FAULT_COUNT.load(Ordering::Relaxed) == 1 {
+            println!("Recovered from fault OK!");
+        } else {
+            println!("Read did not fault!");
+        }
+    }
+
+    // This function is written in assembly, so we know how big the faulting
+    // instruction is
+    extern "C" {
+        fn read_word(addr: *const u32) -> u32;
+    }
+
+    core::arch::global_asm!(
+        r#"
+        // fn read_word(addr: *const u32) -> u32;
+        .arm
+        .global read_word
+        .type read_word, %function
+        read_word:
+            ldr     r0, [r0]
+            bx      lr
+        .size read_word, . - read_word
+    "#
+    );
+
+    #[exception(DataAbort)]
+    unsafe fn data_abort_handler(info: FaultInfo) -> usize {
+        println!("data abort occurred");
+        println!("DFSR Status: {:?}", info.dfsr.status());
+
+        if info.dfar as usize == UNMAPPED {
+            println!("caught fault on the unmapped address");
+        } else {
+            println!("Bad DFAR address {:08x}", info.dfar);
+        }
+
+        if FAULT_COUNT.fetch_add(1, Ordering::Relaxed) != 0 {
+            println!("We double faulted");
+            semihosting::process::abort();
+        }
+
+        // skip the faulting instruction
+        info.addr + 4
+    }
+}
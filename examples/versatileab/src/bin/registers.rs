@@ -29,7 +29,8 @@ fn chip_info() {
 #[cfg(arm_architecture = "v7-r")]
 fn mpu_pmsa_v7() {
     use cortex_ar::{
-        pmsav7::{CacheablePolicy, Config, MemAttr, Mpu, Region, RegionSize},
+        addr::PhysAddr,
+        pmsav7::{AccessPerms, CacheablePolicy, Config, MemAttr, Mpu, Region, RegionSize},
         register::Mpuir,
     };
 
@@ -56,10 +57,11 @@ fn mpu_pmsa_v7() {
     mpu.configure(&Config {
         background_config: true,
         dregions: &[Region {
-            base: 0x2000_0000 as *mut u8,
+            base: PhysAddr::new(0x2000_0000),
             size: RegionSize::_16M,
             subregion_mask: 0x00,
             enabled: true,
+            access: AccessPerms::ReadWrite,
             no_exec: false,
             mem_attr: MemAttr::Cacheable {
                 inner: CacheablePolicy::WriteThroughNoWriteAllocate,
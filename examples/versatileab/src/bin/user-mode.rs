@@ -0,0 +1,136 @@
+//! Example running code in User mode.
+//!
+//! Code in User mode can't use semihosting, so it asks our SVC handler to
+//! print for it. Then it tries to read a privileged register, which causes an
+//! Undefined exception.
+
+#![no_std]
+#![no_main]
+
+use cortex_ar::register::{cpsr::ProcessorMode, Cpsr};
+use semihosting::{print, println};
+
+// pull in our start-up code
+use versatileab::rt::{entry, exception, ExceptionFrame};
+
+/// The SVC call for writing a string
+const SYS_WRITE: u32 = 1;
+
+/// The SVC call for exiting
+const SYS_EXIT: u32 = 2;
+
+/// The entry-point to the Rust application.
+///
+/// It is called by the start-up.
+#[entry]
+fn main() -> ! {
+    println!("Hello, this is a User mode example");
+    println!("Mode is {:?}", Cpsr::read().mode());
+
+    // Drop to User mode. We're in System mode, which uses the same stack.
+    unsafe {
+        core::arch::asm!("cps #0x10");
+    }
+
+    sys_write("Hello from User mode\n");
+    if matches!(Cpsr::read().mode(), Ok(ProcessorMode::Usr)) {
+        sys_write("Mode is Usr\n");
+    }
+
+    sys_write("Reading SCTLR...\n");
+    let sctlr = unsafe { read_sctlr_from_a32() };
+    if sctlr == 0 {
+        sys_write("Read was blocked OK\n");
+    } else {
+        sys_write("Read was not blocked!\n");
+    }
+
+    sys_exit();
+}
+
+/// Ask the SVC handler to print a string
+fn sys_write(s: &str) -> usize {
+    let written: usize;
+    unsafe {
+        core::arch::asm!(
+            "svc {arg}",
+            arg = const SYS_WRITE,
+            inout("r0") s.as_ptr() => written,
+            in("r1") s.len(),
+        );
+    }
+    written
+}
+
+/// Ask the SVC handler to exit
+fn sys_exit() -> ! {
+    unsafe {
+        core::arch::asm!("svc {arg}", arg = const SYS_EXIT, options(noreturn));
+    }
+}
+
+// These functions are written in assembly
+extern "C" {
+    fn read_sctlr_from_a32() -> u32;
+}
+
+core::arch::global_asm!(
+    r#"
+    // fn read_sctlr_from_a32() -> u32;
+    .arm
+    .global read_sctlr_from_a32
+    .type read_sctlr_from_a32, %function
+    read_sctlr_from_a32:
+        mov     r0, #0
+        mrc     p15, 0, r0, c1, c0, 0
+        bx      lr
+    .size read_sctlr_from_a32, . - read_sctlr_from_a32
+"#
+);
+
+/// This is our SVC exception handler, which runs in Supervisor mode
+#[exception(SupervisorCall)]
+fn svc_handler(arg: u32, frame: &mut ExceptionFrame) {
+    match arg {
+        SYS_WRITE => {
+            // Safety: sys_write passed us a valid string
+            let s = unsafe {
+                let bytes = core::slice::from_raw_parts(frame.r0 as *const u8, frame.r1 as usize);
+                core::str::from_utf8_unchecked(bytes)
+            };
+            print!("{}", s);
+            frame.r0 = frame.r1;
+        }
+        SYS_EXIT => {
+            semihosting::process::exit(0);
+        }
+        _ => {
+            panic!("unknown SVC call {:#x}", arg);
+        }
+    }
+}
+
+#[exception(Undefined)]
+unsafe fn undefined_handler(addr: usize, frame: &mut ExceptionFrame) -> usize {
+    println!("undefined abort occurred");
+    println!("Mode was {:?}", Cpsr::new_with_raw_value(frame.spsr).mode());
+
+    if addr == read_sctlr_from_a32 as usize + 4 {
+        println!("caught read_sctlr_from_a32");
+    } else {
+        println!("Bad fault address {:08x}", addr);
+    }
+
+    // skip the instruction
+    addr + 4
+}
+
+#[exception(PrefetchAbort)]
+fn prefetch_abort_handler(_addr: usize) -> ! {
+    panic!("unexpected prefetch abort");
+}
+
+#[exception(DataAbort)]
+fn data_abort_handler(_addr: usize) -> ! {
+    panic!("unexpected data abort exception");
+}
@@ -0,0 +1,14 @@
+/*
+Memory configuration for the Arm Versatile Express with a CoreTile Express A9x4
+daughterboard, as emulated by QEMU's `vexpress-a9` machine.
+
+See https://github.com/qemu/qemu/blob/master/hw/arm/vexpress.c
+*/
+
+MEMORY {
+    SDRAM : ORIGIN = 0x60000000, LENGTH = 64M
+}
+
+REGION_ALIAS("VECTORS", SDRAM);
+REGION_ALIAS("CODE", SDRAM);
+REGION_ALIAS("DATA", SDRAM);
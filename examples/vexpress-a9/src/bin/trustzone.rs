@@ -0,0 +1,104 @@
+//! TrustZone example
+//!
+//! With the `el3-boot` feature, the start-up code runs in the Secure world,
+//! points MVBAR at the Monitor vector table, and then calls `kmain` in the
+//! Non-secure world. Here the Non-secure world makes Secure Monitor Calls,
+//! which `_smc_handler` services in Monitor mode. Some of those calls switch to
+//! the Secure copies of the banked CP15 registers, to show that each world has
+//! its own.
+
+#![no_std]
+#![no_main]
+
+use core::ptr::addr_of;
+
+use cortex_a_rt::ExceptionFrame;
+use cortex_ar::{
+    addr::VirtAddr,
+    register::{Scr, Tpidrurw},
+    smccc::{self, Service},
+};
+use semihosting::println;
+
+// pull in our start-up code
+use vexpress_a9::rt::entry;
+
+/// Returns SCR.NS, as the monitor sees it
+const GET_SCR_NS: u32 = smccc::function_id(true, Service::Oem, 0);
+/// Writes the argument to the Secure copy of TPIDRURW
+const SET_SECURE_TPIDRURW: u32 = smccc::function_id(true, Service::Oem, 1);
+/// Returns the Secure copy of TPIDRURW
+const GET_SECURE_TPIDRURW: u32 = smccc::function_id(true, Service::Oem, 2);
+/// A function our monitor doesn't implement
+const UNKNOWN: u32 = smccc::function_id(true, Service::Oem, 0xFF);
+
+/// The NS bit in SCR
+const SCR_NS: u32 = 1 << 0;
+
+extern "C" {
+    static _vector_table: u32;
+}
+
+/// The entry-point to the Rust application.
+///
+/// It is called by the start-up, in the Non-secure world.
+#[entry]
+fn main() -> ! {
+    // The Non-secure world has its own VBAR, which is still zero
+    let vector_table = VirtAddr::from_ptr(addr_of!(_vector_table));
+    // Safety: Our vector table is the right one to use, and it never moves
+    unsafe { cortex_ar::vectors::set_vector_table(vector_table) };
+
+    // Safety: Nothing else is using TPIDRURW
+    unsafe { Tpidrurw::write(Tpidrurw(0x1111_1111)) };
+    println!("Non-secure TPIDRURW = {:#010x}", Tpidrurw::read().0);
+
+    let [ns, ..] = smccc::smccc_call(GET_SCR_NS, [0; 6]);
+    println!("Monitor saw SCR.NS = {}", ns);
+
+    smccc::smccc_call(SET_SECURE_TPIDRURW, [0x2222_2222, 0, 0, 0, 0, 0]);
+    let [secure, ..] = smccc::smccc_call(GET_SECURE_TPIDRURW, [0; 6]);
+    println!("Secure TPIDRURW = {:#010x}", secure);
+    println!("Non-secure TPIDRURW = {:#010x}", Tpidrurw::read().0);
+
+    let [result, ..] = smccc::smccc_call(UNKNOWN, [0; 6]);
+    println!("Unknown SMC returned {}", result as i32);
+
+    semihosting::process::exit(0);
+}
+
+/// Our Secure Monitor Call handler, which runs in Monitor mode
+///
+/// We don't print from here - the Secure world doesn't share the Non-secure
+/// world's view of memory, so we keep to registers and our own stack.
+#[no_mangle]
+extern "C" fn _smc_handler(function_id: u32, frame: &mut ExceptionFrame) {
+    let arg = frame.r1;
+    frame.r0 = match function_id {
+        GET_SCR_NS => Scr::read().0 & SCR_NS,
+        SET_SECURE_TPIDRURW => {
+            // Safety: Nothing in the Secure world is using TPIDRURW
+            with_secure(|| unsafe { Tpidrurw::write(Tpidrurw(arg)) });
+            0
+        }
+        GET_SECURE_TPIDRURW => with_secure(|| Tpidrurw::read().0),
+        _ => smccc::NOT_SUPPORTED as u32,
+    };
+}
+
+/// Run `f` with the Secure copies of the banked CP15 registers selected
+///
+/// Monitor mode is always Secure, but SCR.NS is still set from the
+/// Non-secure world, and it picks which copy of a banked register we see.
+fn with_secure<R>(f: impl FnOnce() -> R) -> R {
+    let scr = Scr::read();
+    // Safety: Interrupts are masked in Monitor mode, and we put SCR back
+    // before we return to the Non-secure world
+    unsafe { Scr::write(Scr(scr.0 & !SCR_NS)) };
+    cortex_ar::asm::isb();
+    let result = f();
+    // Safety: As above
+    unsafe { Scr::write(scr) };
+    cortex_ar::asm::isb();
+    result
+}
@@ -0,0 +1,20 @@
+//! Common code for all examples
+
+#![no_std]
+
+// Need this to bring in the start-up function
+pub use cortex_a_rt as rt;
+
+#[cfg(not(arm_architecture = "v7-a"))]
+compile_error!("This example/board is only compatible with the Armv7-A architecture");
+
+/// Called when the application raises an unrecoverable `panic!`.
+///
+/// Prints the panic to the console and then exits QEMU using a semihosting
+/// breakpoint.
+#[panic_handler]
+#[cfg(target_os = "none")]
+fn panic(info: &core::panic::PanicInfo) -> ! {
+    semihosting::println!("PANIC: {:#?}", info);
+    semihosting::process::abort();
+}
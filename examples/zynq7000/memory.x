@@ -0,0 +1,16 @@
+/*
+Memory configuration for the AMD Zynq-7000 SoC, on a board with 512 MiB of
+DDR (like the ZedBoard or the Zybo Z7-20).
+
+The first 1 MiB of DDR is hidden behind the On-Chip Memory after reset, so like
+the AMD tools, we start at 1 MiB. The image is loaded into DDR by the FSBL (or
+over JTAG), so .data is copied from the same region it runs in.
+*/
+
+MEMORY {
+    DDR : ORIGIN = 0x00100000, LENGTH = 511M
+}
+
+REGION_ALIAS("VECTORS", DDR);
+REGION_ALIAS("CODE", DDR);
+REGION_ALIAS("DATA", DDR);
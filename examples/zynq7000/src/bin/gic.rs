@@ -0,0 +1,113 @@
+//! Private Timer interrupt example for the Zynq-7000
+//!
+//! Routes the Cortex-A9 Private Timer interrupt through the GIC (a GIC-390,
+//! which is a GICv2), and handles it with an `#[interrupt]` function called
+//! from our `#[irq]` handler.
+
+#![no_std]
+#![no_main]
+
+use core::sync::atomic::{AtomicU32, Ordering};
+
+use cortex_ar::gic::{gic_addresses, GicAddresses, GicV2};
+use zynq7000::{
+    println,
+    rt::{entry, interrupt, irq},
+};
+
+/// The Private Timer registers
+const PRIVATE_TIMER: *mut u32 = (zynq7000::PERIPHBASE + 0x600) as *mut u32;
+
+/// Private Timer Load Register
+const TIMER_LOAD: usize = 0x00 / 4;
+/// Private Timer Control Register
+const TIMER_CONTROL: usize = 0x08 / 4;
+/// Private Timer Interrupt Status Register
+const TIMER_ISR: usize = 0x0C / 4;
+
+const CONTROL_ENABLE: u32 = 1 << 0;
+const CONTROL_AUTO_RELOAD: u32 = 1 << 1;
+const CONTROL_IRQ_ENABLE: u32 = 1 << 2;
+
+/// The Private Timer is wired to PPI 13 (INTID 29)
+const TIMER_INTID: u32 = 29;
+
+/// The Private Timer runs at half the CPU clock, which is usually 666 MHz
+const TIMER_HZ: u32 = 333_333_333;
+
+/// How many times should the timer fire?
+const NUM_TICKS: u32 = 5;
+
+static TICKS: AtomicU32 = AtomicU32::new(0);
+
+/// Get a driver for this core's view of the GIC
+fn gic() -> GicV2 {
+    // Find the GIC by reading MIDR and CBAR
+    match gic_addresses() {
+        // Safety: These are the addresses of the GIC built into our processor
+        Some(GicAddresses::V2 { gicd, gicc }) => unsafe { GicV2::new(gicd, gicc) },
+        other => panic!("Expected a GICv2, found {:?}", other),
+    }
+}
+
+/// The entry-point to the Rust application.
+///
+/// It is called by the start-up code in `cortex-a-rt`.
+#[entry]
+fn main() -> ! {
+    zynq7000::init();
+
+    let mut gic = gic();
+    gic.init_distributor();
+    gic.init_cpu_interface();
+
+    println!("Configure timer interrupt {}...", TIMER_INTID);
+    gic.set_priority(TIMER_INTID, 0x10);
+    gic.enable_interrupt(TIMER_INTID, true);
+
+    println!("Starting timer...");
+    // Safety: Nothing else is using the Private Timer
+    unsafe {
+        PRIVATE_TIMER.add(TIMER_LOAD).write_volatile(TIMER_HZ / 10);
+        PRIVATE_TIMER
+            .add(TIMER_CONTROL)
+            .write_volatile(CONTROL_ENABLE | CONTROL_AUTO_RELOAD | CONTROL_IRQ_ENABLE);
+    }
+
+    unsafe {
+        cortex_ar::interrupt::enable();
+    }
+
+    while TICKS.load(Ordering::Relaxed) < NUM_TICKS {
+        cortex_ar::asm::wfi();
+    }
+
+    cortex_ar::interrupt::disable();
+    // Safety: Nothing else is using the Private Timer
+    unsafe {
+        PRIVATE_TIMER.add(TIMER_CONTROL).write_volatile(0);
+    }
+
+    println!("Timer interrupt test completed OK");
+
+    loop {
+        cortex_ar::asm::wfe();
+    }
+}
+
+/// Called by `cortex-a-rt` when an IRQ occurs
+#[irq]
+fn irq_handler() {
+    zynq7000::rt::dispatch_interrupts(&mut gic());
+}
+
+/// Called by `dispatch_interrupts` when the Private Timer fires
+#[interrupt(PPI = 13)]
+fn private_timer() {
+    // Safety: Writing 1 to the Event Flag clears it
+    unsafe {
+        PRIVATE_TIMER.add(TIMER_ISR).write_volatile(1);
+    }
+    let ticks = TICKS.fetch_add(1, Ordering::Relaxed) + 1;
+    println!("Timer fired {} times", ticks);
+}
@@ -0,0 +1,19 @@
+//! Hello-world over the UART.
+
+#![no_std]
+#![no_main]
+
+// pull in our start-up code
+use zynq7000::{println, rt::entry};
+
+/// The entry-point to the Rust application.
+///
+/// It is called by the start-up code in `cortex-a-rt`.
+#[entry]
+fn main() -> ! {
+    zynq7000::init();
+    let x = 1.0f64;
+    let y = x * 2.0;
+    println!("Hello, this is a Zynq-7000! x = {:0.3}, y = {:0.3}", x, y);
+    panic!("I am an example panic");
+}
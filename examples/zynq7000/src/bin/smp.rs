@@ -0,0 +1,215 @@
+//! Multi-core hello-world for the Zynq-7000
+//!
+//! Wakes up core 1 and runs code on both cores, checking that atomic
+//! fetch_add and the critical-section work across them.
+//!
+//! After reset, core 1 sits in the BootROM, waiting for an event. When it gets
+//! one it reads 0xFFFF_FFF0 (in the On-Chip Memory), and if that isn't zero,
+//! jumps there. So we put the address of our core 1 start-up code there, and
+//! then send an event.
+//!
+//! Run it in QEMU, on two cores, with:
+//!
+//! ```console
+//! $ cargo run --bin smp --target armv7a-none-eabihf --features qemu --config \
+//!     "target.armv7a-none-eabihf.runner='qemu-system-arm -machine xilinx-zynq-a9 -smp 2 -m 512M -semihosting -nographic -serial null -serial mon:stdio -kernel'"
+//! ```
+
+#![no_std]
+#![no_main]
+
+use core::cell::UnsafeCell;
+use core::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+
+use cortex_ar::{addr::VirtAddr, sync::Mutex};
+
+// pull in our start-up code
+use zynq7000::{halt, println, rt::entry};
+
+#[repr(align(16))]
+struct Stack<const LEN_BYTES: usize> {
+    contents: UnsafeCell<[u8; LEN_BYTES]>,
+}
+
+impl<const LEN_BYTES: usize> Stack<LEN_BYTES> {
+    const fn new() -> Self {
+        Self {
+            contents: UnsafeCell::new([0u8; LEN_BYTES]),
+        }
+    }
+
+    fn stack_top(&self) -> usize {
+        let stack_start = self.contents.get() as usize;
+        stack_start + LEN_BYTES
+    }
+}
+
+unsafe impl<const LEN_BYTES: usize> Sync for Stack<LEN_BYTES> {}
+
+static CORE1_STACK: Stack<65536> = Stack::new();
+
+static CORE1_BOOTED: AtomicBool = AtomicBool::new(false);
+
+static SHARED_VARIABLE: AtomicU32 = AtomicU32::new(0);
+
+static SHARED_VARIABLE_2: Mutex<u32> = Mutex::new(0);
+
+/// Where the BootROM on core 1 looks for its start address
+const CORE1_START_ADDRESS: *mut u32 = 0xFFFF_FFF0 as *mut u32;
+
+/// How long core 0 waits for core 1
+const CORE0_WILL_WAIT: usize = 10_000_000;
+
+/// How many CAS loops to run?
+const CAS_LOOPS: u32 = 1000;
+
+/// How many CS Mutex loops to run?
+const CS_MUTEX_LOOPS: u32 = 1000;
+
+/// The entry-point to the Rust application.
+///
+/// It is called by the start-up code in `cortex-a-rt`.
+#[entry]
+fn main() -> ! {
+    zynq7000::init();
+
+    extern "C" {
+        static mut _core1_stack_pointer: usize;
+        fn _core1_start() -> !;
+    }
+    let p = &raw mut _core1_stack_pointer;
+    unsafe {
+        p.write(CORE1_STACK.stack_top());
+    }
+    // Core 1 reads this with its caches off, so it must be in DDR, not just
+    // in our cache
+    cortex_ar::cache::clean_dcache_by_range(VirtAddr::from_ptr(p), core::mem::size_of::<usize>());
+
+    println!("Starting core 1...");
+    unsafe {
+        CORE1_START_ADDRESS.write_volatile(_core1_start as usize as u32);
+    }
+    // Make sure the write has landed before we wake core 1
+    cortex_ar::asm::dsb();
+    cortex_ar::asm::sev();
+
+    // wait some time for core 1 to start
+    for counter in 0..=CORE0_WILL_WAIT {
+        if CORE1_BOOTED.load(Ordering::SeqCst) {
+            break;
+        }
+        if counter == CORE0_WILL_WAIT {
+            println!("CPU 1 is missing?!");
+            halt();
+        }
+    }
+
+    for _ in 0..CAS_LOOPS {
+        SHARED_VARIABLE.fetch_add(1, Ordering::Relaxed);
+    }
+
+    for _ in 0..CS_MUTEX_LOOPS {
+        SHARED_VARIABLE_2.lock(|value| *value += 1);
+    }
+
+    // let the other core finish
+    for _ in 0..CORE0_WILL_WAIT {
+        cortex_ar::asm::nop();
+    }
+
+    let total_a = SHARED_VARIABLE.load(Ordering::Relaxed);
+    if total_a == CAS_LOOPS * 2 {
+        println!("CAS test passed");
+    } else {
+        println!("CAS test failed, got {} not 2000", total_a);
+    }
+
+    let total_b = SHARED_VARIABLE_2.lock(|value| *value);
+
+    if total_b == CS_MUTEX_LOOPS * 2 {
+        println!("CS Mutex test passed");
+    } else {
+        println!("CS Mutex test failed, got {} not 2000", total_b);
+    }
+
+    halt();
+}
+
+/// The entry-point to the Rust application.
+///
+/// It is called by the start-up code below, on Core 1.
+#[entry(core = 1)]
+fn main_core1() {
+    // Core 0 has already built the translation table, and our exclusive
+    // loads and stores won't work until the MMU is on
+    zynq7000::mmu::enable();
+
+    CORE1_BOOTED.store(true, Ordering::SeqCst);
+
+    for _ in 0..CAS_LOOPS {
+        SHARED_VARIABLE.fetch_add(1, Ordering::Relaxed);
+    }
+
+    for _ in 0..CS_MUTEX_LOOPS {
+        SHARED_VARIABLE_2.lock(|value| *value += 1);
+    }
+}
+
+// Start-up code for core 1 on the Zynq-7000.
+//
+// The BootROM leaves us in Secure SVC mode, with the MMU and caches off. We
+// use our vector table, set up our stacks using the stack pointer that core 0
+// left for us, turn on the FPU and run `kmain2`.
+core::arch::global_asm!(
+    r#"
+    // Work around https://github.com/rust-lang/rust/issues/127269
+    .fpu vfp3-d16
+
+    .section .bss
+    .align 4
+    .global _core1_stack_pointer
+    _core1_stack_pointer:
+        .word 0
+
+    .section .text._core1_start
+    .align 4
+
+    .global _core1_start
+    .type _core1_start, %function
+    _core1_start:
+        // Set the VBAR to _vector_table
+        ldr     r0, =_vector_table
+        mcr     p15, 0, r0, c12, c0, 0
+        ldr     r0, =_core1_stack_pointer
+        ldr     r0, [r0]
+        // set up our stacks using that stack pointer
+        bl      _stack_setup
+        // Allow VFP coprocessor access
+        mrc     p15, 0, r0, c1, c0, 2
+        orr     r0, r0, #0xF00000
+        mcr     p15, 0, r0, c1, c0, 2
+        isb
+        // Enable VFP
+        mov     r0, #0x40000000
+        vmsr    fpexc, r0
+        // Zero all registers before calling kmain
+        mov     r0, 0
+        mov     r1, 0
+        mov     r2, 0
+        mov     r3, 0
+        mov     r4, 0
+        mov     r5, 0
+        mov     r6, 0
+        mov     r7, 0
+        mov     r8, 0
+        mov     r9, 0
+        mov     r10, 0
+        mov     r11, 0
+        mov     r12, 0
+        // call our kmain2 for core 1
+        bl      kmain2
+        // In case the application returns, loop forever
+        b       .
+    .size _core1_start, . - _core1_start
+    "#,
+);
@@ -0,0 +1,139 @@
+//! Common code for all examples
+//!
+//! These examples are written for real hardware, so instead of semihosting we
+//! print to UART1, which most Zynq-7000 boards wire up to a USB-UART bridge.
+//!
+//! With the `qemu` feature they also run in QEMU's `xilinx-zynq-a9` machine,
+//! which has the same UARTs. Then [`halt`] exits QEMU, and core 1 is parked
+//! the way the BootROM would park it.
+
+#![no_std]
+
+// Need this to bring in the start-up function
+pub use cortex_a_rt as rt;
+
+#[cfg(not(arm_architecture = "v7-a"))]
+compile_error!("This example/board is only compatible with the Armv7-A architecture");
+
+pub mod mmu;
+pub mod uart;
+
+/// The base address of the Cortex-A9 MPCore private peripherals (the SCU, the
+/// GIC and the private timers)
+///
+/// You can also read this from CBAR, with `cortex_ar::gic::periphbase()`.
+pub const PERIPHBASE: usize = 0xF8F0_0000;
+
+/// The Snoop Control Unit's Control Register
+const SCU_CONTROL: *mut u32 = PERIPHBASE as *mut u32;
+
+/// Print to the console, with a newline
+#[macro_export]
+macro_rules! println {
+    ($($arg:tt)*) => {
+        $crate::uart::CONSOLE.lock(|uart| {
+            use core::fmt::Write as _;
+            let _ = writeln!(uart, $($arg)*);
+        })
+    };
+}
+
+/// Set up the hardware on core 0
+///
+/// Points VBAR at our vector table, turns on the Snoop Control Unit, builds
+/// the translation table, enables the MMU and caches, and sets up the console
+/// UART. Call this before anything else, because the critical-section (and so
+/// [`println!`]) uses exclusive loads and stores, which only work once the MMU
+/// is on.
+pub fn init() {
+    set_vbar();
+    // Safety: This is the SCU Control Register, and setting the Enable bit
+    // keeps the L1 data caches of the two cores coherent
+    unsafe {
+        SCU_CONTROL.write_volatile(SCU_CONTROL.read_volatile() | 1);
+    }
+    mmu::init_table();
+    mmu::enable();
+    uart::CONSOLE.lock(|uart| uart.init());
+}
+
+/// Use our vector table, instead of whatever the FSBL left in VBAR
+///
+/// We aren't linked at address zero, so without this our exception handlers
+/// would never be called.
+fn set_vbar() {
+    extern "C" {
+        static _vector_table: u32;
+    }
+    let vector_table = cortex_ar::addr::VirtAddr::from_ptr(core::ptr::addr_of!(_vector_table));
+    // Safety: Our vector table is the right one to use, and it never moves
+    unsafe {
+        cortex_ar::vectors::set_vector_table(vector_table);
+    }
+}
+
+/// Stop here, for a debugger to have a look
+///
+/// With the `qemu` feature, this exits QEMU instead.
+pub fn halt() -> ! {
+    uart::CONSOLE.lock(|uart| uart.flush());
+    #[cfg(feature = "qemu")]
+    semihosting::process::exit(0);
+    #[cfg(not(feature = "qemu"))]
+    loop {
+        cortex_ar::asm::wfe();
+    }
+}
+
+/// Called when the application raises an unrecoverable `panic!`.
+///
+/// Prints the panic to the console and then waits for a debugger (or, with the
+/// `qemu` feature, exits QEMU).
+#[panic_handler]
+#[cfg(target_os = "none")]
+fn panic(info: &core::panic::PanicInfo) -> ! {
+    use core::fmt::Write as _;
+
+    // We might have panicked whilst holding the console lock, so don't use it
+    //
+    // Safety: We're not coming back, so there's nobody to fight with over the
+    // UART
+    let mut uart = unsafe { uart::Uart::new(uart::UART1_BASE) };
+    let _ = writeln!(uart, "PANIC: {:#?}", info);
+    uart.flush();
+    #[cfg(feature = "qemu")]
+    semihosting::process::abort();
+    #[cfg(not(feature = "qemu"))]
+    loop {
+        cortex_ar::asm::wfe();
+    }
+}
+
+// On a real board, core 1 waits in the BootROM until it is given a start
+// address. QEMU starts every core at our entry point instead, so we do what
+// the BootROM would do: core 1 waits for an event, and jumps to the address at
+// 0xFFFF_FFF0 once it isn't zero. Core 0 carries on into the usual start-up
+// code.
+#[cfg(feature = "qemu")]
+core::arch::global_asm!(
+    r#"
+    .section .text.startup
+    .align 4
+
+    .global _start
+    .type _start, %function
+    _start:
+        // Read MPIDR.Aff0 into R0
+        mrc     p15, 0, r0, c0, c0, 5
+        ands    r0, r0, 0xFF
+        beq     _default_start
+        ldr     r0, =0xFFFFFFF0
+    1:
+        wfe
+        ldr     r1, [r0]
+        cmp     r1, #0
+        beq     1b
+        bx      r1
+    .size _start, . - _start
+    "#,
+);
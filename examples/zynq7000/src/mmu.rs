@@ -0,0 +1,121 @@
+//! Translation table and cache set-up for the Zynq-7000
+//!
+//! We identity map the address space with sections (and supersections):
+//!
+//! * DDR as Normal, write-back cacheable, shareable memory
+//! * the Programmable Logic, I/O peripherals and MPCore private peripherals as
+//!   Device memory
+//! * the OCM at the top of the address space as Strongly-ordered memory,
+//!   because the BootROM on core 1 watches it for a start address
+//!
+//! Everything else is left unmapped, and so gives a translation fault. That
+//! includes the first 1 MB section, so dereferencing a null pointer (or a small
+//! offset from one) faults instead of quietly reading whatever is there. We
+//! don't link anything there anyway - see `memory.x`.
+//!
+//! Both cores share the one table. Core 0 builds it with [`init_table`], then
+//! each core calls [`enable`].
+
+use core::ops::Range;
+
+use cortex_ar::{
+    addr::{PhysAddr, VirtAddr},
+    mmu::{AccessPermissions, L1Table, MemoryRegionAttributes, SectionAttributes, SECTION_SIZE},
+    register::Actlr,
+};
+
+/// DDR is the bottom 1 GB, less the first section, which we leave unmapped to
+/// catch null pointers
+const DDR: Range<usize> = 0x0010_0000..0x4000_0000;
+
+/// The two AXI GP ports into the Programmable Logic
+const PL: Range<usize> = 0x4000_0000..0xC000_0000;
+
+/// The I/O peripherals, SMC, SLCR, PS system registers, MPCore private
+/// peripherals and the Quad-SPI linear address space
+const PERIPHERALS: Range<usize> = 0xE000_0000..0xFFF0_0000;
+
+/// The top 1 MB, where the OCM is mapped (in its default configuration)
+const OCM_HIGH: usize = 0xFFF0_0000;
+
+/// ACTLR.SMP - take part in coherency with the other core
+const ACTLR_SMP: u32 = 1 << 6;
+
+/// ACTLR.FW - broadcast cache and TLB maintenance operations
+const ACTLR_FW: u32 = 1 << 0;
+
+/// Normal memory, write-back cacheable, and shared between the cores
+const NORMAL: SectionAttributes = SectionAttributes {
+    non_global: false,
+    p_bit: false,
+    shareable: true,
+    access: AccessPermissions::FullAccess,
+    memory_attrs: MemoryRegionAttributes::OuterAndInnerWriteBackWriteAlloc.as_raw(),
+    domain: 0,
+    execute_never: false,
+};
+
+/// Device memory, which you can't execute from
+const DEVICE: SectionAttributes = SectionAttributes {
+    non_global: false,
+    p_bit: false,
+    shareable: true,
+    access: AccessPermissions::FullAccess,
+    memory_attrs: MemoryRegionAttributes::ShareableDevice.as_raw(),
+    domain: 0,
+    execute_never: true,
+};
+
+/// Strongly-ordered memory, which you can't execute from
+const STRONGLY_ORDERED: SectionAttributes = SectionAttributes {
+    non_global: false,
+    p_bit: false,
+    shareable: true,
+    access: AccessPermissions::FullAccess,
+    memory_attrs: MemoryRegionAttributes::StronglyOrdered.as_raw(),
+    domain: 0,
+    execute_never: true,
+};
+
+static mut L1_TABLE: L1Table = L1Table::new();
+
+/// Fill in the translation table
+///
+/// Call this once, on core 0, with the MMU off. The table walks don't look in
+/// the caches, so it must be written straight to DDR.
+pub fn init_table() {
+    // Safety: The MMU is off, so nothing else is looking at the table
+    let table = unsafe { &mut *core::ptr::addr_of_mut!(L1_TABLE) };
+    let mut map = |range: Range<usize>, attrs: SectionAttributes| {
+        table
+            .map_range(
+                VirtAddr::new(range.start),
+                PhysAddr::new(range.start),
+                range.end - range.start,
+                attrs,
+            )
+            .unwrap();
+    };
+    map(DDR, NORMAL);
+    map(PL, DEVICE);
+    map(PERIPHERALS, DEVICE);
+    map(OCM_HIGH..OCM_HIGH + SECTION_SIZE, STRONGLY_ORDERED);
+}
+
+/// Turn on the MMU and the L1 caches on this core
+///
+/// The table must have been filled in with [`init_table`].
+pub fn enable() {
+    // Join in with the other core. This must be done before the data cache is
+    // on.
+    //
+    // Safety: Nothing is cached yet, so we can't lose coherency
+    unsafe {
+        Actlr::write(Actlr(Actlr::read().0 | ACTLR_SMP | ACTLR_FW));
+    }
+    // Safety: The MMU and data cache are off, and the table identity maps all
+    // the code and data we use, so nothing moves when the MMU comes on
+    unsafe {
+        cortex_ar::mmu::enable(&*core::ptr::addr_of!(L1_TABLE));
+    }
+}
@@ -0,0 +1,116 @@
+//! A driver for the Cadence UART in the Zynq-7000
+//!
+//! It only transmits, and it polls, which is all we need for a console.
+
+use cortex_ar::sync::Mutex;
+
+/// The base address of UART0
+pub const UART0_BASE: *mut u32 = 0xE000_0000 as *mut u32;
+
+/// The base address of UART1
+pub const UART1_BASE: *mut u32 = 0xE000_1000 as *mut u32;
+
+/// The UART we print to
+///
+/// Call [`Uart::init`] on it before you use it.
+pub static CONSOLE: Mutex<Uart> = Mutex::new(unsafe { Uart::new(UART1_BASE) });
+
+/// Control Register
+const CR: usize = 0x00 / 4;
+/// Mode Register
+const MR: usize = 0x04 / 4;
+/// Baud Rate Generator Register
+const BAUDGEN: usize = 0x18 / 4;
+/// Channel Status Register
+const SR: usize = 0x2C / 4;
+/// Transmit and Receive FIFO
+const FIFO: usize = 0x30 / 4;
+/// Baud Rate Divider Register
+const BAUDDIV: usize = 0x34 / 4;
+
+const CR_RXRST: u32 = 1 << 0;
+const CR_TXRST: u32 = 1 << 1;
+const CR_RXEN: u32 = 1 << 2;
+const CR_RXDIS: u32 = 1 << 3;
+const CR_TXEN: u32 = 1 << 4;
+const CR_TXDIS: u32 = 1 << 5;
+
+/// 8 data bits, no parity, 1 stop bit
+const MR_8N1: u32 = 0b100 << 3;
+
+const SR_TXEMPTY: u32 = 1 << 3;
+const SR_TXFULL: u32 = 1 << 4;
+
+/// Gives 115200 baud from the usual 100 MHz UART reference clock, as
+/// `100 MHz / (124 * (6 + 1))`
+const BAUDGEN_115200: u32 = 124;
+const BAUDDIV_115200: u32 = 6;
+
+/// A Cadence UART
+pub struct Uart {
+    regs: *mut u32,
+}
+
+// Safety: It's just an MMIO address, and we only hand out `&mut Uart`
+unsafe impl Send for Uart {}
+
+impl Uart {
+    /// Create a driver for the UART at the given address
+    ///
+    /// # Safety
+    ///
+    /// `base` must be the base address of a Cadence UART, mapped as Device
+    /// memory, and nothing else may be using it.
+    pub const unsafe fn new(base: *mut u32) -> Uart {
+        Uart { regs: base }
+    }
+
+    /// Reset the UART and set it up for 115200 baud, 8N1
+    ///
+    /// Assumes the FSBL (or `ps7_init`) has set the UART reference clock to
+    /// 100 MHz and routed the pins.
+    pub fn init(&mut self) {
+        self.write(CR, CR_TXDIS | CR_RXDIS);
+        self.write(CR, CR_TXRST | CR_RXRST);
+        // The reset bits clear themselves when the reset is done
+        while self.read(CR) & (CR_TXRST | CR_RXRST) != 0 {}
+        self.write(MR, MR_8N1);
+        self.write(BAUDGEN, BAUDGEN_115200);
+        self.write(BAUDDIV, BAUDDIV_115200);
+        self.write(CR, CR_TXEN | CR_RXEN);
+    }
+
+    /// Send a byte, waiting for space in the FIFO
+    pub fn write_byte(&mut self, byte: u8) {
+        while self.read(SR) & SR_TXFULL != 0 {}
+        self.write(FIFO, u32::from(byte));
+    }
+
+    /// Wait until everything has been sent
+    pub fn flush(&mut self) {
+        while self.read(SR) & SR_TXEMPTY == 0 {}
+    }
+
+    fn read(&self, reg: usize) -> u32 {
+        // Safety: We were given a valid UART in `new`
+        unsafe { self.regs.add(reg).read_volatile() }
+    }
+
+    fn write(&mut self, reg: usize, value: u32) {
+        // Safety: We were given a valid UART in `new`
+        unsafe { self.regs.add(reg).write_volatile(value) }
+    }
+}
+
+impl core::fmt::Write for Uart {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        for byte in s.bytes() {
+            // Serial terminals want a carriage return too
+            if byte == b'\n' {
+                self.write_byte(b'\r');
+            }
+            self.write_byte(byte);
+        }
+        Ok(())
+    }
+}
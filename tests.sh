@@ -6,6 +6,7 @@
 rustup target add armv7r-none-eabi
 rustup target add armv7r-none-eabihf
 rustup target add armv7a-none-eabi
+rustup target add armv7a-none-eabihf
 rustup toolchain add nightly
 rustup component add rust-src --toolchain=nightly
 
@@ -22,6 +23,10 @@ mkdir -p ./target
 
 versatile_ab_cargo="--manifest-path examples/versatileab/Cargo.toml"
 mps3_an536_cargo="--manifest-path examples/mps3-an536/Cargo.toml"
+zynq7000_cargo="--manifest-path examples/zynq7000/Cargo.toml"
+zynq7000_runner="qemu-system-arm -machine xilinx-zynq-a9 -smp 2 -m 512M -semihosting -nographic -serial null -serial mon:stdio -kernel"
+vexpress_a9_cargo="--manifest-path examples/vexpress-a9/Cargo.toml"
+vexpress_a9_runner="qemu-system-arm -machine vexpress-a9,secure=on -cpu cortex-a9 -semihosting -nographic -audio none -kernel"
 
 my_diff() {
     file_a=$1
@@ -57,6 +62,20 @@ for bin_path in $(ls examples/versatileab/src/bin/*.rs); do
     my_diff ./examples/versatileab/reference/$binary-armv7a-none-eabi.out ./target/$binary-armv7a-none-eabi.out || fail $binary "armv7a-none-eabi"
 done
 
+# armv7a-none-eabihf tests, on a dual-core Zynq-7000. These print to a UART,
+# which sends CRLF line endings.
+cargo run ${zynq7000_cargo} --target=armv7a-none-eabihf --bin smp --features=qemu --config "target.armv7a-none-eabihf.runner='${zynq7000_runner}'" | tr -d '\r' | tee ./target/smp-armv7a-none-eabihf_smp2.out
+my_diff ./examples/zynq7000/reference/smp-armv7a-none-eabihf_smp2.out ./target/smp-armv7a-none-eabihf_smp2.out || fail smp "armv7a-none-eabihf"
+
+# armv7a-none-eabi TrustZone tests, on a Versatile Express that boots in the
+# Secure world
+for bin_path in $(ls examples/vexpress-a9/src/bin/*.rs); do
+    filename=${bin_path##*/}
+    binary=${filename%.rs}
+    cargo run ${vexpress_a9_cargo} --target=armv7a-none-eabi --bin $binary --config "target.armv7a-none-eabi.runner='${vexpress_a9_runner}'" | tee ./target/$binary-armv7a-none-eabi.out
+    my_diff ./examples/vexpress-a9/reference/$binary-armv7a-none-eabi.out ./target/$binary-armv7a-none-eabi.out || fail $binary "armv7a-none-eabi"
+done
+
 # These tests only run on QEMU 9 or higher.
 # Ubuntu 24.04 supplies QEMU 8, which doesn't support the machine we have configured for this target
 if qemu-system-arm --version | grep "version 9"; then
@@ -64,6 +83,11 @@ if qemu-system-arm --version | grep "version 9"; then
     for bin_path in $(ls examples/mps3-an536/src/bin/*.rs); do
         filename=${bin_path##*/}
         binary=${filename%.rs}
+        # QEMU has no debugger on the other end of the DCC, so this one would
+        # wait forever
+        if [ "$binary" == "dcc" ]; then
+            continue
+        fi
         cargo +nightly run ${mps3_an536_cargo} --target=armv8r-none-eabihf --bin $binary --features=gic -Zbuild-std=core | tee ./target/$binary-armv8r-none-eabihf.out
         my_diff ./examples/mps3-an536/reference/$binary-armv8r-none-eabihf.out ./target/$binary-armv8r-none-eabihf.out || fail $binary "armv8r-none-eabihf"
     done